@@ -0,0 +1,570 @@
+//! Betweenness centrality.
+//!
+//! The betweenness of a node is the sum of the fractions of shortest paths
+//! between all pairs of other nodes that pass through the node. The edge
+//! betweenness is defined accordingly for edges.
+//!
+//! The implementation uses Brandes' algorithm [1], which runs a single-source
+//! shortest path computation from each source node and accumulates the
+//! dependencies of all nodes in reverse order. Sources are processed in
+//! parallel. Unweighted graphs use breadth-first search, graphs with weighted
+//! edge values, see [`EdgeWeight`], use Dijkstra's algorithm and the weights
+//! must be positive.
+//!
+//! Instead of all nodes, a uniform sample of source nodes can be used to
+//! approximate the scores [2]. The approximated scores are scaled by
+//! `node_count / sampling_size`.
+//!
+//! [1] Ulrik Brandes:
+//! "A Faster Algorithm for Betweenness Centrality",
+//! Journal of Mathematical Sociology, 2001
+//!
+//! [2] Ulrik Brandes, Christian Pich:
+//! "Centrality Estimation in Large Networks",
+//! International Journal of Bifurcation and Chaos, 2007
+use crate::prelude::*;
+
+use atomic_float::AtomicF64;
+use log::info;
+use nanorand::{Rng, WyRand};
+use rayon::prelude::*;
+
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct BetweennessConfig {
+    /// The number of randomly selected source nodes. If not
+    /// set, all nodes are used and the scores are exact.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub sampling_size: Option<usize>,
+
+    /// The seed used to select the source nodes.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = BetweennessConfig::DEFAULT_SEED))]
+    pub seed: u64,
+
+    /// Divides the scores by the number of node pairs
+    /// excluding the scored node.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub normalize: bool,
+
+    /// Computes the betweenness of each edge in addition
+    /// to the betweenness of each node.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub edge_betweenness: bool,
+}
+
+impl Default for BetweennessConfig {
+    fn default() -> Self {
+        Self {
+            sampling_size: None,
+            seed: Self::DEFAULT_SEED,
+            normalize: false,
+            edge_betweenness: false,
+        }
+    }
+}
+
+impl BetweennessConfig {
+    pub const DEFAULT_SEED: u64 = 42;
+
+    pub fn new(sampling_size: Option<usize>, normalize: bool, edge_betweenness: bool) -> Self {
+        Self {
+            sampling_size,
+            normalize,
+            edge_betweenness,
+            ..Self::default()
+        }
+    }
+}
+
+/// Computes the betweenness of each node of a directed graph.
+///
+/// Returns the node scores and, if `config.edge_betweenness` is set, the
+/// edge scores, which are indexed by the outgoing edge ids, see
+/// [`DirectedNeighborsWithEdgeIds`].
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 3)])
+///     .build();
+///
+/// let (scores, _) = directed_betweenness(&graph, BetweennessConfig::default());
+///
+/// assert_eq!(scores, vec![0.0, 2.0, 2.0, 0.0]);
+/// ```
+pub fn directed_betweenness<NI, EV, G>(
+    graph: &G,
+    config: BetweennessConfig,
+) -> (Vec<f64>, Option<Vec<f64>>)
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + DirectedNeighborsWithEdgeIds<NI, EV> + Sync,
+{
+    let node_count = graph.node_count().index();
+    let edge_count = graph.edge_count().index();
+
+    let (mut scores, mut edge_scores) = brandes(
+        node_count,
+        edge_count,
+        |node| graph.out_neighbors_with_edge_ids(node),
+        config,
+    );
+
+    if config.normalize {
+        normalize(&mut scores, pair_count(node_count.saturating_sub(1)));
+        if let Some(edge_scores) = edge_scores.as_mut() {
+            normalize(edge_scores, pair_count(node_count));
+        }
+    }
+
+    (scores, edge_scores)
+}
+
+/// Computes the betweenness of each node of an undirected graph.
+///
+/// Returns the node scores and, if `config.edge_betweenness` is set, the
+/// edge scores, which are indexed by edge id, see
+/// [`UndirectedNeighborsWithEdgeIds`]. Both edge ids of an edge have the same
+/// score.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2), (0, 3)])
+///     .build();
+///
+/// let (scores, _) = undirected_betweenness(&graph, BetweennessConfig::default());
+///
+/// assert_eq!(scores, vec![3.0, 0.0, 0.0, 0.0]);
+/// ```
+pub fn undirected_betweenness<NI, EV, G>(
+    graph: &G,
+    config: BetweennessConfig,
+) -> (Vec<f64>, Option<Vec<f64>>)
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithEdgeIds<NI, EV> + UndirectedEdgeIdMappingOp<NI> + Sync,
+{
+    let node_count = graph.node_count().index();
+    let edge_count = graph.edge_count().index() * 2;
+
+    let (mut scores, edge_scores) = brandes(
+        node_count,
+        edge_count,
+        |node| graph.neighbors_with_edge_ids(node),
+        config,
+    );
+
+    // Each pair of nodes has been considered in both directions.
+    scores.par_iter_mut().for_each(|score| *score /= 2.0);
+
+    let mut edge_scores = edge_scores.map(|edge_scores| {
+        let reverse = graph.reverse_edge_ids();
+        (0..edge_count)
+            .into_par_iter()
+            .map(|edge| (edge_scores[edge] + edge_scores[reverse[edge].index()]) / 2.0)
+            .collect::<Vec<_>>()
+    });
+
+    if config.normalize {
+        normalize(&mut scores, pair_count(node_count.saturating_sub(1)) / 2.0);
+        if let Some(edge_scores) = edge_scores.as_mut() {
+            normalize(edge_scores, pair_count(node_count) / 2.0);
+        }
+    }
+
+    (scores, edge_scores)
+}
+
+/// Returns the number of ordered pairs of distinct nodes.
+fn pair_count(node_count: usize) -> f64 {
+    node_count as f64 * node_count.saturating_sub(1) as f64
+}
+
+fn normalize(scores: &mut [f64], pairs: f64) {
+    if pairs > 0.0 {
+        scores.par_iter_mut().for_each(|score| *score /= pairs);
+    }
+}
+
+fn brandes<'g, NI, EV, E, I>(
+    node_count: usize,
+    edge_count: usize,
+    edges: E,
+    config: BetweennessConfig,
+) -> (Vec<f64>, Option<Vec<f64>>)
+where
+    NI: Idx,
+    EV: EdgeWeight + 'g,
+    E: Fn(NI) -> I + Sync,
+    I: Iterator<Item = (NI, &'g Target<NI, EV>)>,
+{
+    let start = Instant::now();
+
+    let sources = sources(node_count, config);
+    let edge_count = if config.edge_betweenness {
+        edge_count
+    } else {
+        0
+    };
+
+    let scores = (0..node_count)
+        .map(|_| AtomicF64::new(0.0))
+        .collect::<Vec<_>>();
+    let edge_scores = (0..edge_count)
+        .map(|_| AtomicF64::new(0.0))
+        .collect::<Vec<_>>();
+
+    let next_source = AtomicUsize::new(0);
+
+    rayon::scope(|s| {
+        for _ in 0..rayon::current_num_threads() {
+            s.spawn(|_| {
+                let mut state = State::new(node_count, edge_count);
+
+                loop {
+                    let i = next_source.fetch_add(1, Ordering::AcqRel);
+                    if i >= sources.len() {
+                        break;
+                    }
+
+                    if EV::WEIGHTED {
+                        state.dijkstra(sources[i], &edges);
+                    } else {
+                        state.bfs(sources[i], &edges);
+                    }
+                    state.accumulate(sources[i], &edges);
+                }
+
+                for (score, local) in scores.iter().zip(state.scores) {
+                    score.fetch_add(local, Ordering::AcqRel);
+                }
+                for (score, local) in edge_scores.iter().zip(state.edge_scores) {
+                    score.fetch_add(local, Ordering::AcqRel);
+                }
+            });
+        }
+    });
+
+    let scale = node_count as f64 / sources.len().max(1) as f64;
+    let finish = |scores: Vec<AtomicF64>| {
+        scores
+            .into_par_iter()
+            .map(|score| score.into_inner() * scale)
+            .collect::<Vec<_>>()
+    };
+
+    info!(
+        "Computed betweenness from {} sources in {:?}",
+        sources.len(),
+        start.elapsed()
+    );
+
+    let edge_scores = config.edge_betweenness.then(|| finish(edge_scores));
+    (finish(scores), edge_scores)
+}
+
+/// Returns all nodes or a uniform sample of nodes if configured.
+fn sources<NI: Idx>(node_count: usize, config: BetweennessConfig) -> Vec<NI> {
+    let mut sources = (0..node_count).map(NI::new).collect::<Vec<_>>();
+
+    if let Some(sampling_size) = config.sampling_size.filter(|k| *k < node_count) {
+        // partial Fisher-Yates shuffle
+        let mut rng = WyRand::new_seed(config.seed);
+        for i in 0..sampling_size {
+            let j = rng.generate_range(i..node_count);
+            sources.swap(i, j);
+        }
+        sources.truncate(sampling_size);
+    }
+
+    sources
+}
+
+/// The thread-local state of Brandes' algorithm.
+struct State {
+    distances: Vec<f64>,
+    sigma: Vec<f64>,
+    delta: Vec<f64>,
+    order: Vec<usize>,
+    queue: VecDeque<usize>,
+    heap: BinaryHeap<HeapEntry>,
+    scores: Vec<f64>,
+    edge_scores: Vec<f64>,
+}
+
+impl State {
+    fn new(node_count: usize, edge_count: usize) -> Self {
+        Self {
+            distances: vec![f64::INFINITY; node_count],
+            sigma: vec![0.0; node_count],
+            delta: vec![0.0; node_count],
+            order: Vec::new(),
+            queue: VecDeque::new(),
+            heap: BinaryHeap::new(),
+            scores: vec![0.0; node_count],
+            edge_scores: vec![0.0; edge_count],
+        }
+    }
+
+    /// Computes the number of shortest paths from the source to all nodes
+    /// and their order by distance using breadth-first search.
+    fn bfs<'g, NI, EV, E, I>(&mut self, source: NI, edges: &E)
+    where
+        NI: Idx,
+        EV: EdgeWeight + 'g,
+        E: Fn(NI) -> I,
+        I: Iterator<Item = (NI, &'g Target<NI, EV>)>,
+    {
+        self.distances[source.index()] = 0.0;
+        self.sigma[source.index()] = 1.0;
+        self.queue.push_back(source.index());
+
+        while let Some(node) = self.queue.pop_front() {
+            self.order.push(node);
+            let distance = self.distances[node] + 1.0;
+
+            for (_, target) in edges(NI::new(node)) {
+                let target = target.target.index();
+                if self.distances[target] == f64::INFINITY {
+                    self.distances[target] = distance;
+                    self.queue.push_back(target);
+                }
+                if self.distances[target] == distance {
+                    self.sigma[target] += self.sigma[node];
+                }
+            }
+        }
+    }
+
+    /// Computes the number of shortest paths from the source to all nodes
+    /// and their order by distance using Dijkstra's algorithm.
+    fn dijkstra<'g, NI, EV, E, I>(&mut self, source: NI, edges: &E)
+    where
+        NI: Idx,
+        EV: EdgeWeight + 'g,
+        E: Fn(NI) -> I,
+        I: Iterator<Item = (NI, &'g Target<NI, EV>)>,
+    {
+        self.distances[source.index()] = 0.0;
+        self.sigma[source.index()] = 1.0;
+        self.heap.push(HeapEntry {
+            distance: 0.0,
+            node: source.index(),
+        });
+
+        while let Some(HeapEntry { distance, node }) = self.heap.pop() {
+            // Skip outdated entries and nodes that have already been settled.
+            if distance > self.distances[node] || self.delta[node] < 0.0 {
+                continue;
+            }
+            // Mark the node as settled, `delta` is reset before accumulation.
+            self.delta[node] = -1.0;
+            self.order.push(node);
+
+            for (_, target) in edges(NI::new(node)) {
+                let length = distance + target.value.weight();
+                let target = target.target.index();
+
+                match length.partial_cmp(&self.distances[target]) {
+                    Some(CmpOrdering::Less) => {
+                        self.distances[target] = length;
+                        self.sigma[target] = self.sigma[node];
+                        self.heap.push(HeapEntry {
+                            distance: length,
+                            node: target,
+                        });
+                    }
+                    Some(CmpOrdering::Equal) => self.sigma[target] += self.sigma[node],
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Accumulates the dependencies of all nodes reached from the source in
+    /// reverse order of their distance and resets the state.
+    fn accumulate<'g, NI, EV, E, I>(&mut self, source: NI, edges: &E)
+    where
+        NI: Idx,
+        EV: EdgeWeight + 'g,
+        E: Fn(NI) -> I,
+        I: Iterator<Item = (NI, &'g Target<NI, EV>)>,
+    {
+        for &node in &self.order {
+            self.delta[node] = 0.0;
+        }
+
+        for &node in self.order.iter().rev() {
+            let distance = self.distances[node];
+            let mut delta = 0.0;
+
+            for (edge, target) in edges(NI::new(node)) {
+                let length = if EV::WEIGHTED {
+                    target.value.weight()
+                } else {
+                    1.0
+                };
+                let target = target.target.index();
+
+                if distance + length == self.distances[target] {
+                    let dependency =
+                        self.sigma[node] / self.sigma[target] * (1.0 + self.delta[target]);
+                    delta += dependency;
+                    if let Some(edge_score) = self.edge_scores.get_mut(edge.index()) {
+                        *edge_score += dependency;
+                    }
+                }
+            }
+
+            self.delta[node] = delta;
+            if node != source.index() {
+                self.scores[node] += delta;
+            }
+        }
+
+        for node in self.order.drain(..) {
+            self.distances[node] = f64::INFINITY;
+            self.sigma[node] = 0.0;
+            self.delta[node] = 0.0;
+        }
+    }
+}
+
+/// A node and its tentative distance, ordered such that `BinaryHeap` pops the
+/// smallest distance first.
+#[derive(PartialEq)]
+struct HeapEntry {
+    distance: f64,
+    node: usize,
+}
+
+impl Eq for HeapEntry {}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        other
+            .distance
+            .partial_cmp(&self.distance)
+            .unwrap_or(CmpOrdering::Equal)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1E-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn undirected_test() {
+        // Two triangles connected by the bridge (2, 3)
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (3, 5), (4, 5)])
+            .build();
+
+        let config = BetweennessConfig::new(None, false, true);
+        let (scores, edge_scores) = undirected_betweenness(&graph, config);
+
+        assert_close(&scores, &[0.0, 0.0, 6.0, 6.0, 0.0, 0.0]);
+
+        // neighbors of 2 are [0, 1, 3], i.e., (2, 3) has id 6 and (3, 2) id 7
+        let edge_scores = edge_scores.unwrap();
+        assert_close(&edge_scores[6..8], &[9.0, 9.0]);
+        assert_close(&edge_scores[0..2], &[1.0, 4.0]);
+    }
+
+    #[test]
+    fn directed_test() {
+        // Two shortest paths from 0 to 3
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)])
+            .build();
+
+        let config = BetweennessConfig::new(None, false, true);
+        let (scores, edge_scores) = directed_betweenness(&graph, config);
+
+        assert_close(&scores, &[0.0, 1.0, 1.0, 3.0, 0.0]);
+        assert_close(&edge_scores.unwrap(), &[2.0, 2.0, 3.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn weighted_test() {
+        // The direct edge (0, 2) is longer than the path via 1.
+        let graph: DirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![
+                (0, 1, 1.0),
+                (1, 2, 1.0),
+                (0, 2, 3.0),
+                (0, 3, 1.0),
+                (3, 2, 1.0),
+            ])
+            .build();
+
+        let (scores, _) = directed_betweenness(&graph, BetweennessConfig::default());
+
+        assert_close(&scores, &[0.0, 0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn normalize_test() {
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (0, 2), (0, 3), (0, 4)])
+            .build();
+
+        let config = BetweennessConfig::new(None, true, false);
+        let (scores, _) = undirected_betweenness(&graph, config);
+
+        assert_close(&scores, &[1.0, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn sampling_test() {
+        let edges = (1..100)
+            .map(|n| (n - 1, n))
+            .collect::<Vec<(usize, usize)>>();
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+
+        let (exact, _) = undirected_betweenness(&graph, BetweennessConfig::default());
+        let config = BetweennessConfig::new(Some(100), false, false);
+        assert_close(&undirected_betweenness(&graph, config).0, &exact);
+
+        let config = BetweennessConfig::new(Some(50), false, false);
+        let (approx, _) = undirected_betweenness(&graph, config);
+        assert_eq!(approx, undirected_betweenness(&graph, config).0);
+
+        // The middle of a path has the highest betweenness.
+        let max = approx.iter().cloned().fold(0.0, f64::max);
+        assert!(approx[50] > max / 2.0);
+        assert!(approx[0] == 0.0 && approx[99] == 0.0);
+    }
+}
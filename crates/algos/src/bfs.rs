@@ -0,0 +1,296 @@
+//! Direction-optimizing parallel breadth-first search [1].
+//!
+//! Each round expands the frontier either top-down, i.e., the frontier nodes
+//! visit their outgoing neighbors, or bottom-up, i.e., all unvisited nodes
+//! look for an incoming neighbor in the frontier. Bottom-up rounds are used
+//! once the frontier touches a large part of the edges, see
+//! [`edge_map`](crate::frontier::edge_map).
+//!
+//! [1] Scott Beamer, Krste Asanović, David Patterson:
+//! "Direction-Optimizing Breadth-First Search", SC 2012
+use crate::frontier::{edge_map_with, EdgeMapFn, Frontier, Traversal};
+use crate::prelude::*;
+use crate::view::symmetric;
+
+use log::info;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// The distance of nodes that have not been reached.
+pub const UNREACHABLE: usize = usize::MAX;
+
+#[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct BfsConfig {
+    /// The maximum distance from the source nodes. Nodes
+    /// further away are not visited.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub max_depth: Option<usize>,
+
+    /// Disables bottom-up rounds, i.e., the search always
+    /// expands the frontier top-down.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub top_down: bool,
+}
+
+impl BfsConfig {
+    pub fn new(max_depth: Option<usize>) -> Self {
+        Self {
+            max_depth,
+            ..Self::default()
+        }
+    }
+}
+
+/// The result of a breadth-first search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BfsResult<NI> {
+    /// The hop distance of each node to its closest source node or
+    /// [`UNREACHABLE`].
+    pub distances: Vec<usize>,
+    /// The node from which each node has been discovered. Source nodes and
+    /// unreachable nodes are their own parent.
+    pub parents: Vec<NI>,
+}
+
+impl<NI: Idx> BfsResult<NI> {
+    /// Returns the hop distance of the node or `None` if it is unreachable.
+    pub fn distance(&self, node: NI) -> Option<usize> {
+        Some(self.distances[node.index()]).filter(|d| *d != UNREACHABLE)
+    }
+
+    /// Returns the parent of the node or `None` if it is a source node or
+    /// unreachable.
+    pub fn parent(&self, node: NI) -> Option<NI> {
+        match self.distances[node.index()] {
+            0 | UNREACHABLE => None,
+            _ => Some(self.parents[node.index()]),
+        }
+    }
+
+    /// Returns the path from the closest source node to the given node or
+    /// `None` if the node is unreachable.
+    pub fn path_to(&self, node: NI) -> Option<Vec<NI>> {
+        self.distance(node)?;
+
+        let mut path = vec![node];
+        let mut current = node;
+        while let Some(parent) = self.parent(current) {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Runs a breadth-first search from the given source nodes along the
+/// outgoing edges of a directed graph. Bottom-up rounds use `in_neighbors`.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 3), (3, 0), (4, 0)])
+///     .build();
+///
+/// let result = directed_bfs(&graph, &[0], BfsConfig::default());
+///
+/// assert_eq!(result.distance(3), Some(3));
+/// assert_eq!(result.distance(4), None);
+/// assert_eq!(result.path_to(3), Some(vec![0, 1, 2, 3]));
+/// ```
+pub fn directed_bfs<NI, G>(graph: &G, sources: &[NI], config: BfsConfig) -> BfsResult<NI>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    bfs(graph, sources, config)
+}
+
+/// Runs a breadth-first search from the given source nodes on an undirected
+/// graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 3), (4, 5)])
+///     .build();
+///
+/// let result = undirected_bfs(&graph, &[0, 3], BfsConfig::new(Some(1)));
+///
+/// assert_eq!(result.distances, vec![0, 1, 1, 0, UNREACHABLE, UNREACHABLE]);
+/// ```
+pub fn undirected_bfs<NI, G>(graph: &G, sources: &[NI], config: BfsConfig) -> BfsResult<NI>
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedDegrees<NI> + UndirectedNeighbors<NI> + Sync,
+{
+    bfs(&symmetric(graph), sources, config)
+}
+
+struct Visit<'a, NI> {
+    distances: &'a [AtomicUsize],
+    parents: &'a [Atomic<NI>],
+    depth: usize,
+}
+
+impl<NI: Idx> EdgeMapFn<NI> for Visit<'_, NI> {
+    fn update_atomic(&self, source: NI, target: NI) -> bool {
+        let visited = self.distances[target.index()]
+            .compare_exchange(UNREACHABLE, self.depth, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok();
+
+        if visited {
+            self.parents[target.index()].store(source, Ordering::Relaxed);
+        }
+
+        visited
+    }
+
+    fn update(&self, source: NI, target: NI) -> bool {
+        self.distances[target.index()].store(self.depth, Ordering::Relaxed);
+        self.parents[target.index()].store(source, Ordering::Relaxed);
+        true
+    }
+
+    fn cond(&self, target: NI) -> bool {
+        self.distances[target.index()].load(Ordering::Acquire) == UNREACHABLE
+    }
+}
+
+fn bfs<NI, G>(graph: &G, sources: &[NI], config: BfsConfig) -> BfsResult<NI>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+    let max_depth = config.max_depth.unwrap_or(UNREACHABLE - 1);
+    let traversal = if config.top_down {
+        Traversal::Sparse
+    } else {
+        Traversal::Auto
+    };
+
+    let distances = (0..node_count)
+        .map(|_| AtomicUsize::new(UNREACHABLE))
+        .collect::<Vec<_>>();
+    let parents = (0..node_count)
+        .map(|node| Atomic::new(NI::new(node)))
+        .collect::<Vec<_>>();
+
+    let mut sources = sources.to_vec();
+    sources.sort_unstable();
+    sources.dedup();
+
+    for source in &sources {
+        distances[source.index()].store(0, Ordering::Relaxed);
+    }
+
+    let mut frontier = Frontier::from_nodes(node_count, sources);
+    let mut depth = 0;
+
+    while !frontier.is_empty() && depth < max_depth {
+        depth += 1;
+
+        let visit = Visit {
+            distances: &distances,
+            parents: &parents,
+            depth,
+        };
+
+        frontier = edge_map_with(graph, &frontier, &visit, traversal);
+    }
+
+    info!("Computed BFS with depth {} in {:?}", depth, start.elapsed());
+
+    BfsResult {
+        distances: distances.into_iter().map(AtomicUsize::into_inner).collect(),
+        parents: parents.into_iter().map(Atomic::into_inner).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(len: u32) -> Vec<(u32, u32)> {
+        (1..len).map(|n| (n - 1, n)).collect()
+    }
+
+    #[test]
+    fn directed_test() {
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (5, 0)])
+            .build();
+
+        for top_down in [true, false] {
+            let config = BfsConfig {
+                top_down,
+                ..BfsConfig::default()
+            };
+            let result = directed_bfs(&graph, &[0], config);
+
+            assert_eq!(result.distances, vec![0, 1, 1, 2, 3, UNREACHABLE]);
+            assert_eq!(result.parent(0), None);
+            assert_eq!(result.parent(1), Some(0));
+            assert!(matches!(result.parent(3), Some(1) | Some(2)));
+            assert_eq!(result.parent(5), None);
+        }
+    }
+
+    #[test]
+    fn bottom_up_test() {
+        // A star makes the second round touch all edges, which triggers a
+        // bottom-up round.
+        let mut edges = (1..100).map(|n| (0, n)).collect::<Vec<_>>();
+        edges.extend((1..100).map(|n| (n, 100)));
+
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+
+        let result = undirected_bfs(&graph, &[0], BfsConfig::default());
+        let top_down = undirected_bfs(
+            &graph,
+            &[0],
+            BfsConfig {
+                top_down: true,
+                ..BfsConfig::default()
+            },
+        );
+
+        assert_eq!(result.distances, top_down.distances);
+        assert_eq!(result.distance(100), Some(2));
+        assert_eq!(result.path_to(100).map(|p| p.len()), Some(3));
+    }
+
+    #[test]
+    fn multiple_sources_test() {
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new().edges(path(10)).build();
+
+        let result = undirected_bfs(&graph, &[0, 9, 9], BfsConfig::default());
+
+        assert_eq!(result.distances, vec![0, 1, 2, 3, 4, 4, 3, 2, 1, 0]);
+        assert_eq!(result.path_to(6), Some(vec![9, 8, 7, 6]));
+    }
+
+    #[test]
+    fn max_depth_test() {
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new().edges(path(5)).build();
+
+        let result = directed_bfs(&graph, &[0], BfsConfig::new(Some(2)));
+        assert_eq!(result.distances, vec![0, 1, 2, UNREACHABLE, UNREACHABLE]);
+
+        let result = directed_bfs(&graph, &[0], BfsConfig::new(Some(0)));
+        assert_eq!(result.distance(1), None);
+    }
+}
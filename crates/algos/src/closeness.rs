@@ -0,0 +1,594 @@
+//! Closeness and harmonic centrality.
+//!
+//! The closeness of a node `v` that reaches `r` other nodes is `r` divided by
+//! the sum of the distances to these nodes. For graphs that are not
+//! (strongly) connected, the Wasserman–Faust variant [1] additionally
+//! multiplies the closeness by `r / (n - 1)`, which avoids that nodes in small
+//! components get high scores. The harmonic centrality of `v` is the sum of
+//! the inverse distances to all other nodes divided by `n - 1`.
+//!
+//! The exact computation runs breadth-first searches from 64 source nodes at
+//! a time, using one bit per source and node, on top of
+//! [`edge_map`](crate::frontier::edge_map). Its running time is quadratic in
+//! the number of nodes.
+//!
+//! The approximate computation uses HyperBall [2], which keeps a HyperLogLog
+//! counter for the ball of radius `t` around each node and grows all balls by
+//! one in each round. Its running time is linear in the number of edges times
+//! the diameter of the graph.
+//!
+//! [1] Stanley Wasserman, Katherine Faust:
+//! "Social Network Analysis: Methods and Applications",
+//! Cambridge University Press, 1994
+//!
+//! [2] Paolo Boldi, Sebastiano Vigna:
+//! "In-Core Computation of Geometric Centralities with HyperBall:
+//! A Hundred Billion Nodes and Beyond", ICDM Workshops 2013
+use crate::frontier::{edge_map, EdgeMapFn, Frontier};
+use crate::prelude::*;
+use crate::sampling::split_mix;
+use crate::view::{symmetric, transpose};
+
+use atomic_float::AtomicF64;
+use log::info;
+use rayon::prelude::*;
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::time::Instant;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct ClosenessConfig {
+    /// Multiplies the closeness by the fraction of reached
+    /// nodes (Wasserman–Faust). Does not affect harmonic
+    /// centrality.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub wasserman_faust: bool,
+
+    /// Approximates the distances using HyperBall instead
+    /// of computing them exactly.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub approximate: bool,
+
+    /// The base-2 logarithm of the number of registers of
+    /// each HyperLogLog counter, between 4 and 16.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = ClosenessConfig::DEFAULT_PRECISION))]
+    pub precision: u8,
+
+    /// The seed of the hash function used by the
+    /// HyperLogLog counters.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = ClosenessConfig::DEFAULT_SEED))]
+    pub seed: u64,
+}
+
+impl Default for ClosenessConfig {
+    fn default() -> Self {
+        Self {
+            wasserman_faust: false,
+            approximate: false,
+            precision: Self::DEFAULT_PRECISION,
+            seed: Self::DEFAULT_SEED,
+        }
+    }
+}
+
+impl ClosenessConfig {
+    pub const DEFAULT_PRECISION: u8 = 6;
+    pub const DEFAULT_SEED: u64 = 42;
+
+    pub fn new(wasserman_faust: bool, approximate: bool) -> Self {
+        Self {
+            wasserman_faust,
+            approximate,
+            ..Self::default()
+        }
+    }
+}
+
+/// Computes the closeness of each node of a directed graph based on the
+/// distances along outgoing edges.
+///
+/// To use the distances along incoming edges, pass a
+/// [`transpose`](crate::view::transpose)d graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (1, 3)])
+///     .build();
+///
+/// let closeness = directed_closeness(&graph, ClosenessConfig::default());
+///
+/// assert_eq!(closeness, vec![0.6, 1.0, 0.0, 0.0]);
+/// ```
+pub fn directed_closeness<NI, G>(graph: &G, config: ClosenessConfig) -> Vec<f64>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    distance_sums(graph, config).closeness(config.wasserman_faust)
+}
+
+/// Computes the closeness of each node of an undirected graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2), (0, 3)])
+///     .build();
+///
+/// let closeness = undirected_closeness(&graph, ClosenessConfig::default());
+///
+/// assert_eq!(closeness, vec![1.0, 0.6, 0.6, 0.6]);
+/// ```
+pub fn undirected_closeness<NI, G>(graph: &G, config: ClosenessConfig) -> Vec<f64>
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedDegrees<NI> + UndirectedNeighbors<NI> + Sync,
+{
+    distance_sums(&symmetric(graph), config).closeness(config.wasserman_faust)
+}
+
+/// Computes the harmonic centrality of each node of a directed graph based on
+/// the distances along outgoing edges.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2)])
+///     .build();
+///
+/// let harmonic = directed_harmonic(&graph, ClosenessConfig::default());
+///
+/// assert_eq!(harmonic, vec![0.75, 0.5, 0.0]);
+/// ```
+pub fn directed_harmonic<NI, G>(graph: &G, config: ClosenessConfig) -> Vec<f64>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    distance_sums(graph, config).harmonic()
+}
+
+/// Computes the harmonic centrality of each node of an undirected graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2)])
+///     .build();
+///
+/// let harmonic = undirected_harmonic(&graph, ClosenessConfig::default());
+///
+/// assert_eq!(harmonic, vec![0.75, 1.0, 0.75]);
+/// ```
+pub fn undirected_harmonic<NI, G>(graph: &G, config: ClosenessConfig) -> Vec<f64>
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedDegrees<NI> + UndirectedNeighbors<NI> + Sync,
+{
+    distance_sums(&symmetric(graph), config).harmonic()
+}
+
+/// Per node sums over all other reachable nodes.
+struct DistanceSums {
+    /// The number of reached nodes.
+    reached: Vec<f64>,
+    /// The sum of the distances to the reached nodes.
+    farness: Vec<f64>,
+    /// The sum of the inverse distances to the reached nodes.
+    harmonic: Vec<f64>,
+}
+
+impl DistanceSums {
+    fn closeness(self, wasserman_faust: bool) -> Vec<f64> {
+        let others = self.reached.len().saturating_sub(1) as f64;
+
+        self.reached
+            .into_par_iter()
+            .zip(self.farness)
+            .map(|(reached, farness)| {
+                if farness <= 0.0 {
+                    0.0
+                } else if wasserman_faust {
+                    reached / farness * reached / others
+                } else {
+                    reached / farness
+                }
+            })
+            .collect()
+    }
+
+    fn harmonic(self) -> Vec<f64> {
+        let others = self.harmonic.len().saturating_sub(1).max(1) as f64;
+
+        self.harmonic
+            .into_par_iter()
+            .map(|harmonic| harmonic / others)
+            .collect()
+    }
+}
+
+fn distance_sums<NI, G>(graph: &G, config: ClosenessConfig) -> DistanceSums
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    if config.approximate {
+        assert!(
+            (4..=16).contains(&config.precision),
+            "precision must be between 4 and 16"
+        );
+        hyper_ball(graph, config.precision, config.seed)
+    } else {
+        multi_source_bfs(graph)
+    }
+}
+
+/// Visits the nodes that are reached by a source in the current round.
+struct MultiSourceVisit<'a> {
+    seen: &'a [AtomicU64],
+    visit: &'a [AtomicU64],
+    next: &'a [AtomicU64],
+    all_sources: u64,
+}
+
+impl<NI: Idx> EdgeMapFn<NI> for MultiSourceVisit<'_> {
+    fn update_atomic(&self, source: NI, target: NI) -> bool {
+        let sources = self.visit[source.index()].load(Ordering::Relaxed)
+            & !self.seen[target.index()].load(Ordering::Relaxed);
+
+        sources != 0 && self.next[target.index()].fetch_or(sources, Ordering::AcqRel) == 0
+    }
+
+    fn cond(&self, target: NI) -> bool {
+        self.seen[target.index()].load(Ordering::Relaxed) != self.all_sources
+    }
+}
+
+fn multi_source_bfs<NI, G>(graph: &G) -> DistanceSums
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+
+    let seen = (0..node_count)
+        .map(|_| AtomicU64::new(0))
+        .collect::<Vec<_>>();
+    let visit = (0..node_count)
+        .map(|_| AtomicU64::new(0))
+        .collect::<Vec<_>>();
+    let next = (0..node_count)
+        .map(|_| AtomicU64::new(0))
+        .collect::<Vec<_>>();
+
+    let mut reached = vec![0.0; node_count];
+    let mut farness = vec![0.0; node_count];
+    let mut harmonic = vec![0.0; node_count];
+
+    for batch in (0..node_count).step_by(WORD_BITS) {
+        let batch_size = usize::min(WORD_BITS, node_count - batch);
+        let all_sources = u64::MAX >> (WORD_BITS - batch_size);
+
+        seen.par_iter().for_each(|s| s.store(0, Ordering::Relaxed));
+
+        let sources = (batch..batch + batch_size).map(NI::new).collect::<Vec<_>>();
+        for (bit, source) in sources.iter().enumerate() {
+            seen[source.index()].store(1 << bit, Ordering::Relaxed);
+            visit[source.index()].store(1 << bit, Ordering::Relaxed);
+        }
+
+        let mut frontier = Frontier::from_nodes(node_count, sources);
+        let mut depth = 0;
+
+        while !frontier.is_empty() {
+            depth += 1;
+
+            let visit_fn = MultiSourceVisit {
+                seen: &seen,
+                visit: &visit,
+                next: &next,
+                all_sources,
+            };
+            let next_frontier = edge_map(graph, &frontier, &visit_fn);
+
+            frontier.for_each(|node| visit[node.index()].store(0, Ordering::Relaxed));
+
+            // Counts for each source the nodes at the current depth.
+            let counts = next_frontier
+                .to_vec()
+                .into_par_iter()
+                .fold(
+                    || [0_usize; WORD_BITS],
+                    |mut counts, node| {
+                        let mut sources = next[node.index()].swap(0, Ordering::Relaxed);
+                        seen[node.index()].fetch_or(sources, Ordering::Relaxed);
+                        visit[node.index()].store(sources, Ordering::Relaxed);
+
+                        while sources != 0 {
+                            counts[sources.trailing_zeros() as usize] += 1;
+                            sources &= sources - 1;
+                        }
+
+                        counts
+                    },
+                )
+                .reduce(
+                    || [0_usize; WORD_BITS],
+                    |mut a, b| {
+                        a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                        a
+                    },
+                );
+
+            for (bit, count) in counts.iter().take(batch_size).enumerate() {
+                let count = *count as f64;
+                reached[batch + bit] += count;
+                farness[batch + bit] += count * depth as f64;
+                harmonic[batch + bit] += count / depth as f64;
+            }
+
+            frontier = next_frontier;
+        }
+    }
+
+    info!(
+        "Computed exact distance sums for {} nodes in {:?}",
+        node_count,
+        start.elapsed()
+    );
+
+    DistanceSums {
+        reached,
+        farness,
+        harmonic,
+    }
+}
+
+/// Merges the counters of the nodes that changed in the previous round into
+/// the counters of their incoming neighbors.
+struct Merge<'a> {
+    previous: &'a [AtomicU8],
+    current: &'a [AtomicU8],
+    changed: &'a [AtomicBool],
+    registers: usize,
+}
+
+impl<NI: Idx> EdgeMapFn<NI> for Merge<'_> {
+    fn update_atomic(&self, source: NI, target: NI) -> bool {
+        let source = &self.previous[source.index() * self.registers..][..self.registers];
+        let target_counter = &self.current[target.index() * self.registers..][..self.registers];
+
+        let mut changed = false;
+        for (s, t) in source.iter().zip(target_counter) {
+            let s = s.load(Ordering::Relaxed);
+            if s > t.load(Ordering::Relaxed) && s > t.fetch_max(s, Ordering::Relaxed) {
+                changed = true;
+            }
+        }
+
+        changed && !self.changed[target.index()].swap(true, Ordering::AcqRel)
+    }
+}
+
+fn hyper_ball<NI, G>(graph: &G, precision: u8, seed: u64) -> DistanceSums
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+    let registers = 1_usize << precision;
+
+    let init = |i: usize| {
+        let (node, register) = (i / registers, i % registers);
+        let hash = split_mix(seed ^ split_mix(node as u64));
+        if hash as usize & (registers - 1) == register {
+            ((hash >> precision).leading_zeros() + 1 - precision as u32) as u8
+        } else {
+            0
+        }
+    };
+
+    let previous = (0..node_count * registers)
+        .into_par_iter()
+        .map(|i| AtomicU8::new(init(i)))
+        .collect::<Vec<_>>();
+    let current = (0..node_count * registers)
+        .into_par_iter()
+        .map(|i| AtomicU8::new(init(i)))
+        .collect::<Vec<_>>();
+    let changed = (0..node_count)
+        .map(|_| AtomicBool::new(false))
+        .collect::<Vec<_>>();
+
+    // The ball of radius 0 only contains the node itself.
+    let sizes = (0..node_count)
+        .map(|_| AtomicF64::new(1.0))
+        .collect::<Vec<_>>();
+    let farness = (0..node_count)
+        .map(|_| AtomicF64::new(0.0))
+        .collect::<Vec<_>>();
+    let harmonic = (0..node_count)
+        .map(|_| AtomicF64::new(0.0))
+        .collect::<Vec<_>>();
+
+    // Pushing along the reversed edges merges the counters of the outgoing
+    // neighbors into the counter of a node.
+    let reversed = transpose(graph);
+    let mut frontier = Frontier::all(node_count);
+    let mut depth = 0;
+
+    while !frontier.is_empty() {
+        depth += 1;
+
+        let merge = Merge {
+            previous: &previous,
+            current: &current,
+            changed: &changed,
+            registers,
+        };
+        frontier = edge_map(&reversed, &frontier, &merge);
+
+        frontier.for_each(|node| {
+            let node = node.index();
+            changed[node].store(false, Ordering::Relaxed);
+
+            let range = node * registers..(node + 1) * registers;
+            for (p, c) in previous[range.clone()].iter().zip(&current[range]) {
+                p.store(c.load(Ordering::Relaxed), Ordering::Relaxed);
+            }
+
+            let size = estimate(&previous[node * registers..][..registers]);
+            let delta = size - sizes[node].swap(size, Ordering::Relaxed);
+            farness[node].fetch_add(delta * depth as f64, Ordering::Relaxed);
+            harmonic[node].fetch_add(delta / depth as f64, Ordering::Relaxed);
+        });
+    }
+
+    info!(
+        "Computed approximate distance sums for {} nodes in {} rounds in {:?}",
+        node_count,
+        depth,
+        start.elapsed()
+    );
+
+    let into_vec = |values: Vec<AtomicF64>| {
+        values
+            .into_par_iter()
+            .map(AtomicF64::into_inner)
+            .collect::<Vec<_>>()
+    };
+
+    DistanceSums {
+        reached: sizes
+            .into_par_iter()
+            .map(|size| size.into_inner() - 1.0)
+            .collect(),
+        farness: into_vec(farness),
+        harmonic: into_vec(harmonic),
+    }
+}
+
+/// Estimates the cardinality of a HyperLogLog counter.
+fn estimate(counter: &[AtomicU8]) -> f64 {
+    let m = counter.len() as f64;
+    let alpha = match counter.len() {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        _ => 0.7213 / (1.0 + 1.079 / m),
+    };
+
+    let (sum, zeros) = counter.iter().fold((0.0, 0), |(sum, zeros), register| {
+        let register = register.load(Ordering::Relaxed);
+        (
+            sum + (-(register as f64)).exp2(),
+            zeros + usize::from(register == 0),
+        )
+    });
+
+    let estimate = alpha * m * m / sum;
+
+    // Linear counting is more accurate for small cardinalities.
+    if estimate <= 2.5 * m && zeros > 0 {
+        m * (m / zeros as f64).ln()
+    } else {
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64], tolerance: f64) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= tolerance, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn wasserman_faust_test() {
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (2, 3), (3, 4)])
+            .build();
+
+        let closeness = undirected_closeness(&graph, ClosenessConfig::new(false, false));
+        assert_close(&closeness, &[1.0, 1.0, 2.0 / 3.0, 1.0, 2.0 / 3.0], 1E-9);
+
+        let closeness = undirected_closeness(&graph, ClosenessConfig::new(true, false));
+        assert_close(&closeness, &[0.25, 0.25, 1.0 / 3.0, 0.5, 1.0 / 3.0], 1E-9);
+    }
+
+    #[test]
+    fn multiple_batches_test() {
+        // A cycle with more nodes than sources per batch
+        let edges = (0..150_u32).map(|n| (n, (n + 1) % 150)).collect::<Vec<_>>();
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new().edges(edges).build();
+
+        let farness = (1..150).sum::<u32>() as f64;
+        let harmonic = (1..150).map(|d| 1.0 / d as f64).sum::<f64>() / 149.0;
+
+        let config = ClosenessConfig::default();
+        assert_close(
+            &directed_closeness(&graph, config),
+            &[149.0 / farness; 150],
+            1E-9,
+        );
+        assert_close(&directed_harmonic(&graph, config), &[harmonic; 150], 1E-9);
+    }
+
+    #[test]
+    fn hyper_ball_test() {
+        let edges = (1..100_u32).map(|n| (n - 1, n)).collect::<Vec<_>>();
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new().edges(edges).build();
+
+        let exact = undirected_harmonic(&graph, ClosenessConfig::default());
+
+        let config = ClosenessConfig {
+            approximate: true,
+            precision: 12,
+            ..ClosenessConfig::default()
+        };
+        let approximate = undirected_harmonic(&graph, config);
+
+        assert_close(&approximate, &exact, 0.05);
+    }
+
+    #[test]
+    fn hyper_ball_directed_test() {
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2), (1, 3)])
+            .build();
+
+        let config = ClosenessConfig {
+            approximate: true,
+            precision: 10,
+            ..ClosenessConfig::default()
+        };
+        let closeness = directed_closeness(&graph, config);
+
+        assert_close(&closeness, &[0.6, 1.0, 0.0, 0.0], 0.01);
+    }
+}
@@ -0,0 +1,553 @@
+//! Frontiers and the `edge_map` / `vertex_map` operators known from Ligra [1].
+//!
+//! A [`Frontier`] is a subset of the nodes of a graph, e.g., the nodes that
+//! have been discovered in the last round of a breadth-first search. Small
+//! frontiers are stored as a sparse list of node ids, large frontiers as a
+//! dense bitmap with one bit per node.
+//!
+//! [`edge_map`] applies a function to all edges that leave the frontier and
+//! returns the targets for which the function returned `true` as the next
+//! frontier. Depending on the number of edges leaving the frontier, it either
+//! pushes updates along the outgoing edges of the frontier (sparse) or lets
+//! every node pull updates from its incoming neighbors in the frontier
+//! (dense). [`vertex_map`] applies a function to all nodes of a frontier.
+//!
+//! [1] Julian Shun, Guy E. Blelloch:
+//! "Ligra: A Lightweight Graph Processing Framework for Shared Memory",
+//! PPoPP 2013
+use crate::prelude::*;
+
+use rayon::prelude::*;
+
+use std::sync::atomic::Ordering;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// `edge_map` switches to dense traversal if the number of edges leaving the
+/// frontier exceeds `edge_count / DENSE_THRESHOLD`.
+pub const DENSE_THRESHOLD: usize = 20;
+
+/// Defines how `edge_map_with` traverses the edges leaving the frontier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Traversal {
+    /// Selects sparse or dense traversal depending on the number of edges
+    /// leaving the frontier.
+    Auto,
+    /// Pushes updates along the outgoing edges of the frontier nodes.
+    Sparse,
+    /// Pulls updates from the incoming neighbors of all nodes.
+    Dense,
+}
+
+/// The function that is applied to the edges in `edge_map`.
+pub trait EdgeMapFn<NI: Idx>: Sync {
+    /// Applies the function to the edge `(source, target)` and returns `true`
+    /// if `target` should be contained in the next frontier.
+    ///
+    /// The function is called concurrently for edges with the same target
+    /// during sparse traversal. To avoid duplicates in the next frontier, it
+    /// should return `true` at most once per target, e.g., by using a
+    /// compare-and-swap operation.
+    fn update_atomic(&self, source: NI, target: NI) -> bool;
+
+    /// Applies the function to the edge `(source, target)` during dense
+    /// traversal, where all edges with the same target are processed by the
+    /// same thread.
+    fn update(&self, source: NI, target: NI) -> bool {
+        self.update_atomic(source, target)
+    }
+
+    /// Returns `false` if the edges leading to `target` can be skipped, e.g.,
+    /// because `target` has already been visited.
+    fn cond(&self, _target: NI) -> bool {
+        true
+    }
+}
+
+/// A subset of the nodes of a graph.
+#[derive(Clone, Debug)]
+pub struct Frontier<NI: Idx> {
+    node_count: usize,
+    len: usize,
+    nodes: Nodes<NI>,
+}
+
+#[derive(Clone, Debug)]
+enum Nodes<NI> {
+    Sparse(Vec<NI>),
+    Dense(Vec<u64>),
+}
+
+impl<NI: Idx> Frontier<NI> {
+    /// Creates an empty frontier for a graph with the given node count.
+    pub fn empty(node_count: usize) -> Self {
+        Self::sparse(node_count, Vec::new())
+    }
+
+    /// Creates a frontier that contains the given node.
+    pub fn single(node_count: usize, node: NI) -> Self {
+        Self::sparse(node_count, vec![node])
+    }
+
+    /// Creates a frontier that contains all nodes of the graph.
+    pub fn all(node_count: usize) -> Self {
+        let mut words = vec![u64::MAX; word_count(node_count)];
+        let remainder = node_count % WORD_BITS;
+        if remainder > 0 {
+            words[node_count / WORD_BITS] = (1 << remainder) - 1;
+        }
+
+        Self::dense(node_count, words)
+    }
+
+    /// Creates a frontier from a list of distinct nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph::frontier::Frontier;
+    ///
+    /// let frontier = Frontier::from_nodes(10, vec![3_u32, 1, 4]);
+    ///
+    /// assert_eq!(frontier.len(), 3);
+    /// assert!(!frontier.is_dense());
+    /// assert_eq!(frontier.to_dense().to_vec(), vec![1, 3, 4]);
+    /// ```
+    pub fn from_nodes(node_count: usize, nodes: Vec<NI>) -> Self {
+        Self::sparse(node_count, nodes)
+    }
+
+    fn sparse(node_count: usize, nodes: Vec<NI>) -> Self {
+        Self {
+            node_count,
+            len: nodes.len(),
+            nodes: Nodes::Sparse(nodes),
+        }
+    }
+
+    fn dense(node_count: usize, words: Vec<u64>) -> Self {
+        let len = words.par_iter().map(|w| w.count_ones() as usize).sum();
+        Self {
+            node_count,
+            len,
+            nodes: Nodes::Dense(words),
+        }
+    }
+
+    /// Returns the number of nodes in the frontier.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the frontier contains no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Returns `true` if the frontier is stored as a bitmap.
+    pub fn is_dense(&self) -> bool {
+        matches!(self.nodes, Nodes::Dense(_))
+    }
+
+    /// Returns `true` if the frontier contains the given node.
+    ///
+    /// Runs in constant time for dense frontiers and in linear time for sparse
+    /// frontiers.
+    pub fn contains(&self, node: NI) -> bool {
+        match &self.nodes {
+            Nodes::Sparse(nodes) => nodes.contains(&node),
+            Nodes::Dense(words) => test_bit(words, node.index()),
+        }
+    }
+
+    /// Converts the frontier into a bitmap.
+    pub fn to_dense(self) -> Self {
+        match self.nodes {
+            Nodes::Dense(_) => self,
+            Nodes::Sparse(nodes) => {
+                let mut words = Vec::with_capacity(word_count(self.node_count));
+                words.resize_with(word_count(self.node_count), || Atomic::new(0_u64));
+
+                nodes.par_iter().for_each(|node| {
+                    let bit = 1 << (node.index() % WORD_BITS);
+                    words[node.index() / WORD_BITS].fetch_or(bit, Ordering::Relaxed);
+                });
+
+                let words = words.into_iter().map(Atomic::into_inner).collect();
+                Self::dense(self.node_count, words)
+            }
+        }
+    }
+
+    /// Converts the frontier into a list of nodes in ascending order.
+    pub fn to_sparse(self) -> Self {
+        match self.nodes {
+            Nodes::Sparse(_) => self,
+            Nodes::Dense(words) => Self::sparse(self.node_count, dense_nodes(&words).collect()),
+        }
+    }
+
+    /// Returns the nodes of the frontier. Nodes of a dense frontier are
+    /// returned in ascending order.
+    pub fn to_vec(&self) -> Vec<NI> {
+        match &self.nodes {
+            Nodes::Sparse(nodes) => nodes.clone(),
+            Nodes::Dense(words) => dense_nodes(words).collect(),
+        }
+    }
+
+    /// Calls `f` for each node of the frontier in parallel.
+    pub fn for_each<F>(&self, f: F)
+    where
+        F: Fn(NI) + Sync + Send,
+    {
+        match &self.nodes {
+            Nodes::Sparse(nodes) => nodes.par_iter().for_each(|node| f(*node)),
+            Nodes::Dense(words) => dense_nodes(words).for_each(f),
+        }
+    }
+}
+
+/// Applies `f` to all nodes of the frontier and returns the nodes for which
+/// `f` returned `true`.
+///
+/// # Example
+///
+/// ```
+/// use graph::frontier::{vertex_map, Frontier};
+///
+/// let frontier = Frontier::<u32>::all(10);
+/// let even = vertex_map(&frontier, |node| node % 2 == 0);
+///
+/// assert_eq!(even.to_vec(), vec![0, 2, 4, 6, 8]);
+/// ```
+pub fn vertex_map<NI, F>(frontier: &Frontier<NI>, f: F) -> Frontier<NI>
+where
+    NI: Idx,
+    F: Fn(NI) -> bool + Sync + Send,
+{
+    match &frontier.nodes {
+        Nodes::Sparse(nodes) => {
+            let nodes = nodes.par_iter().copied().filter(|node| f(*node)).collect();
+            Frontier::sparse(frontier.node_count, nodes)
+        }
+        Nodes::Dense(words) => {
+            let words = words
+                .par_iter()
+                .enumerate()
+                .map(|(w, word)| {
+                    bits(*word, w * WORD_BITS)
+                        .filter(|node| f(NI::new(*node)))
+                        .fold(0, |word, node| word | (1 << (node % WORD_BITS)))
+                })
+                .collect();
+            Frontier::dense(frontier.node_count, words)
+        }
+    }
+}
+
+/// Applies `f` to all edges leaving the frontier and returns the targets for
+/// which `f` returned `true`.
+///
+/// The traversal is selected automatically, see [`Traversal::Auto`].
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// use graph::prelude::*;
+/// use graph::frontier::{edge_map, EdgeMapFn, Frontier};
+///
+/// struct Visit(Vec<AtomicBool>);
+///
+/// impl EdgeMapFn<u32> for Visit {
+///     fn update_atomic(&self, _source: u32, target: u32) -> bool {
+///         !self.0[target as usize].swap(true, Ordering::AcqRel)
+///     }
+///
+///     fn cond(&self, target: u32) -> bool {
+///         !self.0[target as usize].load(Ordering::Acquire)
+///     }
+/// }
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2), (1, 3), (2, 3)])
+///     .build();
+///
+/// let visit = Visit((0..4).map(|n| AtomicBool::new(n == 0)).collect());
+///
+/// let frontier = Frontier::single(4, 0);
+/// let frontier = edge_map(&graph, &frontier, &visit);
+/// assert_eq!(frontier.len(), 2);
+///
+/// let frontier = edge_map(&graph, &frontier, &visit);
+/// assert_eq!(frontier.to_vec(), vec![3]);
+/// ```
+pub fn edge_map<NI, G, F>(graph: &G, frontier: &Frontier<NI>, f: &F) -> Frontier<NI>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+    F: EdgeMapFn<NI>,
+{
+    edge_map_with(graph, frontier, f, Traversal::Auto)
+}
+
+/// Applies `f` to all edges leaving the frontier using the given traversal
+/// and returns the targets for which `f` returned `true`.
+///
+/// Sparse traversal returns a sparse frontier, dense traversal a dense one.
+pub fn edge_map_with<NI, G, F>(
+    graph: &G,
+    frontier: &Frontier<NI>,
+    f: &F,
+    traversal: Traversal,
+) -> Frontier<NI>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+    F: EdgeMapFn<NI>,
+{
+    let dense = match traversal {
+        Traversal::Sparse => false,
+        Traversal::Dense => true,
+        Traversal::Auto => {
+            let threshold = graph.edge_count().index() / DENSE_THRESHOLD;
+            frontier.len() + out_degree_sum(graph, frontier) > threshold
+        }
+    };
+
+    if dense {
+        match &frontier.nodes {
+            Nodes::Dense(words) => edge_map_dense(graph, frontier.node_count, words, f),
+            Nodes::Sparse(_) => {
+                let frontier = frontier.clone().to_dense();
+                edge_map_with(graph, &frontier, f, Traversal::Dense)
+            }
+        }
+    } else {
+        match &frontier.nodes {
+            Nodes::Sparse(nodes) => edge_map_sparse(graph, frontier.node_count, nodes, f),
+            Nodes::Dense(_) => {
+                let frontier = frontier.clone().to_sparse();
+                edge_map_with(graph, &frontier, f, Traversal::Sparse)
+            }
+        }
+    }
+}
+
+fn edge_map_sparse<NI, G, F>(graph: &G, node_count: usize, nodes: &[NI], f: &F) -> Frontier<NI>
+where
+    NI: Idx,
+    G: DirectedNeighbors<NI> + Sync,
+    F: EdgeMapFn<NI>,
+{
+    let next = nodes
+        .par_iter()
+        .flat_map_iter(|&source| {
+            graph
+                .out_neighbors(source)
+                .copied()
+                .filter(move |&target| f.cond(target) && f.update_atomic(source, target))
+        })
+        .collect();
+
+    Frontier::sparse(node_count, next)
+}
+
+fn edge_map_dense<NI, G, F>(graph: &G, node_count: usize, words: &[u64], f: &F) -> Frontier<NI>
+where
+    NI: Idx,
+    G: DirectedNeighbors<NI> + Sync,
+    F: EdgeMapFn<NI>,
+{
+    let next = (0..words.len())
+        .into_par_iter()
+        .map(|w| {
+            let mut word = 0_u64;
+            let end = usize::min((w + 1) * WORD_BITS, node_count);
+
+            for node in w * WORD_BITS..end {
+                let target = NI::new(node);
+                if !f.cond(target) {
+                    continue;
+                }
+                for &source in graph.in_neighbors(target) {
+                    if test_bit(words, source.index()) && f.update(source, target) {
+                        word |= 1 << (node % WORD_BITS);
+                    }
+                    if !f.cond(target) {
+                        break;
+                    }
+                }
+            }
+
+            word
+        })
+        .collect();
+
+    Frontier::dense(node_count, next)
+}
+
+fn out_degree_sum<NI, G>(graph: &G, frontier: &Frontier<NI>) -> usize
+where
+    NI: Idx,
+    G: DirectedDegrees<NI> + Sync,
+{
+    match &frontier.nodes {
+        Nodes::Sparse(nodes) => nodes
+            .par_iter()
+            .map(|node| graph.out_degree(*node).index())
+            .sum(),
+        Nodes::Dense(words) => dense_nodes(words)
+            .map(|node: NI| graph.out_degree(node).index())
+            .sum(),
+    }
+}
+
+fn word_count(node_count: usize) -> usize {
+    let remainder = node_count % WORD_BITS;
+    node_count / WORD_BITS + usize::from(remainder > 0)
+}
+
+fn test_bit(words: &[u64], index: usize) -> bool {
+    words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+}
+
+fn dense_nodes<NI: Idx>(words: &[u64]) -> impl ParallelIterator<Item = NI> + '_ {
+    words
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(w, word)| bits(*word, w * WORD_BITS).map(NI::new))
+}
+
+/// Iterates the indices of the set bits of a word, offset by `base`.
+fn bits(mut word: u64, base: usize) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if word == 0 {
+            return None;
+        }
+        let bit = word.trailing_zeros() as usize;
+        word &= word - 1;
+        Some(base + bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::AtomicUsize;
+
+    const UNVISITED: usize = usize::MAX;
+
+    struct Bfs {
+        parents: Vec<AtomicUsize>,
+    }
+
+    impl Bfs {
+        fn new(node_count: usize, root: usize) -> Self {
+            let parents = (0..node_count)
+                .map(|n| AtomicUsize::new(if n == root { root } else { UNVISITED }))
+                .collect();
+            Self { parents }
+        }
+    }
+
+    impl EdgeMapFn<usize> for Bfs {
+        fn update_atomic(&self, source: usize, target: usize) -> bool {
+            self.parents[target]
+                .compare_exchange(UNVISITED, source, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+        }
+
+        fn cond(&self, target: usize) -> bool {
+            self.parents[target].load(Ordering::Acquire) == UNVISITED
+        }
+    }
+
+    fn graph() -> DirectedCsrGraph<usize> {
+        GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![
+                (0, 1),
+                (0, 2),
+                (1, 3),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 0),
+                (6, 0),
+            ])
+            .build()
+    }
+
+    fn levels(traversal: Traversal) -> Vec<Vec<usize>> {
+        let graph = graph();
+        let bfs = Bfs::new(graph.node_count(), 0);
+
+        let mut frontier = Frontier::single(graph.node_count(), 0);
+        let mut levels = Vec::new();
+
+        while !frontier.is_empty() {
+            let mut level = frontier.to_vec();
+            level.sort_unstable();
+            levels.push(level);
+            frontier = edge_map_with(&graph, &frontier, &bfs, traversal);
+        }
+
+        levels
+    }
+
+    #[test]
+    fn edge_map_test() {
+        let expected = vec![vec![0], vec![1, 2], vec![3], vec![4], vec![5]];
+
+        assert_eq!(levels(Traversal::Sparse), expected);
+        assert_eq!(levels(Traversal::Dense), expected);
+        assert_eq!(levels(Traversal::Auto), expected);
+    }
+
+    #[test]
+    fn conversion_test() {
+        let frontier = Frontier::from_nodes(130, vec![129_usize, 0, 64, 63]);
+
+        let dense = frontier.to_dense();
+        assert!(dense.is_dense());
+        assert_eq!(dense.len(), 4);
+        assert!(dense.contains(64));
+        assert!(!dense.contains(65));
+
+        let sparse = dense.to_sparse();
+        assert!(!sparse.is_dense());
+        assert_eq!(sparse.to_vec(), vec![0, 63, 64, 129]);
+    }
+
+    #[test]
+    fn all_test() {
+        for node_count in [0, 1, 63, 64, 65, 128] {
+            let frontier = Frontier::<usize>::all(node_count);
+            assert_eq!(frontier.len(), node_count);
+            assert_eq!(frontier.to_vec(), (0..node_count).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn vertex_map_test() {
+        let sparse = Frontier::from_nodes(100, (0..100_usize).collect());
+        let dense = Frontier::<usize>::all(100);
+
+        let odd = |node: usize| node % 2 == 1;
+
+        assert_eq!(vertex_map(&sparse, odd).len(), 50);
+        assert_eq!(
+            vertex_map(&dense, odd).to_vec(),
+            vertex_map(&sparse, odd).to_vec()
+        );
+    }
+}
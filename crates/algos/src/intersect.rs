@@ -0,0 +1,337 @@
+//! Intersection of sorted neighbor lists.
+//!
+//! Many algorithms, e.g., triangle counting, clustering coefficients, Jaccard
+//! similarity or k-truss, need to intersect the neighbor lists of two nodes.
+//! This module provides merge-based, galloping and hash-based intersection.
+//! [`intersect`] and the counting functions pick between merge-based and
+//! galloping intersection depending on the ratio of the list lengths.
+//!
+//! Except for hash-based intersection, the input lists must be sorted, i.e.,
+//! the graph must be built using either [`CsrLayout::Sorted`] or
+//! [`CsrLayout::Deduplicated`].
+//!
+//! [`CsrLayout::Sorted`]: crate::prelude::CsrLayout::Sorted
+//! [`CsrLayout::Deduplicated`]: crate::prelude::CsrLayout::Deduplicated
+use std::{cmp::Ordering, hash::Hash};
+
+use ahash::AHashSet;
+
+/// Galloping intersection is used if the longer list is at least this many
+/// times longer than the shorter list.
+pub const GALLOPING_RATIO: usize = 32;
+
+/// The algorithm used to intersect two lists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntersectionStrategy {
+    /// Walks both sorted lists in lockstep. Runs in `O(|a| + |b|)`.
+    Merge,
+    /// Searches each element of the shorter sorted list in the longer list
+    /// using exponential search. Runs in `O(|a| * log(|b| / |a|))` for
+    /// `|a| <= |b|`.
+    Galloping,
+    /// Probes the elements of the longer list in a hash set built from the
+    /// shorter list. Does not require sorted lists.
+    Hash,
+}
+
+impl IntersectionStrategy {
+    /// Selects the strategy for two sorted lists of the given lengths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph::intersect::IntersectionStrategy;
+    ///
+    /// assert_eq!(IntersectionStrategy::select(10, 20), IntersectionStrategy::Merge);
+    /// assert_eq!(IntersectionStrategy::select(10, 1000), IntersectionStrategy::Galloping);
+    /// ```
+    pub fn select(a_len: usize, b_len: usize) -> Self {
+        let (short, long) = (a_len.min(b_len), a_len.max(b_len));
+
+        if short > 0 && long / short >= GALLOPING_RATIO {
+            IntersectionStrategy::Galloping
+        } else {
+            IntersectionStrategy::Merge
+        }
+    }
+}
+
+/// Calls `f` for each element that is contained in both sorted lists.
+///
+/// The strategy is selected via [`IntersectionStrategy::select`]. Elements are
+/// visited in ascending order. If an element occurs multiple times in both
+/// lists, it is visited as often as it occurs in the list with fewer
+/// occurrences.
+///
+/// # Example
+///
+/// ```
+/// use graph::intersect::intersect;
+///
+/// let mut common = Vec::new();
+/// intersect(&[1, 3, 5, 7], &[2, 3, 4, 5], |x| common.push(x));
+///
+/// assert_eq!(common, vec![3, 5]);
+/// ```
+pub fn intersect<T, F>(a: &[T], b: &[T], f: F)
+where
+    T: Copy + Ord,
+    F: FnMut(T),
+{
+    match IntersectionStrategy::select(a.len(), b.len()) {
+        IntersectionStrategy::Galloping => galloping_intersect(a, b, f),
+        _ => merge_intersect(a, b, f),
+    }
+}
+
+/// Calls `f` for each element that is contained in both lists using the given
+/// strategy.
+pub fn intersect_with<T, F>(strategy: IntersectionStrategy, a: &[T], b: &[T], f: F)
+where
+    T: Copy + Ord + Hash,
+    F: FnMut(T),
+{
+    match strategy {
+        IntersectionStrategy::Merge => merge_intersect(a, b, f),
+        IntersectionStrategy::Galloping => galloping_intersect(a, b, f),
+        IntersectionStrategy::Hash => hash_intersect(a, b, f),
+    }
+}
+
+/// Returns the elements that are contained in both sorted lists.
+///
+/// # Example
+///
+/// ```
+/// use graph::intersect::intersection;
+///
+/// assert_eq!(intersection(&[1, 2, 3], &[2, 3, 4]), vec![2, 3]);
+/// ```
+pub fn intersection<T: Copy + Ord>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut common = Vec::with_capacity(a.len().min(b.len()));
+    intersect(a, b, |x| common.push(x));
+    common
+}
+
+/// Returns the number of elements that are contained in both sorted lists.
+///
+/// # Example
+///
+/// ```
+/// use graph::intersect::intersection_count;
+///
+/// assert_eq!(intersection_count(&[1, 2, 3], &[2, 3, 4]), 2);
+/// ```
+pub fn intersection_count<T: Copy + Ord>(a: &[T], b: &[T]) -> usize {
+    let mut count = 0;
+    intersect(a, b, |_| count += 1);
+    count
+}
+
+/// Returns the number of elements smaller than `bound` that are contained in
+/// both sorted lists.
+///
+/// Only the prefixes of the lists that are smaller than `bound` are
+/// considered, which allows counting each triangle only once by intersecting
+/// the neighbors of `u` and `v` with bound `v` for every edge `v < u`.
+///
+/// # Example
+///
+/// ```
+/// use graph::intersect::intersection_count_below;
+///
+/// assert_eq!(intersection_count_below(&[1, 2, 3, 5], &[1, 3, 4, 5], 4), 2);
+/// ```
+pub fn intersection_count_below<T: Copy + Ord>(a: &[T], b: &[T], bound: T) -> usize {
+    let mut count = 0;
+    intersect_below(a, b, bound, |_| count += 1);
+    count
+}
+
+/// Calls `f` for each element smaller than `bound` that is contained in both
+/// sorted lists, see [`intersection_count_below`].
+///
+/// # Example
+///
+/// ```
+/// use graph::intersect::intersect_below;
+///
+/// let mut common = Vec::new();
+/// intersect_below(&[1, 2, 3, 5], &[1, 3, 4, 5], 4, |x| common.push(x));
+///
+/// assert_eq!(common, vec![1, 3]);
+/// ```
+pub fn intersect_below<T, F>(a: &[T], b: &[T], bound: T, f: F)
+where
+    T: Copy + Ord,
+    F: FnMut(T),
+{
+    let a = &a[..a.partition_point(|x| *x < bound)];
+    let b = &b[..b.partition_point(|x| *x < bound)];
+    intersect(a, b, f)
+}
+
+/// Intersects two sorted lists by walking them in lockstep.
+pub fn merge_intersect<T, F>(a: &[T], b: &[T], mut f: F)
+where
+    T: Copy + Ord,
+    F: FnMut(T),
+{
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                f(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+}
+
+/// Intersects two sorted lists by searching the elements of the shorter list
+/// in the longer list.
+pub fn galloping_intersect<T, F>(a: &[T], b: &[T], mut f: F)
+where
+    T: Copy + Ord,
+    F: FnMut(T),
+{
+    let (short, mut long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+
+    for x in short {
+        long = &long[gallop(long, x)..];
+
+        match long.first() {
+            None => break,
+            Some(y) if y == x => {
+                f(*x);
+                long = &long[1..];
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Intersects two lists by probing the elements of the longer list in a hash
+/// set of the shorter list. The lists do not need to be sorted, but must not
+/// contain duplicates. Elements are visited in the order of the longer list.
+pub fn hash_intersect<T, F>(a: &[T], b: &[T], mut f: F)
+where
+    T: Copy + Eq + Hash,
+    F: FnMut(T),
+{
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+
+    if short.is_empty() {
+        return;
+    }
+
+    let set = short.iter().collect::<AHashSet<_>>();
+
+    for x in long {
+        if set.contains(x) {
+            f(*x);
+        }
+    }
+}
+
+/// Returns the index of the first element in the sorted slice that is not
+/// smaller than `x`, using exponential search from the start of the slice.
+fn gallop<T: Ord>(slice: &[T], x: &T) -> usize {
+    let mut bound = 1;
+    while bound < slice.len() && slice[bound] < *x {
+        bound *= 2;
+    }
+
+    // `slice[bound / 2] < x` holds for all `bound > 1`
+    let lo = bound / 2;
+    let hi = usize::min(bound + 1, slice.len());
+
+    lo + slice[lo..hi].partition_point(|y| y < x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRATEGIES: [IntersectionStrategy; 3] = [
+        IntersectionStrategy::Merge,
+        IntersectionStrategy::Galloping,
+        IntersectionStrategy::Hash,
+    ];
+
+    fn collect(strategy: IntersectionStrategy, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut common = Vec::new();
+        intersect_with(strategy, a, b, |x| common.push(x));
+        common
+    }
+
+    #[test]
+    fn strategies_test() {
+        let a = (0..1000).filter(|x| x % 3 == 0).collect::<Vec<u32>>();
+        let b = (0..1000).filter(|x| x % 5 == 0).collect::<Vec<u32>>();
+        let expected = (0..1000).filter(|x| x % 15 == 0).collect::<Vec<u32>>();
+
+        for strategy in STRATEGIES {
+            assert_eq!(collect(strategy, &a, &b), expected, "{:?}", strategy);
+            assert_eq!(collect(strategy, &b, &a), expected, "{:?}", strategy);
+            assert_eq!(
+                collect(strategy, &a, &[]),
+                Vec::<u32>::new(),
+                "{:?}",
+                strategy
+            );
+        }
+    }
+
+    #[test]
+    fn skewed_test() {
+        let long = (0..10_000).collect::<Vec<u32>>();
+        let short = vec![0, 17, 4242, 9999, 10_001];
+
+        assert_eq!(
+            IntersectionStrategy::select(short.len(), long.len()),
+            IntersectionStrategy::Galloping
+        );
+        assert_eq!(intersection(&short, &long), vec![0, 17, 4242, 9999]);
+        assert_eq!(intersection(&long, &short), vec![0, 17, 4242, 9999]);
+    }
+
+    #[test]
+    fn duplicates_test() {
+        let a = [1, 1, 2, 2, 2, 3];
+        let b = [1, 2, 2, 4];
+
+        let mut merge = Vec::new();
+        merge_intersect(&a, &b, |x| merge.push(x));
+        let mut galloping = Vec::new();
+        galloping_intersect(&a, &b, |x| galloping.push(x));
+
+        assert_eq!(merge, vec![1, 2, 2]);
+        assert_eq!(galloping, vec![1, 2, 2]);
+    }
+
+    #[test]
+    fn count_below_test() {
+        let a = [0, 1, 2, 3, 4, 5];
+        let b = [1, 3, 5, 7];
+
+        assert_eq!(intersection_count_below(&a, &b, 0), 0);
+        assert_eq!(intersection_count_below(&a, &b, 3), 1);
+        assert_eq!(intersection_count_below(&a, &b, 4), 2);
+        assert_eq!(intersection_count_below(&a, &b, 100), 3);
+    }
+
+    #[test]
+    fn gallop_test() {
+        let slice = [1, 3, 5, 7, 9, 11, 13];
+
+        for x in 0..15 {
+            assert_eq!(gallop(&slice, &x), slice.partition_point(|y| *y < x));
+        }
+        assert_eq!(gallop(&[], &42), 0);
+    }
+}
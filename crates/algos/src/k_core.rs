@@ -0,0 +1,410 @@
+//! K-core decomposition.
+//!
+//! The k-core of a graph is the largest subgraph in which every node has at
+//! least `k` neighbors. The core number of a node is the largest `k` such that
+//! the node belongs to the k-core, the degeneracy of a graph is the largest
+//! core number.
+//!
+//! The decomposition peels the graph bucket by bucket [1]: every node is kept
+//! in the bucket of its current degree. All nodes in the bucket of `k` are
+//! removed in parallel, which decrements the degrees of their neighbors
+//! atomically. Neighbors whose degree drops to `k` are removed next, all other
+//! neighbors move to the bucket of their new degree. Once the bucket is empty,
+//! `k` increases to the next non-empty bucket. The removal is implemented on
+//! top of [`edge_map`](crate::frontier::edge_map).
+//!
+//! For directed graphs, the in-core (out-core) uses the in-degrees
+//! (out-degrees) of the nodes, i.e., every node of the k-in-core has at least
+//! `k` incoming edges from other nodes of the k-in-core.
+//!
+//! [1] Laxman Dhulipala, Guy E. Blelloch, Julian Shun:
+//! "Julienne: A Framework for Parallel Graph Algorithms using
+//! Work-efficient Bucketing", SPAA 2017
+use crate::frontier::{edge_map, EdgeMapFn, Frontier};
+use crate::prelude::*;
+use crate::view::{symmetric, transpose};
+
+use log::info;
+use rayon::prelude::*;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const UNASSIGNED: usize = usize::MAX;
+
+/// The result of a k-core decomposition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoreDecomposition {
+    /// The core number of each node.
+    pub core_numbers: Vec<usize>,
+    /// The largest core number.
+    pub degeneracy: usize,
+}
+
+/// Computes the core number of each node of an undirected graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// // A triangle with a tail
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 0), (2, 3)])
+///     .build();
+///
+/// let cores = k_core_decomposition(&graph);
+///
+/// assert_eq!(cores.core_numbers, vec![2, 2, 2, 1]);
+/// assert_eq!(cores.degeneracy, 2);
+/// ```
+pub fn k_core_decomposition<NI, G>(graph: &G) -> CoreDecomposition
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedDegrees<NI> + UndirectedNeighbors<NI> + Sync,
+{
+    peel(&symmetric(graph))
+}
+
+/// Computes the in-core number of each node of a directed graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 0), (0, 3), (1, 3), (2, 3)])
+///     .build();
+///
+/// assert_eq!(in_core_decomposition(&graph).core_numbers, vec![1, 1, 1, 1]);
+/// assert_eq!(out_core_decomposition(&graph).core_numbers, vec![1, 1, 1, 0]);
+/// ```
+pub fn in_core_decomposition<NI, G>(graph: &G) -> CoreDecomposition
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    peel(graph)
+}
+
+/// Computes the out-core number of each node of a directed graph.
+pub fn out_core_decomposition<NI, G>(graph: &G) -> CoreDecomposition
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    peel(&transpose(graph))
+}
+
+/// Removes a node, which decrements the degrees of its remaining neighbors.
+/// Each neighbor whose degree changed is returned once per round.
+struct Peel<'a> {
+    degrees: &'a [AtomicUsize],
+    cores: &'a [AtomicUsize],
+    rounds: &'a [AtomicUsize],
+    k: usize,
+    round: usize,
+}
+
+impl<NI: Idx> EdgeMapFn<NI> for Peel<'_> {
+    fn update_atomic(&self, _source: NI, target: NI) -> bool {
+        let k = self.k;
+        // A node whose degree reaches `k` is removed in the next round of the
+        // current bucket, so its degree is never decremented below `k`.
+        let decremented = self.degrees[target.index()]
+            .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |d| {
+                d.checked_sub(1).filter(|_| d > k)
+            })
+            .is_ok();
+
+        decremented && self.rounds[target.index()].swap(self.round, Ordering::AcqRel) != self.round
+    }
+
+    fn cond(&self, target: NI) -> bool {
+        self.cores[target.index()].load(Ordering::Relaxed) == UNASSIGNED
+            && self.degrees[target.index()].load(Ordering::Relaxed) > self.k
+    }
+}
+
+/// Peels the graph by in-degree, removing a node decrements the in-degree of
+/// its outgoing neighbors.
+fn peel<NI, G>(graph: &G) -> CoreDecomposition
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+
+    let degrees = (0..node_count)
+        .into_par_iter()
+        .map(|node| AtomicUsize::new(graph.in_degree(NI::new(node)).index()))
+        .collect::<Vec<_>>();
+    let cores = (0..node_count)
+        .map(|_| AtomicUsize::new(UNASSIGNED))
+        .collect::<Vec<_>>();
+
+    let rounds = (0..node_count)
+        .map(|_| AtomicUsize::new(UNASSIGNED))
+        .collect::<Vec<_>>();
+
+    let max_degree = degrees
+        .par_iter()
+        .map(|degree| degree.load(Ordering::Relaxed))
+        .max()
+        .unwrap_or(0);
+    let mut buckets = vec![Vec::new(); max_degree + 1];
+    for (node, degree) in degrees.iter().enumerate() {
+        buckets[degree.load(Ordering::Relaxed)].push(NI::new(node));
+    }
+
+    let mut round = 0;
+
+    for k in 0..buckets.len() {
+        // Nodes that moved to a lower bucket leave a stale entry behind.
+        let bucket = std::mem::take(&mut buckets[k])
+            .into_par_iter()
+            .filter(|node| {
+                cores[node.index()].load(Ordering::Relaxed) == UNASSIGNED
+                    && degrees[node.index()].load(Ordering::Relaxed) == k
+            })
+            .collect::<Vec<_>>();
+
+        let mut frontier = Frontier::from_nodes(node_count, bucket);
+
+        while !frontier.is_empty() {
+            frontier.for_each(|node| cores[node.index()].store(k, Ordering::Relaxed));
+
+            let peel = Peel {
+                degrees: &degrees,
+                cores: &cores,
+                rounds: &rounds,
+                k,
+                round,
+            };
+            round += 1;
+
+            let mut next = Vec::new();
+            for node in edge_map(graph, &frontier, &peel).to_vec() {
+                match degrees[node.index()].load(Ordering::Relaxed) {
+                    degree if degree == k => next.push(node),
+                    degree => buckets[degree].push(node),
+                }
+            }
+
+            frontier = Frontier::from_nodes(node_count, next);
+        }
+    }
+
+    let core_numbers = cores
+        .into_par_iter()
+        .map(AtomicUsize::into_inner)
+        .collect::<Vec<_>>();
+    let degeneracy = core_numbers.par_iter().copied().max().unwrap_or(0);
+
+    info!(
+        "Computed core numbers with degeneracy {} in {:?}",
+        degeneracy,
+        start.elapsed()
+    );
+
+    CoreDecomposition {
+        core_numbers,
+        degeneracy,
+    }
+}
+
+/// The k-core of an undirected graph, see [`KCoreOp`].
+pub struct KCore<NI: Idx> {
+    /// The subgraph induced by the nodes of the k-core.
+    pub graph: UndirectedCsrGraph<NI>,
+    /// The original id of each node in `graph`.
+    pub nodes: Vec<NI>,
+}
+
+pub trait KCoreOp<NI: Idx> {
+    /// Extracts the k-core of the graph, i.e., the subgraph induced by the
+    /// nodes with a core number of at least `k`, or returns `None` if the
+    /// k-core is empty.
+    ///
+    /// The nodes of the k-core are numbered in the order of their original
+    /// ids. Node values are not copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph::prelude::*;
+    ///
+    /// // A triangle with a tail
+    /// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+    ///     .edges(vec![(3, 0), (0, 1), (1, 2), (2, 0)])
+    ///     .build();
+    ///
+    /// let core = graph.k_core(2).unwrap();
+    ///
+    /// assert_eq!(core.nodes, vec![0, 1, 2]);
+    /// assert_eq!(core.graph.edge_count(), 3);
+    /// assert!(graph.k_core(3).is_none());
+    /// ```
+    fn k_core(&self, k: usize) -> Option<KCore<NI>>;
+}
+
+impl<NI, NV> KCoreOp<NI> for UndirectedCsrGraph<NI, NV>
+where
+    NI: Idx,
+    NV: Send + Sync,
+{
+    fn k_core(&self, k: usize) -> Option<KCore<NI>> {
+        let start = Instant::now();
+
+        let core_numbers = k_core_decomposition(self).core_numbers;
+
+        let nodes = (0..core_numbers.len())
+            .into_par_iter()
+            .filter(|node| core_numbers[*node] >= k)
+            .map(NI::new)
+            .collect::<Vec<_>>();
+
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let mut ids = vec![UNASSIGNED; core_numbers.len()];
+        for (id, node) in nodes.iter().enumerate() {
+            ids[node.index()] = id;
+        }
+
+        let edges = nodes
+            .par_iter()
+            .flat_map_iter(|&node| {
+                let ids = &ids;
+                let source = ids[node.index()];
+                let mut self_loop = false;
+
+                // Every edge is contained in the neighbor lists of both of its
+                // nodes, including self loops, which appear twice.
+                self.neighbors(node)
+                    .filter(move |target| {
+                        if **target == node {
+                            self_loop = !self_loop;
+                            self_loop
+                        } else {
+                            **target > node
+                        }
+                    })
+                    .filter(move |target| ids[target.index()] != UNASSIGNED)
+                    .map(move |target| (NI::new(source), NI::new(ids[target.index()]), ()))
+            })
+            .collect::<Vec<_>>();
+
+        let edge_list = EdgeList::with_max_node_id(edges, NI::new(nodes.len() - 1));
+        let graph = UndirectedCsrGraph::from((edge_list, CsrLayout::Sorted));
+
+        info!(
+            "Extracted {}-core with {} nodes in {:?}",
+            k,
+            nodes.len(),
+            start.elapsed()
+        );
+
+        Some(KCore { graph, nodes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Repeatedly removes a node of minimum degree.
+    fn sequential_core_numbers(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+        let mut neighbors = vec![Vec::new(); node_count];
+        for (u, v) in edges {
+            neighbors[*u].push(*v);
+            neighbors[*v].push(*u);
+        }
+
+        let mut degrees = neighbors.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut cores = vec![UNASSIGNED; node_count];
+        let mut k = 0;
+
+        for _ in 0..node_count {
+            let node = (0..node_count)
+                .filter(|node| cores[*node] == UNASSIGNED)
+                .min_by_key(|node| degrees[*node])
+                .unwrap();
+
+            k = usize::max(k, degrees[node]);
+            cores[node] = k;
+
+            for neighbor in &neighbors[node] {
+                degrees[*neighbor] -= 1;
+            }
+        }
+
+        cores
+    }
+
+    #[test]
+    fn clique_test() {
+        // A clique of size 4 with a tail attached to it
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![
+                (0, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (1, 3),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+            ])
+            .build();
+
+        let cores = k_core_decomposition(&graph);
+
+        assert_eq!(cores.core_numbers, vec![3, 3, 3, 3, 1, 1]);
+        assert_eq!(cores.degeneracy, 3);
+
+        let core = graph.k_core(3).unwrap();
+        assert_eq!(core.nodes, vec![0, 1, 2, 3]);
+        assert_eq!(core.graph.node_count(), 4);
+        assert_eq!(core.graph.edge_count(), 6);
+        assert!((0..4).all(|node| core.graph.degree(node) == 3));
+
+        assert_eq!(graph.k_core(0).unwrap().graph.edge_count(), 8);
+    }
+
+    #[test]
+    fn random_graph_test() {
+        let node_count = 500;
+        let edges = (0..2000_usize)
+            .map(|i| (i * 7919 % node_count, (i * i * 31 + 17) % node_count))
+            .filter(|(u, v)| u != v)
+            .collect::<Vec<_>>();
+
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new().edges(edges.clone()).build();
+
+        let expected = sequential_core_numbers(graph.node_count(), &edges);
+        let cores = k_core_decomposition(&graph);
+
+        assert_eq!(cores.core_numbers, expected);
+        assert_eq!(cores.degeneracy, expected.into_iter().max().unwrap());
+    }
+
+    #[test]
+    fn directed_test() {
+        // 0 -> 1 -> 2 -> 0 is a cycle, 3 points to all of them.
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2), (2, 0), (3, 0), (3, 1), (3, 2)])
+            .build();
+
+        let in_cores = in_core_decomposition(&graph);
+        assert_eq!(in_cores.core_numbers, vec![1, 1, 1, 0]);
+
+        let out_cores = out_core_decomposition(&graph);
+        assert_eq!(out_cores.core_numbers, vec![1, 1, 1, 1]);
+        assert_eq!(out_cores.degeneracy, 1);
+    }
+}
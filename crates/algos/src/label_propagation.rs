@@ -0,0 +1,398 @@
+//! Label propagation community detection [1].
+//!
+//! Every node starts with its own label, or a seed label, and repeatedly
+//! adopts the label with the highest total edge weight among its neighbors.
+//! Densely connected groups of nodes quickly agree on a single label, which
+//! identifies their community. The algorithm stops if no label changes or
+//! after a maximum number of iterations.
+//!
+//! Ties between labels are broken by a hash of the label, which avoids that a
+//! single label, e.g., the smallest one, spreads through the whole graph.
+//! Without a seed, all nodes update their labels in parallel in place, which
+//! converges quickly but is not deterministic. With a seed, the labels are
+//! hashed with the seed and each iteration updates two halves of the nodes,
+//! selected by a seeded hash, one after the other. The result then only
+//! depends on the graph and the seed.
+//!
+//! [1] Usha Nandini Raghavan, Réka Albert, Soundar Kumara:
+//! "Near linear time algorithm to detect community structures in large-scale
+//! networks", Physical Review E, 2007
+use crate::prelude::*;
+use crate::sampling::split_mix;
+
+use log::info;
+use rayon::prelude::*;
+
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+const CHUNK_SIZE: usize = 1024;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct LabelPropagationConfig {
+    /// The maximum number of label propagation iterations.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = LabelPropagationConfig::DEFAULT_MAX_ITERATIONS))]
+    pub max_iterations: usize,
+
+    /// Makes the result deterministic by updating nodes in
+    /// a seeded order and breaking ties by a seeded hash.
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub seed: Option<u64>,
+}
+
+impl Default for LabelPropagationConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: Self::DEFAULT_MAX_ITERATIONS,
+            seed: None,
+        }
+    }
+}
+
+impl LabelPropagationConfig {
+    pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+    /// The seed of the hash that breaks ties if no seed is given.
+    pub const DEFAULT_TIE_BREAKING_SEED: u64 = 0x5EED;
+
+    pub fn new(max_iterations: usize, seed: Option<u64>) -> Self {
+        Self {
+            max_iterations,
+            seed,
+        }
+    }
+}
+
+/// A graph whose edges are treated as undirected and weighted, see
+/// [`EdgeWeight`]. Graphs without edge values have unit weights.
+///
+/// The trait is implemented for all graphs that provide
+/// [`UndirectedNeighborsWithValues`]. Directed graphs can be used through the
+/// [`undirected`](crate::view::undirected) view, which combines the
+/// outgoing and the incoming edges of a node.
+pub trait WeightedNeighbors<NI: Idx, EV>: Graph<NI> + Sync {
+    /// Calls `f` with each neighbor of the node and the weight of the edge.
+    fn for_each_weighted_neighbor<F>(&self, node: NI, f: F)
+    where
+        F: FnMut(NI, f64);
+}
+
+impl<NI, EV, G> WeightedNeighbors<NI, EV> for G
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+{
+    fn for_each_weighted_neighbor<F>(&self, node: NI, mut f: F)
+    where
+        F: FnMut(NI, f64),
+    {
+        for target in self.neighbors_with_values(node) {
+            f(target.target, target.value.weight());
+        }
+    }
+}
+
+/// Detects communities using label propagation where each node starts with
+/// its own id as label.
+///
+/// Returns the label of each node. Nodes with the same label belong to the
+/// same community.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// // Two triangles connected by a single edge
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)])
+///     .build();
+///
+/// let config = LabelPropagationConfig::new(10, Some(42));
+/// let labels = label_propagation(&graph, config);
+///
+/// assert!(labels[0] == labels[1] && labels[1] == labels[2]);
+/// assert!(labels[3] == labels[4] && labels[4] == labels[5]);
+/// assert_ne!(labels[0], labels[3]);
+/// ```
+pub fn label_propagation<NI, EV, G>(graph: &G, config: LabelPropagationConfig) -> Vec<NI>
+where
+    NI: Idx,
+    G: WeightedNeighbors<NI, EV>,
+{
+    let labels = (0..graph.node_count().index())
+        .into_par_iter()
+        .map(NI::new)
+        .collect::<Vec<_>>();
+
+    propagate(graph, labels, config)
+}
+
+/// Detects communities using label propagation where each node starts with
+/// its node value as label, e.g., to extend known communities to the rest of
+/// the graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32, u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 3)])
+///     .node_values(vec![7, 7, 7, 3])
+///     .build();
+///
+/// let labels = seeded_label_propagation(&graph, LabelPropagationConfig::default());
+///
+/// assert_eq!(labels, vec![7, 7, 7, 7]);
+/// ```
+pub fn seeded_label_propagation<NI, EV, G>(graph: &G, config: LabelPropagationConfig) -> Vec<NI>
+where
+    NI: Idx,
+    G: WeightedNeighbors<NI, EV> + NodeValues<NI, NI>,
+{
+    let labels = (0..graph.node_count().index())
+        .into_par_iter()
+        .map(|node| *graph.node_value(NI::new(node)))
+        .collect::<Vec<_>>();
+
+    propagate(graph, labels, config)
+}
+
+fn propagate<NI, EV, G>(graph: &G, labels: Vec<NI>, config: LabelPropagationConfig) -> Vec<NI>
+where
+    NI: Idx,
+    G: WeightedNeighbors<NI, EV>,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+    let labels = labels.into_iter().map(Atomic::new).collect::<Vec<_>>();
+
+    let mut iteration = 0;
+
+    while iteration < config.max_iterations {
+        let iteration_start = Instant::now();
+
+        let changed = match config.seed {
+            None => (0..node_count)
+                .into_par_iter()
+                .with_min_len(CHUNK_SIZE)
+                .map_init(Vec::new, |neighbors, node| {
+                    let node = NI::new(node);
+                    let seed = LabelPropagationConfig::DEFAULT_TIE_BREAKING_SEED;
+                    let label = best_label(graph, &labels, node, neighbors, seed);
+                    let changed = label != labels[node.index()].load(Ordering::Relaxed);
+                    if changed {
+                        labels[node.index()].store(label, Ordering::Relaxed);
+                    }
+                    changed
+                })
+                .filter(|changed| *changed)
+                .count(),
+            Some(seed) => (0..2)
+                .map(|half| {
+                    let half_seed = split_mix(seed ^ split_mix(iteration as u64));
+                    let updates = (0..node_count)
+                        .into_par_iter()
+                        .with_min_len(CHUNK_SIZE)
+                        .filter(|node| split_mix(half_seed ^ *node as u64) & 1 == half)
+                        .map_init(Vec::new, |neighbors, node| {
+                            let node = NI::new(node);
+                            (node, best_label(graph, &labels, node, neighbors, seed))
+                        })
+                        .filter(|(node, label)| {
+                            *label != labels[node.index()].load(Ordering::Relaxed)
+                        })
+                        .collect::<Vec<_>>();
+
+                    updates.par_iter().for_each(|(node, label)| {
+                        labels[node.index()].store(*label, Ordering::Relaxed)
+                    });
+
+                    updates.len()
+                })
+                .sum(),
+        };
+
+        iteration += 1;
+
+        info!(
+            "Finished iteration {} with {} label changes in {:?}",
+            iteration,
+            changed,
+            iteration_start.elapsed()
+        );
+
+        if changed == 0 {
+            break;
+        }
+    }
+
+    info!(
+        "Computed label propagation in {} iterations in {:?}",
+        iteration,
+        start.elapsed()
+    );
+
+    labels.into_iter().map(Atomic::into_inner).collect()
+}
+
+/// Returns the label with the highest total weight among the neighbors of the
+/// node. The current label of the node wins ties, other ties are broken by a
+/// seeded hash of the label.
+fn best_label<NI, EV, G>(
+    graph: &G,
+    labels: &[Atomic<NI>],
+    node: NI,
+    neighbors: &mut Vec<(NI, f64)>,
+    seed: u64,
+) -> NI
+where
+    NI: Idx,
+    G: WeightedNeighbors<NI, EV>,
+{
+    neighbors.clear();
+    graph.for_each_weighted_neighbor(node, |neighbor, weight| {
+        neighbors.push((labels[neighbor.index()].load(Ordering::Relaxed), weight));
+    });
+    neighbors.sort_unstable_by_key(|(label, _)| *label);
+
+    let current = labels[node.index()].load(Ordering::Relaxed);
+    let priority = |label: NI| split_mix(seed ^ label.index() as u64);
+
+    let mut best = (current, 0.0);
+    let mut i = 0;
+
+    while i < neighbors.len() {
+        let label = neighbors[i].0;
+        let mut weight = 0.0;
+        while i < neighbors.len() && neighbors[i].0 == label {
+            weight += neighbors[i].1;
+            i += 1;
+        }
+
+        let better = weight > best.1
+            || (weight == best.1
+                && best.0 != current
+                && (label == current || priority(label) > priority(best.0)));
+
+        if better {
+            best = (label, weight);
+        }
+    }
+
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::undirected;
+
+    fn same_community<NI: Idx>(labels: &[NI], nodes: &[usize]) -> bool {
+        nodes.iter().all(|node| labels[*node] == labels[nodes[0]])
+    }
+
+    // Two cliques of size 8 connected by the edge (7, 8)
+    fn cliques() -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        for offset in [0, 8] {
+            for u in 0..8 {
+                for v in u + 1..8 {
+                    edges.push((offset + u, offset + v));
+                }
+            }
+        }
+        edges.push((7, 8));
+        edges
+    }
+
+    fn assert_cliques<NI: Idx>(labels: &[NI]) {
+        assert!(same_community(labels, &[0, 1, 2, 3, 4, 5, 6, 7]));
+        assert!(same_community(labels, &[8, 9, 10, 11, 12, 13, 14, 15]));
+        assert_ne!(labels[0], labels[15]);
+    }
+
+    #[test]
+    fn two_cliques_test() {
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new().edges(cliques()).build();
+
+        for seed in [None, Some(42), Some(1337)] {
+            let labels = label_propagation(&graph, LabelPropagationConfig::new(10, seed));
+            assert_cliques(&labels);
+        }
+    }
+
+    #[test]
+    fn deterministic_test() {
+        let edges = (0..1000_usize)
+            .flat_map(|n| [(n, (n * 7 + 1) % 1000), (n, (n * 13 + 5) % 1000)])
+            .collect::<Vec<_>>();
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+
+        let config = LabelPropagationConfig::new(20, Some(7));
+        let expected = label_propagation(&graph, config);
+
+        for _ in 0..5 {
+            assert_eq!(label_propagation(&graph, config), expected);
+        }
+    }
+
+    #[test]
+    fn directed_test() {
+        let edges = cliques()
+            .into_iter()
+            .map(|(u, v)| if u % 2 == 0 { (u, v) } else { (v, u) })
+            .collect::<Vec<_>>();
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+
+        let labels = label_propagation(
+            &undirected(&graph),
+            LabelPropagationConfig::new(10, Some(42)),
+        );
+        assert_cliques(&labels);
+    }
+
+    #[test]
+    fn weighted_test() {
+        // Node 6 is connected to both triangles, but stronger to the second.
+        let graph: UndirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .edges_with_values(vec![
+                (0, 1, 1.0),
+                (1, 2, 1.0),
+                (2, 0, 1.0),
+                (3, 4, 1.0),
+                (4, 5, 1.0),
+                (5, 3, 1.0),
+                (6, 0, 1.0),
+                (6, 1, 1.0),
+                (6, 3, 5.0),
+            ])
+            .build();
+
+        let labels = label_propagation(&graph, LabelPropagationConfig::new(10, Some(42)));
+
+        assert!(same_community(&labels, &[0, 1, 2]));
+        assert_eq!(labels[6], labels[3]);
+        assert_ne!(labels[6], labels[0]);
+    }
+
+    #[test]
+    fn seeded_test() {
+        let graph: UndirectedCsrGraph<usize, usize> = GraphBuilder::new()
+            .edges(cliques())
+            .node_values(vec![
+                100, 100, 100, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 200, 200, 200,
+            ])
+            .build();
+
+        let labels = seeded_label_propagation(&graph, LabelPropagationConfig::new(10, Some(1)));
+
+        assert_cliques(&labels);
+        assert_eq!(labels[0], 100);
+        assert_eq!(labels[15], 200);
+    }
+}
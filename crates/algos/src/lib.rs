@@ -141,10 +141,23 @@
 //! ```
 
 pub mod afforest;
+pub mod betweenness;
+pub mod bfs;
+pub mod closeness;
 pub mod dss;
+pub mod frontier;
+pub mod intersect;
+pub mod k_core;
+pub mod label_propagation;
+pub mod linalg;
+pub mod louvain;
 pub mod page_rank;
 pub mod prelude;
+pub mod sampling;
+pub mod scc;
+pub mod spectral;
 pub mod sssp;
 pub mod triangle_count;
 pub mod utils;
+pub mod view;
 pub mod wcc;
@@ -0,0 +1,495 @@
+//! Sparse matrix-vector multiplication (SpMV) over directed graphs.
+//!
+//! A directed graph with `n` nodes is treated as a sparse `n x n` adjacency
+//! matrix `A`, where `A[u][v]` is set for every edge `(u, v)`. Unweighted
+//! graphs use the multiplicative identity of the semiring for every entry,
+//! weighted graphs use the edge values.
+//!
+//! All multiplications compute `y = x A`, i.e.,
+//! `y[v] = ⊕ { A[u][v] ⊗ x[u] | (u, v) ∈ E }`, using the `⊕` and `⊗` of the
+//! given [`Semiring`]. The pull variants iterate the incoming neighbors of
+//! each node and need no synchronization. The push variants scatter the
+//! values along the outgoing edges and skip nodes where `x[u]` is zero, which
+//! is faster for sparse input vectors. Multiplication with the transposed
+//! matrix, i.e., `y = A x`, is done by wrapping the graph with
+//! [`transpose`](crate::view::transpose).
+//!
+//! # Example
+//!
+//! A few iterations of PageRank using pull-based SpMV:
+//!
+//! ```
+//! use graph::prelude::*;
+//! use graph::linalg::{normalize_by_out_degree, spmv_pull, PlusTimes};
+//!
+//! let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+//!     .edges(vec![(0, 1), (1, 2), (2, 0), (2, 1)])
+//!     .build();
+//!
+//! let n = graph.node_count() as usize;
+//! let damping_factor = 0.85;
+//!
+//! let mut scores = vec![1.0 / n as f64; n];
+//! let mut incoming = vec![0.0; n];
+//!
+//! for _ in 0..50 {
+//!     let mut contributions = scores.clone();
+//!     normalize_by_out_degree(&graph, &mut contributions);
+//!     spmv_pull(&graph, &contributions, &mut incoming, PlusTimes::default());
+//!
+//!     for (score, incoming) in scores.iter_mut().zip(incoming.iter()) {
+//!         *score = (1.0 - damping_factor) / n as f64 + damping_factor * incoming;
+//!     }
+//! }
+//!
+//! assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1E-6);
+//! assert!(scores[0] < scores[2]);
+//! ```
+use crate::prelude::*;
+
+use rayon::prelude::*;
+
+use std::marker::PhantomData;
+use std::sync::atomic::Ordering;
+
+/// A semiring defines the addition `⊕` and multiplication `⊗` used in SpMV.
+///
+/// `zero` must be the identity of `add` and annihilate `mul`, `one` must be
+/// the identity of `mul`.
+pub trait Semiring: Sync {
+    type Value: Copy + PartialEq + Send + Sync;
+
+    /// The identity of `add`.
+    fn zero(&self) -> Self::Value;
+
+    /// The identity of `mul`.
+    fn one(&self) -> Self::Value;
+
+    fn add(&self, a: Self::Value, b: Self::Value) -> Self::Value;
+
+    fn mul(&self, a: Self::Value, b: Self::Value) -> Self::Value;
+}
+
+/// The arithmetic semiring `(+, *)`, e.g., for PageRank.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlusTimes<T>(PhantomData<T>);
+
+/// The tropical semiring `(min, +)`, e.g., for shortest paths.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinPlus<T>(PhantomData<T>);
+
+/// The boolean semiring `(or, and)`, e.g., for reachability.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrAnd;
+
+/// Numeric types that can be used with [`PlusTimes`] and [`MinPlus`].
+pub trait Number: Copy + PartialEq + PartialOrd + Send + Sync {
+    const ZERO: Self;
+    const ONE: Self;
+    /// The largest value, i.e., infinity for floating point types.
+    const MAX: Self;
+
+    /// Adds two numbers, saturating at `MAX` for integer types.
+    fn add(self, other: Self) -> Self;
+
+    fn mul(self, other: Self) -> Self;
+}
+
+macro_rules! impl_number_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Number for $ty {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+                const MAX: Self = <$ty>::INFINITY;
+
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+
+                fn mul(self, other: Self) -> Self {
+                    self * other
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_number_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Number for $ty {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$ty>::MAX;
+
+                fn add(self, other: Self) -> Self {
+                    self.saturating_add(other)
+                }
+
+                fn mul(self, other: Self) -> Self {
+                    self.saturating_mul(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_number_float!(f32, f64);
+impl_number_int!(u32, u64, usize);
+
+impl<T: Number> Semiring for PlusTimes<T> {
+    type Value = T;
+
+    fn zero(&self) -> T {
+        T::ZERO
+    }
+
+    fn one(&self) -> T {
+        T::ONE
+    }
+
+    fn add(&self, a: T, b: T) -> T {
+        a.add(b)
+    }
+
+    fn mul(&self, a: T, b: T) -> T {
+        a.mul(b)
+    }
+}
+
+impl<T: Number> Semiring for MinPlus<T> {
+    type Value = T;
+
+    fn zero(&self) -> T {
+        T::MAX
+    }
+
+    fn one(&self) -> T {
+        T::ZERO
+    }
+
+    fn add(&self, a: T, b: T) -> T {
+        if b < a {
+            b
+        } else {
+            a
+        }
+    }
+
+    fn mul(&self, a: T, b: T) -> T {
+        a.add(b)
+    }
+}
+
+impl Semiring for OrAnd {
+    type Value = bool;
+
+    fn zero(&self) -> bool {
+        false
+    }
+
+    fn one(&self) -> bool {
+        true
+    }
+
+    fn add(&self, a: bool, b: bool) -> bool {
+        a || b
+    }
+
+    fn mul(&self, a: bool, b: bool) -> bool {
+        a && b
+    }
+}
+
+/// Computes `y = x A` by pulling the values of the incoming neighbors of each
+/// node. Every entry of `A` is `semiring.one()`.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::linalg::{spmv_pull, OrAnd};
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 3)])
+///     .build();
+///
+/// let x = vec![true, true, false, false];
+/// let mut y = vec![false; 4];
+/// spmv_pull(&graph, &x, &mut y, OrAnd);
+///
+/// assert_eq!(y, vec![false, true, true, false]);
+/// ```
+pub fn spmv_pull<NI, G, S>(graph: &G, x: &[S::Value], y: &mut [S::Value], semiring: S)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighbors<NI> + Sync,
+    S: Semiring,
+{
+    check_dimensions(graph, x, y);
+
+    y.par_iter_mut().enumerate().for_each(|(v, y)| {
+        *y = graph
+            .in_neighbors(NI::new(v))
+            .fold(semiring.zero(), |acc, u| semiring.add(acc, x[u.index()]));
+    });
+}
+
+/// Computes `y = x A` by pushing the value of each node with a non-zero
+/// entry in `x` to its outgoing neighbors. Every entry of `A` is
+/// `semiring.one()`.
+pub fn spmv_push<NI, G, S>(graph: &G, x: &[S::Value], y: &mut [S::Value], semiring: S)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighbors<NI> + Sync,
+    S: Semiring,
+{
+    check_dimensions(graph, x, y);
+
+    push(graph, x, y, &semiring, |u, scatter| {
+        graph
+            .out_neighbors(u)
+            .for_each(|v| scatter(*v, x[u.index()]))
+    });
+}
+
+/// Computes `y = x A` by pulling the values of the incoming neighbors of each
+/// node, where `A[u][v]` is the value of the edge `(u, v)`.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::linalg::{weighted_spmv_pull, MinPlus};
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .edges_with_values(vec![(0, 1, 1.0), (0, 2, 4.0), (1, 2, 2.0)])
+///     .build();
+///
+/// // One round of Bellman-Ford
+/// let x = vec![0.0, 1.0, f32::INFINITY];
+/// let mut y = vec![0.0; 3];
+/// weighted_spmv_pull(&graph, &x, &mut y, MinPlus::default());
+///
+/// assert_eq!(y, vec![f32::INFINITY, 1.0, 3.0]);
+/// ```
+pub fn weighted_spmv_pull<NI, G, S>(graph: &G, x: &[S::Value], y: &mut [S::Value], semiring: S)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, S::Value> + Sync,
+    S: Semiring,
+{
+    check_dimensions(graph, x, y);
+
+    y.par_iter_mut().enumerate().for_each(|(v, y)| {
+        *y = graph
+            .in_neighbors_with_values(NI::new(v))
+            .fold(semiring.zero(), |acc, u| {
+                semiring.add(acc, semiring.mul(u.value, x[u.target.index()]))
+            });
+    });
+}
+
+/// Computes `y = x A` by pushing the value of each node with a non-zero
+/// entry in `x` to its outgoing neighbors, where `A[u][v]` is the value of
+/// the edge `(u, v)`.
+pub fn weighted_spmv_push<NI, G, S>(graph: &G, x: &[S::Value], y: &mut [S::Value], semiring: S)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, S::Value> + Sync,
+    S: Semiring,
+{
+    check_dimensions(graph, x, y);
+
+    push(graph, x, y, &semiring, |u, scatter| {
+        graph
+            .out_neighbors_with_values(u)
+            .for_each(|v| scatter(v.target, semiring.mul(v.value, x[u.index()])))
+    });
+}
+
+fn push<NI, G, S, F>(graph: &G, x: &[S::Value], y: &mut [S::Value], semiring: &S, scatter_node: F)
+where
+    NI: Idx,
+    G: Graph<NI> + Sync,
+    S: Semiring,
+    F: Fn(NI, &dyn Fn(NI, S::Value)) + Sync,
+{
+    let zero = semiring.zero();
+    let acc = (0..y.len())
+        .into_par_iter()
+        .map(|_| Atomic::new(zero))
+        .collect::<Vec<_>>();
+
+    let scatter = |v: NI, value: S::Value| {
+        let _ = acc[v.index()].fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+            Some(semiring.add(current, value))
+        });
+    };
+
+    (0..graph.node_count().index())
+        .into_par_iter()
+        .filter(|u| x[*u] != zero)
+        .for_each(|u| scatter_node(NI::new(u), &scatter));
+
+    y.par_iter_mut()
+        .zip(acc.into_par_iter())
+        .for_each(|(y, acc)| *y = acc.into_inner());
+}
+
+fn check_dimensions<NI: Idx, G: Graph<NI>, T>(graph: &G, x: &[T], y: &[T]) {
+    let node_count = graph.node_count().index();
+    assert_eq!(x.len(), node_count, "x must have one entry per node");
+    assert_eq!(y.len(), node_count, "y must have one entry per node");
+}
+
+/// Divides the entry of each node by its out-degree, which turns `x A` into
+/// a random walk step on the adjacency matrix `A`. Entries of nodes without
+/// outgoing edges are set to zero.
+pub fn normalize_by_out_degree<NI, G, T>(graph: &G, x: &mut [T])
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + Sync,
+    T: Real,
+{
+    normalize(x, |node| graph.out_degree(node))
+}
+
+/// Divides the entry of each node by its in-degree. Entries of nodes without
+/// incoming edges are set to zero.
+pub fn normalize_by_in_degree<NI, G, T>(graph: &G, x: &mut [T])
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + Sync,
+    T: Real,
+{
+    normalize(x, |node| graph.in_degree(node))
+}
+
+fn normalize<NI, T, F>(x: &mut [T], degree: F)
+where
+    NI: Idx,
+    T: Real,
+    F: Fn(NI) -> NI + Sync,
+{
+    x.par_iter_mut().enumerate().for_each(|(node, value)| {
+        *value = value.div_degree(degree(NI::new(node)).index());
+    });
+}
+
+/// Floating point types that can be normalized by a degree.
+pub trait Real: Copy + Send + Sync {
+    /// Divides the value by the given degree, returns zero for degree zero.
+    fn div_degree(self, degree: usize) -> Self;
+}
+
+macro_rules! impl_real {
+    ($($ty:ty),*) => {
+        $(
+            impl Real for $ty {
+                fn div_degree(self, degree: usize) -> Self {
+                    if degree == 0 {
+                        0.0
+                    } else {
+                        self / degree as $ty
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_real!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::transpose;
+
+    fn graph() -> DirectedCsrGraph<usize, (), f64> {
+        GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![
+                (0, 1, 0.5),
+                (0, 2, 2.0),
+                (1, 2, 1.0),
+                (2, 0, 3.0),
+                (3, 2, 4.0),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn plus_times_test() {
+        let graph = graph();
+        let x = vec![1.0, 2.0, 3.0, 0.0];
+        let mut pull = vec![0.0; 4];
+        let mut push = vec![0.0; 4];
+
+        weighted_spmv_pull(&graph, &x, &mut pull, PlusTimes::default());
+        weighted_spmv_push(&graph, &x, &mut push, PlusTimes::default());
+
+        assert_eq!(pull, vec![9.0, 0.5, 4.0, 0.0]);
+        assert_eq!(push, pull);
+    }
+
+    #[test]
+    fn min_plus_test() {
+        let graph = graph();
+        let x = vec![0.0, f64::INFINITY, f64::INFINITY, 1.0];
+        let mut pull = vec![0.0; 4];
+        let mut push = vec![0.0; 4];
+
+        weighted_spmv_pull(&graph, &x, &mut pull, MinPlus::default());
+        weighted_spmv_push(&graph, &x, &mut push, MinPlus::default());
+
+        assert_eq!(pull, vec![f64::INFINITY, 0.5, 2.0, f64::INFINITY]);
+        assert_eq!(push, pull);
+    }
+
+    #[test]
+    fn unweighted_test() {
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (0, 2), (1, 2), (2, 0), (3, 2)])
+            .build();
+        let x = vec![1_u64, 2, 3, 4];
+        let mut pull = vec![0; 4];
+        let mut push = vec![0; 4];
+
+        spmv_pull(&graph, &x, &mut pull, PlusTimes::default());
+        spmv_push(&graph, &x, &mut push, PlusTimes::default());
+
+        assert_eq!(pull, vec![3, 1, 7, 0]);
+        assert_eq!(push, pull);
+
+        let x = vec![false, false, false, true];
+        let mut y = vec![false; 4];
+        spmv_push(&graph, &x, &mut y, OrAnd);
+        assert_eq!(y, vec![false, false, true, false]);
+    }
+
+    #[test]
+    fn transpose_test() {
+        let graph = graph();
+        let x = vec![1.0, 2.0, 3.0, 4.0];
+        let mut y = vec![0.0; 4];
+
+        weighted_spmv_pull(&transpose(&graph), &x, &mut y, PlusTimes::default());
+
+        assert_eq!(y, vec![7.0, 3.0, 3.0, 12.0]);
+    }
+
+    #[test]
+    fn normalize_test() {
+        let graph = graph();
+        let mut x = vec![1.0_f32; 4];
+
+        normalize_by_out_degree(&graph, &mut x);
+        assert_eq!(x, vec![0.5, 1.0, 1.0, 1.0]);
+
+        let mut x = vec![1.0_f32; 4];
+        normalize_by_in_degree(&graph, &mut x);
+        assert_eq!(x, vec![1.0, 1.0, 1.0 / 3.0, 0.0]);
+    }
+}
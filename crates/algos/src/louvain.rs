@@ -0,0 +1,593 @@
+//! Louvain community detection [1].
+//!
+//! Louvain greedily maximizes the modularity of a partition of the nodes of
+//! a weighted undirected graph. Each level consists of two phases:
+//!
+//! - In the local moving phase, every node moves to the neighboring
+//!   community that yields the highest modularity gain, until no node moves
+//!   or the gain drops below the tolerance. Nodes are moved in parallel.
+//!   To avoid that two nodes swap their singleton communities forever, a
+//!   node only moves from one singleton community to another if the target
+//!   has the smaller id [2].
+//! - In the aggregation phase, every community becomes a node of a new
+//!   [`UndirectedCsrGraph`]. The edge weights between two communities are
+//!   summed up, the edges within a community become a self loop.
+//!
+//! The next level runs on the aggregated graph. The algorithm stops if a level
+//! does not merge any nodes or after a maximum number of levels.
+//!
+//! [1] Vincent D. Blondel, Jean-Loup Guillaume, Renaud Lambiotte, Etienne Lefebvre:
+//! "Fast unfolding of communities in large networks",
+//! Journal of Statistical Mechanics, 2008
+//!
+//! [2] Hao Lu, Mahantesh Halappanavar, Ananth Kalyanaraman:
+//! "Parallel heuristics for scalable community detection",
+//! Parallel Computing, 2015
+use crate::prelude::*;
+
+use ahash::AHashMap;
+use atomic_float::AtomicF64;
+use log::info;
+use rayon::prelude::*;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct LouvainConfig {
+    /// The maximum number of levels, i.e., aggregations.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = LouvainConfig::DEFAULT_MAX_LEVELS))]
+    pub max_levels: usize,
+
+    /// The maximum number of local moving iterations per
+    /// level.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = LouvainConfig::DEFAULT_MAX_ITERATIONS))]
+    pub max_iterations: usize,
+
+    /// If the modularity gain of a local moving iteration
+    /// is below the tolerance value, the level stops.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = LouvainConfig::DEFAULT_TOLERANCE))]
+    pub tolerance: f64,
+
+    /// Higher values lead to more and smaller communities.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = LouvainConfig::DEFAULT_RESOLUTION))]
+    pub resolution: f64,
+}
+
+impl Default for LouvainConfig {
+    fn default() -> Self {
+        Self {
+            max_levels: Self::DEFAULT_MAX_LEVELS,
+            max_iterations: Self::DEFAULT_MAX_ITERATIONS,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            resolution: Self::DEFAULT_RESOLUTION,
+        }
+    }
+}
+
+impl LouvainConfig {
+    pub const DEFAULT_MAX_LEVELS: usize = 10;
+    pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+    pub const DEFAULT_TOLERANCE: f64 = 1E-6;
+    pub const DEFAULT_RESOLUTION: f64 = 1.0;
+
+    pub fn new(max_levels: usize, max_iterations: usize, tolerance: f64, resolution: f64) -> Self {
+        Self {
+            max_levels,
+            max_iterations,
+            tolerance,
+            resolution,
+        }
+    }
+}
+
+/// The result of [`louvain`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LouvainResult<NI> {
+    /// The community of each node of the input graph after each level.
+    /// Community ids are in `0..community_count`. Contains at least one
+    /// level.
+    pub levels: Vec<Vec<NI>>,
+    /// The modularity of the communities of the last level.
+    pub modularity: f64,
+}
+
+impl<NI: Idx> LouvainResult<NI> {
+    /// Returns the community of each node after the last level.
+    pub fn communities(&self) -> &[NI] {
+        self.levels.last().expect("at least one level")
+    }
+
+    /// Returns the number of communities after the last level.
+    pub fn community_count(&self) -> usize {
+        self.communities()
+            .par_iter()
+            .map(|community| community.index() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Detects communities by maximizing modularity using Louvain.
+///
+/// Edge weights, see [`EdgeWeight`], must not be negative. The reported
+/// modularity uses a resolution of `1.0`, see [`modularity`].
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// // Two triangles connected by a single edge
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)])
+///     .build();
+///
+/// let result = louvain(&graph, LouvainConfig::default());
+///
+/// assert_eq!(result.communities(), &[0, 0, 0, 1, 1, 1]);
+/// assert!((result.modularity - 5.0 / 14.0).abs() < 1E-9);
+/// ```
+pub fn louvain<NI, EV, G>(graph: &G, config: LouvainConfig) -> LouvainResult<NI>
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+
+    let mut levels = Vec::new();
+    let mut assignment = (0..node_count).collect::<Vec<_>>();
+    let mut aggregated: Option<UndirectedCsrGraph<NI, (), f64>> = None;
+
+    while levels.len() < config.max_levels {
+        let level = match &aggregated {
+            None => level(graph, config),
+            Some(aggregated) => level(aggregated, config),
+        };
+
+        let (communities, next) = match level {
+            Some(level) => level,
+            None => break,
+        };
+
+        assignment
+            .par_iter_mut()
+            .for_each(|community| *community = communities[*community]);
+        levels.push(
+            assignment
+                .par_iter()
+                .map(|c| NI::new(*c))
+                .collect::<Vec<_>>(),
+        );
+
+        info!(
+            "Finished level {} with {} communities",
+            levels.len(),
+            next.node_count().index()
+        );
+
+        aggregated = Some(next);
+    }
+
+    if levels.is_empty() {
+        levels.push((0..node_count).into_par_iter().map(NI::new).collect());
+    }
+
+    let modularity = modularity(graph, levels.last().expect("at least one level"));
+
+    info!(
+        "Computed Louvain with {} levels and a modularity of {:.6} in {:?}",
+        levels.len(),
+        modularity,
+        start.elapsed()
+    );
+
+    LouvainResult { levels, modularity }
+}
+
+/// Computes the modularity of a partition of the nodes of a weighted
+/// undirected graph, where `communities` contains an arbitrary community id
+/// for each node.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (2, 3)])
+///     .build();
+///
+/// assert_eq!(modularity(&graph, &[0, 0, 2, 2]), 0.5);
+/// assert_eq!(modularity(&graph, &[0, 0, 0, 0]), 0.0);
+/// ```
+pub fn modularity<NI, EV, G>(graph: &G, communities: &[NI]) -> f64
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+{
+    assert_eq!(
+        communities.len(),
+        graph.node_count().index(),
+        "communities must contain one id per node"
+    );
+
+    modularity_with(graph, |node| communities[node.index()].index(), 1.0)
+}
+
+fn modularity_with<NI, EV, G, F>(graph: &G, community: F, resolution: f64) -> f64
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+    F: Fn(NI) -> usize + Sync,
+{
+    let (internal, totals) = (0..graph.node_count().index())
+        .into_par_iter()
+        .map(NI::new)
+        .fold(
+            || (0.0, AHashMap::<usize, f64>::new()),
+            |(mut internal, mut totals), node| {
+                let own = community(node);
+                let mut degree = 0.0;
+                for target in graph.neighbors_with_values(node) {
+                    let weight = target.value.weight();
+                    degree += weight;
+                    if community(target.target) == own {
+                        internal += weight;
+                    }
+                }
+                *totals.entry(own).or_insert(0.0) += degree;
+                (internal, totals)
+            },
+        )
+        .reduce(
+            || (0.0, AHashMap::new()),
+            |(internal_a, mut totals_a), (internal_b, totals_b)| {
+                for (community, total) in totals_b {
+                    *totals_a.entry(community).or_insert(0.0) += total;
+                }
+                (internal_a + internal_b, totals_a)
+            },
+        );
+
+    let total_weight = totals.values().sum::<f64>();
+    if total_weight == 0.0 {
+        return 0.0;
+    }
+
+    let expected = totals
+        .values()
+        .map(|total| (total / total_weight).powi(2))
+        .sum::<f64>();
+
+    internal / total_weight - resolution * expected
+}
+
+/// Runs the local moving phase on the graph and aggregates the communities.
+/// Returns the dense community id of each node and the aggregated graph or
+/// `None` if no nodes have been merged.
+fn level<NI, EV, G>(
+    graph: &G,
+    config: LouvainConfig,
+) -> Option<(Vec<usize>, UndirectedCsrGraph<NI, (), f64>)>
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+{
+    let node_count = graph.node_count().index();
+    let communities = local_moving(graph, config);
+
+    // Renumber communities in order of their first node.
+    let mut ids = vec![usize::MAX; node_count];
+    let mut community_count = 0;
+    let communities = communities
+        .into_iter()
+        .map(|community| {
+            if ids[community] == usize::MAX {
+                ids[community] = community_count;
+                community_count += 1;
+            }
+            ids[community]
+        })
+        .collect::<Vec<_>>();
+
+    if community_count == node_count {
+        return None;
+    }
+
+    let aggregated = aggregate(graph, &communities, community_count);
+
+    Some((communities, aggregated))
+}
+
+fn local_moving<NI, EV, G>(graph: &G, config: LouvainConfig) -> Vec<usize>
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+    let resolution = config.resolution;
+
+    let degrees = (0..node_count)
+        .into_par_iter()
+        .map(|node| {
+            graph
+                .neighbors_with_values(NI::new(node))
+                .map(|target| target.value.weight())
+                .sum::<f64>()
+        })
+        .collect::<Vec<_>>();
+
+    let total_weight = degrees.par_iter().sum::<f64>();
+
+    let communities = (0..node_count).map(AtomicUsize::new).collect::<Vec<_>>();
+    if total_weight == 0.0 {
+        return communities
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect();
+    }
+
+    let totals = degrees
+        .iter()
+        .map(|degree| AtomicF64::new(*degree))
+        .collect::<Vec<_>>();
+    let sizes = (0..node_count)
+        .map(|_| AtomicUsize::new(1))
+        .collect::<Vec<_>>();
+
+    let community = |node: NI| communities[node.index()].load(Ordering::Relaxed);
+    let mut modularity = modularity_with(graph, community, resolution);
+
+    for iteration in 0..config.max_iterations {
+        let moves = (0..node_count)
+            .into_par_iter()
+            .map_init(AHashMap::<usize, f64>::new, |weights, node| {
+                let own = communities[node].load(Ordering::Relaxed);
+                let degree = degrees[node];
+
+                weights.clear();
+                for target in graph.neighbors_with_values(NI::new(node)) {
+                    if target.target.index() != node {
+                        *weights.entry(community(target.target)).or_insert(0.0) +=
+                            target.value.weight();
+                    }
+                }
+
+                // The gain of joining a community, up to constant factors,
+                // after removing the node from its own community.
+                let gain = |community: usize, weight: f64| {
+                    let mut total = totals[community].load(Ordering::Relaxed);
+                    if community == own {
+                        total -= degree;
+                    }
+                    weight - resolution * degree * total / total_weight
+                };
+
+                let own_weight = weights.get(&own).copied().unwrap_or(0.0);
+                let mut best = (own, gain(own, own_weight));
+
+                for (&community, &weight) in weights.iter() {
+                    if community == own {
+                        continue;
+                    }
+                    let gain = gain(community, weight);
+                    if gain > best.1 || (gain == best.1 && best.0 != own && community < best.0) {
+                        best = (community, gain);
+                    }
+                }
+
+                let (target, _) = best;
+                let singletons = sizes[own].load(Ordering::Relaxed) == 1
+                    && sizes[target].load(Ordering::Relaxed) == 1;
+
+                if target == own || (singletons && target > own) {
+                    return false;
+                }
+
+                communities[node].store(target, Ordering::Relaxed);
+                totals[own].fetch_sub(degree, Ordering::Relaxed);
+                totals[target].fetch_add(degree, Ordering::Relaxed);
+                sizes[own].fetch_sub(1, Ordering::Relaxed);
+                sizes[target].fetch_add(1, Ordering::Relaxed);
+
+                true
+            })
+            .filter(|moved| *moved)
+            .count();
+
+        let next_modularity = modularity_with(graph, community, resolution);
+        let gain = next_modularity - modularity;
+        modularity = next_modularity;
+
+        info!(
+            "Finished local moving iteration {} with {} moves and a modularity of {:.6}",
+            iteration, moves, modularity
+        );
+
+        if moves == 0 || gain < config.tolerance {
+            break;
+        }
+    }
+
+    info!("Finished local moving in {:?}", start.elapsed());
+
+    communities
+        .into_iter()
+        .map(AtomicUsize::into_inner)
+        .collect()
+}
+
+/// Builds a graph with one node per community. The weight of an edge between
+/// two communities is the total weight of the edges between their nodes. The
+/// edges within a community become a self loop, which is stored twice in the
+/// neighbor list of the community and thus keeps its total degree.
+fn aggregate<NI, EV, G>(
+    graph: &G,
+    communities: &[usize],
+    community_count: usize,
+) -> UndirectedCsrGraph<NI, (), f64>
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV> + Sync,
+{
+    let start = Instant::now();
+
+    let mut offsets = vec![0_usize; community_count + 1];
+    for community in communities {
+        offsets[community + 1] += 1;
+    }
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+
+    let mut members = vec![0_usize; communities.len()];
+    let mut next = offsets.clone();
+    for (node, community) in communities.iter().enumerate() {
+        members[next[*community]] = node;
+        next[*community] += 1;
+    }
+
+    let edges = (0..community_count)
+        .into_par_iter()
+        .map_init(AHashMap::<usize, f64>::new, |weights, community| {
+            weights.clear();
+            for node in &members[offsets[community]..offsets[community + 1]] {
+                for target in graph.neighbors_with_values(NI::new(*node)) {
+                    *weights
+                        .entry(communities[target.target.index()])
+                        .or_insert(0.0) += target.value.weight();
+                }
+            }
+
+            weights
+                .iter()
+                .filter(|(other, _)| **other >= community)
+                .map(|(&other, &weight)| {
+                    let weight = if other == community {
+                        weight / 2.0
+                    } else {
+                        weight
+                    };
+                    (NI::new(community), NI::new(other), weight)
+                })
+                .collect::<Vec<_>>()
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let edge_list = EdgeList::with_max_node_id(edges, NI::new(community_count - 1));
+    let aggregated = UndirectedCsrGraph::from((edge_list, CsrLayout::Sorted));
+
+    info!(
+        "Aggregated {} nodes into {} communities in {:?}",
+        communities.len(),
+        community_count,
+        start.elapsed()
+    );
+
+    aggregated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_triangles() -> UndirectedCsrGraph<usize> {
+        GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)])
+            .build()
+    }
+
+    #[test]
+    fn modularity_test() {
+        let graph = two_triangles();
+
+        let partition = modularity(&graph, &[0, 0, 0, 1, 1, 1]);
+        assert!((partition - (6.0 / 7.0 - 0.5)).abs() < 1E-9);
+
+        let singletons = modularity(&graph, &[0, 1, 2, 3, 4, 5]);
+        let expected = -[2.0, 2.0, 3.0, 3.0, 2.0, 2.0_f64]
+            .iter()
+            .map(|degree| (degree / 14.0).powi(2))
+            .sum::<f64>();
+        assert!((singletons - expected).abs() < 1E-9);
+    }
+
+    #[test]
+    fn aggregate_test() {
+        let graph = two_triangles();
+        let communities = [0, 0, 0, 1, 1, 1];
+
+        let aggregated = aggregate(&graph, &communities, 2);
+
+        assert_eq!(aggregated.node_count(), 2);
+        let expected = modularity(&graph, &[0, 0, 0, 1, 1, 1]);
+        assert!((modularity(&aggregated, &[0, 1]) - expected).abs() < 1E-9);
+        assert_eq!(modularity(&aggregated, &[0, 0]), 0.0);
+    }
+
+    #[test]
+    fn ring_of_cliques_test() {
+        // Six cliques of size 4 connected in a ring
+        let mut edges = Vec::new();
+        for clique in 0..6 {
+            let offset = clique * 4;
+            for u in 0..4 {
+                for v in u + 1..4 {
+                    edges.push((offset + u, offset + v));
+                }
+            }
+            edges.push((offset + 3, (offset + 4) % 24));
+        }
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+
+        let result = louvain(&graph, LouvainConfig::default());
+
+        assert_eq!(result.community_count(), 6);
+        for (node, community) in result.communities().iter().enumerate() {
+            assert_eq!(*community, node / 4);
+        }
+        assert!((result.modularity - (6.0 / 7.0 - 1.0 / 6.0)).abs() < 1E-9);
+    }
+
+    #[test]
+    fn weighted_test() {
+        // A square where the weights pull 0, 1 and 2, 3 together.
+        let graph: UndirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .edges_with_values(vec![(0, 1, 10.0), (1, 2, 1.0), (2, 3, 10.0), (3, 0, 1.0)])
+            .build();
+
+        let result = louvain(&graph, LouvainConfig::default());
+
+        assert_eq!(result.communities(), &[0, 0, 1, 1]);
+        assert!(result.modularity > 0.0);
+    }
+
+    #[test]
+    fn resolution_test() {
+        let graph = two_triangles();
+
+        let config = LouvainConfig {
+            resolution: 0.01,
+            ..LouvainConfig::default()
+        };
+        let result = louvain(&graph, config);
+
+        assert_eq!(result.community_count(), 1);
+        assert_eq!(result.modularity, 0.0);
+        assert!(result.levels.len() <= 2);
+    }
+}
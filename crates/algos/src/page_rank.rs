@@ -28,6 +28,13 @@ pub struct PageRankConfig {
     /// surfer will continue at any step.
     #[cfg_attr(feature = "clap", clap(long, default_value_t = PageRankConfig::DEFAULT_DAMPING_FACTOR))]
     pub damping_factor: f32,
+
+    /// Distributes the score of nodes without outgoing
+    /// edges according to the teleport distribution in
+    /// each iteration instead of dropping it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "clap", clap(long))]
+    pub redistribute_dangling: bool,
 }
 
 impl Default for PageRankConfig {
@@ -36,6 +43,7 @@ impl Default for PageRankConfig {
             max_iterations: Self::DEFAULT_MAX_ITERATIONS,
             tolerance: Self::DEFAULT_TOLERANCE,
             damping_factor: Self::DEFAULT_DAMPING_FACTOR,
+            redistribute_dangling: false,
         }
     }
 }
@@ -50,34 +58,213 @@ impl PageRankConfig {
             max_iterations,
             tolerance,
             damping_factor,
+            redistribute_dangling: false,
+        }
+    }
+}
+
+/// The distribution according to which the random surfer jumps to a node.
+#[derive(Copy, Clone, Debug)]
+pub enum Teleport<'a, NI> {
+    /// Jumps to every node with the same probability.
+    Uniform,
+    /// Jumps to one of the given nodes with the same probability.
+    Sources(&'a [NI]),
+    /// Jumps to every node with a probability proportional to its value.
+    /// The slice must contain one non-negative value per node.
+    Distribution(&'a [f32]),
+}
+
+impl<NI: Idx> Teleport<'_, NI> {
+    /// Returns the jump probability of each node or `None` if it is uniform.
+    fn probabilities(&self, node_count: usize) -> Option<Vec<f32>> {
+        match self {
+            Teleport::Uniform => None,
+            Teleport::Sources(sources) => {
+                assert!(!sources.is_empty(), "teleport sources must not be empty");
+
+                let mut sources = sources.to_vec();
+                sources.sort_unstable();
+                sources.dedup();
+
+                let probability = 1.0 / sources.len() as f32;
+                let mut probabilities = vec![0.0; node_count];
+                for source in sources {
+                    probabilities[source.index()] = probability;
+                }
+
+                Some(probabilities)
+            }
+            Teleport::Distribution(values) => {
+                assert_eq!(
+                    values.len(),
+                    node_count,
+                    "teleport distribution must contain one value per node"
+                );
+
+                let total = values.par_iter().sum::<f32>();
+                assert!(total > 0.0, "teleport distribution must not be zero");
+
+                Some(values.par_iter().map(|value| value / total).collect())
+            }
         }
     }
 }
 
+/// Computes the PageRank of each node using a uniform teleport distribution.
+///
+/// Returns the scores, the number of iterations and the error of the last
+/// iteration.
 pub fn page_rank<NI, G>(graph: &G, config: PageRankConfig) -> (Vec<f32>, usize, f64)
 where
     NI: Idx,
     G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    personalized_page_rank(graph, Teleport::Uniform, config)
+}
+
+/// Computes the PageRank of each node where the random surfer jumps according
+/// to the given teleport distribution, e.g., to a set of source nodes.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 0), (3, 2), (4, 0)])
+///     .build();
+///
+/// let (scores, _, _) =
+///     personalized_page_rank(&graph, Teleport::Sources(&[3]), PageRankConfig::default());
+///
+/// // Node 4 is not reachable from the source node.
+/// assert_eq!(scores[4], 0.0);
+/// assert!(scores[3] > 0.0);
+/// ```
+pub fn personalized_page_rank<NI, G>(
+    graph: &G,
+    teleport: Teleport<'_, NI>,
+    config: PageRankConfig,
+) -> (Vec<f32>, usize, f64)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    run(
+        graph,
+        teleport,
+        config,
+        |node| graph.out_degree(node).index() as f32,
+        |node, out_scores| {
+            graph
+                .in_neighbors(node)
+                .map(|v| unsafe { out_scores.add(v.index()).read() })
+                .sum::<f32>()
+        },
+    )
+}
+
+/// Computes the PageRank of each node where the random surfer follows an
+/// outgoing edge with a probability proportional to its non-negative value.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .edges_with_values(vec![(0, 1, 9.0), (0, 2, 1.0), (1, 0, 1.0), (2, 0, 1.0)])
+///     .build();
+///
+/// let (scores, _, _) = weighted_page_rank(&graph, Teleport::Uniform, PageRankConfig::default());
+///
+/// assert!(scores[1] > scores[2]);
+/// ```
+pub fn weighted_page_rank<NI, G>(
+    graph: &G,
+    teleport: Teleport<'_, NI>,
+    config: PageRankConfig,
+) -> (Vec<f32>, usize, f64)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, f32> + Sync,
+{
+    let out_weights = (0..graph.node_count().index())
+        .into_par_iter()
+        .map(|node| {
+            graph
+                .out_neighbors_with_values(NI::new(node))
+                .map(|target| target.value)
+                .sum::<f32>()
+        })
+        .collect::<Vec<_>>();
+
+    run(
+        graph,
+        teleport,
+        config,
+        |node| out_weights[node.index()],
+        |node, out_scores| {
+            graph
+                .in_neighbors_with_values(node)
+                .map(|v| unsafe { out_scores.add(v.target.index()).read() } * v.value)
+                .sum::<f32>()
+        },
+    )
+}
+
+/// Runs PageRank where `out_weight` returns the total weight of the outgoing
+/// edges of a node and `incoming` the sum of the weighted scores of the
+/// incoming neighbors, which are already divided by their `out_weight`.
+fn run<NI, G, W, I>(
+    graph: &G,
+    teleport: Teleport<'_, NI>,
+    config: PageRankConfig,
+    out_weight: W,
+    incoming: I,
+) -> (Vec<f32>, usize, f64)
+where
+    NI: Idx,
+    G: Graph<NI> + Sync,
+    W: Fn(NI) -> f32 + Sync,
+    I: Fn(NI, &SharedMut<f32>) -> f32 + Sync,
 {
     let PageRankConfig {
         max_iterations,
         tolerance,
         damping_factor,
+        redistribute_dangling,
     } = config;
 
     let node_count = graph.node_count().index();
     let init_score = 1_f32 / node_count as f32;
     let base_score = (1.0_f32 - damping_factor) / node_count as f32;
 
+    let teleport = teleport.probabilities(node_count);
+
+    let mut scores = match &teleport {
+        Some(probabilities) => probabilities.clone(),
+        None => vec![init_score; node_count],
+    };
+
     let mut out_scores = Vec::with_capacity(node_count);
 
-    (0..node_count)
-        .into_par_iter()
-        .map(NI::new)
-        .map(|node| init_score / graph.out_degree(node).index() as f32)
+    scores
+        .par_iter()
+        .enumerate()
+        .map(|(node, score)| out_score(*score, out_weight(NI::new(node))))
         .collect_into_vec(&mut out_scores);
 
-    let mut scores = vec![init_score; node_count];
+    let dangling_nodes = if redistribute_dangling {
+        (0..node_count)
+            .into_par_iter()
+            .map(NI::new)
+            .filter(|node| out_weight(*node) == 0.0)
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
 
     let scores_ptr = SharedMut::new(scores.as_mut_ptr());
     let out_scores_ptr = SharedMut::new(out_scores.as_mut_ptr());
@@ -86,13 +273,40 @@ where
 
     loop {
         let start = Instant::now();
-        let error = page_rank_iteration(
-            graph,
-            base_score,
-            damping_factor,
-            &out_scores_ptr,
-            &scores_ptr,
-        );
+
+        // The score of dangling nodes is distributed like a teleport.
+        let dangling_score = damping_factor
+            * dangling_nodes
+                .par_iter()
+                .map(|node| unsafe { scores_ptr.add(node.index()).read() })
+                .sum::<f32>();
+
+        let error = match &teleport {
+            None => {
+                let jump_score = base_score + dangling_score / node_count as f32;
+                page_rank_iteration(
+                    graph,
+                    |_| jump_score,
+                    damping_factor,
+                    &out_weight,
+                    &incoming,
+                    &out_scores_ptr,
+                    &scores_ptr,
+                )
+            }
+            Some(probabilities) => {
+                let jump_score = 1.0 - damping_factor + dangling_score;
+                page_rank_iteration(
+                    graph,
+                    |node| jump_score * probabilities[node.index()],
+                    damping_factor,
+                    &out_weight,
+                    &incoming,
+                    &out_scores_ptr,
+                    &scores_ptr,
+                )
+            }
+        };
 
         info!(
             "Finished iteration {} with an error of {:.6} in {:?}",
@@ -109,16 +323,32 @@ where
     }
 }
 
-fn page_rank_iteration<NI, G>(
+/// Returns the share of the score that is sent along each unit of outgoing
+/// weight. Nodes without outgoing weight are dangling and send nothing, which
+/// also keeps edges with a weight of zero from reading an infinite score.
+fn out_score(score: f32, out_weight: f32) -> f32 {
+    if out_weight == 0.0 {
+        0.0
+    } else {
+        score / out_weight
+    }
+}
+
+fn page_rank_iteration<NI, G, J, W, I>(
     graph: &G,
-    base_score: f32,
+    jump_score: J,
     damping_factor: f32,
+    out_weight: &W,
+    incoming: &I,
     out_scores: &SharedMut<f32>,
     scores: &SharedMut<f32>,
 ) -> f64
 where
     NI: Idx,
-    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+    G: Graph<NI> + Sync,
+    J: Fn(NI) -> f32 + Sync,
+    W: Fn(NI) -> f32 + Sync,
+    I: Fn(NI, &SharedMut<f32>) -> f32 + Sync,
 {
     let next_chunk = Atomic::new(NI::zero());
     let total_error = AtomicF64::new(0_f64);
@@ -137,13 +367,10 @@ where
                     let end = (start + NI::new(CHUNK_SIZE)).min(graph.node_count());
 
                     for u in start..end {
-                        let incoming_total = graph
-                            .in_neighbors(u)
-                            .map(|v| unsafe { out_scores.add(v.index()).read() })
-                            .sum::<f32>();
+                        let incoming_total = incoming(u, out_scores);
 
                         let old_score = unsafe { scores.add(u.index()).read() };
-                        let new_score = base_score + damping_factor * incoming_total;
+                        let new_score = jump_score(u) + damping_factor * incoming_total;
 
                         unsafe { scores.add(u.index()).write(new_score) };
                         let diff = (new_score - old_score) as f64;
@@ -152,7 +379,7 @@ where
                         unsafe {
                             out_scores
                                 .add(u.index())
-                                .write(new_score / graph.out_degree(u).index() as f32)
+                                .write(out_score(new_score, out_weight(u)))
                         }
                     }
                 }
@@ -195,4 +422,118 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_pr_temporal_snapshot() {
+        let edges = vec![(0, 1, 1), (1, 2, 2), (2, 0, 3), (0, 2, 7), (2, 1, 9)];
+
+        let temporal: TemporalCsrGraph<usize, u32> =
+            GraphBuilder::new().edges_with_values(edges.clone()).build();
+
+        let window: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(
+                edges
+                    .into_iter()
+                    .filter(|(_, _, t)| (2..8).contains(t))
+                    .map(|(s, t, _)| (s, t)),
+            )
+            .build();
+
+        let expected = page_rank(&window, PageRankConfig::default());
+        let actual = page_rank(&temporal.snapshot(2, 8), PageRankConfig::default());
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_pr_redistribute_dangling() {
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (0, 2), (1, 2), (3, 0)])
+            .build();
+
+        let config = PageRankConfig {
+            max_iterations: 100,
+            tolerance: 1E-7,
+            redistribute_dangling: true,
+            ..PageRankConfig::default()
+        };
+
+        let (scores, _, _) = page_rank(&graph, config);
+        assert!((scores.iter().sum::<f32>() - 1.0).abs() < 1E-4);
+
+        let (scores, _, _) = personalized_page_rank(&graph, Teleport::Sources(&[3]), config);
+        assert!((scores.iter().sum::<f32>() - 1.0).abs() < 1E-4);
+        // Dangling mass flows back to the source.
+        assert!(scores[3] > 0.15);
+    }
+
+    #[test]
+    fn test_pr_personalized() {
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3)])
+            .build();
+
+        let config = PageRankConfig::default();
+
+        let (uniform, _, _) = personalized_page_rank(&graph, Teleport::Uniform, config);
+        assert_eq!(uniform, page_rank(&graph, config).0);
+
+        let (sources, _, _) = personalized_page_rank(&graph, Teleport::Sources(&[0, 1]), config);
+        let distribution = [2.0, 2.0, 0.0, 0.0, 0.0];
+        let (distributed, _, _) =
+            personalized_page_rank(&graph, Teleport::Distribution(&distribution), config);
+
+        assert_eq!(sources, distributed);
+        assert!(sources[0] > uniform[0]);
+        assert!(sources[4] < uniform[4]);
+    }
+
+    #[test]
+    fn test_pr_weighted() {
+        let edges = vec![(0, 1), (0, 2), (1, 2), (2, 0), (3, 0), (3, 2)];
+
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new().edges(edges.clone()).build();
+        let weighted: DirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .edges_with_values(edges.iter().map(|(s, t)| (*s, *t, 1.0)))
+            .build();
+
+        let config = PageRankConfig::default();
+        let expected = page_rank(&graph, config);
+        let actual = weighted_page_rank(&weighted, Teleport::Uniform, config);
+
+        assert_eq!(actual, expected);
+
+        // Moving all weight of 0 to the edge (0, 1) is like removing (0, 2).
+        let weighted: DirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .edges_with_values(vec![(0, 1, 1.0), (0, 2, 0.0), (1, 2, 1.0), (2, 0, 1.0)])
+            .build();
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2), (2, 0)])
+            .build();
+
+        let (expected, _, _) = page_rank(&graph, config);
+        let (actual, _, _) = weighted_page_rank(&weighted, Teleport::Uniform, config);
+
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1E-6);
+        }
+    }
+
+    #[test]
+    fn test_pr_weighted_zero_out_weight() {
+        let weighted: DirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .edges_with_values(vec![(0, 1, 0.0), (1, 2, 1.0), (2, 0, 1.0)])
+            .build();
+        let graph: DirectedCsrGraph<usize> =
+            GraphBuilder::new().edges(vec![(1, 2), (2, 0)]).build();
+
+        let config = PageRankConfig::default();
+        let (expected, _, _) = page_rank(&graph, config);
+        let (actual, _, _) = weighted_page_rank(&weighted, Teleport::Uniform, config);
+
+        assert!(actual.iter().all(|score| score.is_finite()));
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1E-6);
+        }
+    }
 }
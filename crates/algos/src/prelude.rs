@@ -1,4 +1,12 @@
+pub use crate::betweenness::*;
+pub use crate::bfs::*;
+pub use crate::closeness::*;
+pub use crate::k_core::*;
+pub use crate::label_propagation::*;
+pub use crate::louvain::*;
 pub use crate::page_rank::*;
+pub use crate::scc::*;
+pub use crate::spectral::*;
 pub use crate::sssp::*;
 pub use crate::triangle_count::*;
 pub use crate::utils::*;
@@ -0,0 +1,722 @@
+//! Random walks and neighborhood sampling.
+//!
+//! The module generates random walks, e.g., as input for node embeddings like
+//! DeepWalk or node2vec [1], and samples fixed-size neighborhoods as used by
+//! GraphSAGE [2]. All functions run in parallel and are deterministic for a
+//! given seed, independent of the number of threads.
+//!
+//! Walks on directed graphs follow outgoing edges, walks on undirected graphs
+//! follow all edges.
+//!
+//! [1] Aditya Grover, Jure Leskovec:
+//! "node2vec: Scalable Feature Learning for Networks", KDD 2016
+//!
+//! [2] William L. Hamilton, Rex Ying, Jure Leskovec:
+//! "Inductive Representation Learning on Large Graphs", NeurIPS 2017
+use crate::prelude::*;
+
+use log::info;
+use nanorand::{Rng, WyRand};
+use rayon::prelude::*;
+
+use std::time::Instant;
+
+/// A graph whose neighbor lists can be accessed as slices, which is required
+/// to sample neighbors in constant time.
+pub trait WalkNeighbors<NI: Idx, EV>: Graph<NI> + Sync {
+    /// Returns the nodes a walk can move to from the given node.
+    fn walk_neighbors(&self, node: NI) -> &[Target<NI, EV>];
+}
+
+impl<NI, NV, EV> WalkNeighbors<NI, EV> for DirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: Send + Sync,
+    EV: Send + Sync,
+{
+    fn walk_neighbors(&self, node: NI) -> &[Target<NI, EV>] {
+        self.out_neighbors_with_values(node).as_slice()
+    }
+}
+
+impl<NI, NV, EV> WalkNeighbors<NI, EV> for UndirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: Send + Sync,
+    EV: Send + Sync,
+{
+    fn walk_neighbors(&self, node: NI) -> &[Target<NI, EV>] {
+        self.neighbors_with_values(node).as_slice()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct RandomWalkConfig {
+    /// The maximum number of nodes in a walk, including the start node.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = RandomWalkConfig::DEFAULT_WALK_LENGTH))]
+    pub walk_length: usize,
+
+    /// The number of walks that are started at each node.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = RandomWalkConfig::DEFAULT_WALKS_PER_NODE))]
+    pub walks_per_node: usize,
+
+    /// The node2vec return parameter `p`. Higher values make it
+    /// less likely to immediately return to the previous node.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = RandomWalkConfig::DEFAULT_RETURN_FACTOR))]
+    pub return_factor: f64,
+
+    /// The node2vec in-out parameter `q`. Values above 1 keep
+    /// the walk close to the previous node, values below 1
+    /// push it further away.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = RandomWalkConfig::DEFAULT_IN_OUT_FACTOR))]
+    pub in_out_factor: f64,
+
+    /// The probability to jump back to the start node
+    /// instead of moving to a neighbor.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = RandomWalkConfig::DEFAULT_RESTART_PROBABILITY))]
+    pub restart_probability: f64,
+
+    /// The seed for the random number generators.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = RandomWalkConfig::DEFAULT_SEED))]
+    pub seed: u64,
+}
+
+impl Default for RandomWalkConfig {
+    fn default() -> Self {
+        Self {
+            walk_length: Self::DEFAULT_WALK_LENGTH,
+            walks_per_node: Self::DEFAULT_WALKS_PER_NODE,
+            return_factor: Self::DEFAULT_RETURN_FACTOR,
+            in_out_factor: Self::DEFAULT_IN_OUT_FACTOR,
+            restart_probability: Self::DEFAULT_RESTART_PROBABILITY,
+            seed: Self::DEFAULT_SEED,
+        }
+    }
+}
+
+impl RandomWalkConfig {
+    pub const DEFAULT_WALK_LENGTH: usize = 80;
+    pub const DEFAULT_WALKS_PER_NODE: usize = 10;
+    pub const DEFAULT_RETURN_FACTOR: f64 = 1.0;
+    pub const DEFAULT_IN_OUT_FACTOR: f64 = 1.0;
+    pub const DEFAULT_RESTART_PROBABILITY: f64 = 0.0;
+    pub const DEFAULT_SEED: u64 = 42;
+
+    pub fn new(walk_length: usize, walks_per_node: usize) -> Self {
+        Self {
+            walk_length,
+            walks_per_node,
+            ..Self::default()
+        }
+    }
+
+    fn is_second_order(&self) -> bool {
+        self.return_factor != 1.0 || self.in_out_factor != 1.0
+    }
+}
+
+/// A set of random walks stored in two flat arrays.
+///
+/// The nodes of walk `i` are stored in `nodes[offsets[i]..offsets[i + 1]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RandomWalks<NI> {
+    offsets: Vec<usize>,
+    nodes: Vec<NI>,
+}
+
+impl<NI: Idx> RandomWalks<NI> {
+    /// Returns the number of walks.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns `true` if there are no walks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the nodes of the walk with the given index.
+    pub fn walk(&self, index: usize) -> &[NI] {
+        &self.nodes[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    /// Returns an iterator over all walks.
+    pub fn iter(&self) -> impl Iterator<Item = &[NI]> + '_ {
+        self.offsets.windows(2).map(|w| &self.nodes[w[0]..w[1]])
+    }
+
+    /// Returns the start offsets of all walks followed by the total number of nodes.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Returns the nodes of all walks.
+    pub fn nodes(&self) -> &[NI] {
+        &self.nodes
+    }
+
+    /// Returns the offsets and nodes arrays.
+    pub fn into_parts(self) -> (Vec<usize>, Vec<NI>) {
+        (self.offsets, self.nodes)
+    }
+}
+
+/// Generates random walks where each step picks a neighbor uniformly at
+/// random, or according to the node2vec parameters if they differ from 1.
+///
+/// `config.walks_per_node` walks are started at each node. Walk `i` starts at
+/// node `i % node_count`. A walk ends early if it reaches a node without
+/// neighbors.
+///
+/// node2vec walks check whether a node is a neighbor of the previous node
+/// using binary search, i.e., the graph must be built using either
+/// [`CsrLayout::Sorted`] or [`CsrLayout::Deduplicated`].
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::sampling::{random_walks, RandomWalkConfig};
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2), (2, 0)])
+///     .build();
+///
+/// let walks = random_walks(&graph, RandomWalkConfig::new(4, 2));
+///
+/// assert_eq!(walks.len(), 6);
+/// assert_eq!(walks.walk(1), &[1, 2, 0, 1]);
+/// ```
+pub fn random_walks<NI, EV, G>(graph: &G, config: RandomWalkConfig) -> RandomWalks<NI>
+where
+    NI: Idx,
+    G: WalkNeighbors<NI, EV>,
+{
+    generate_walks(graph, None, config)
+}
+
+/// Generates random walks where each step picks a neighbor with a probability
+/// proportional to the weight of the connecting edge, see [`EdgeWeight`].
+///
+/// Edge weights must not be negative. A walk ends early if it reaches a node
+/// without neighbors or whose edges all have value zero. See [`random_walks`]
+/// for the other parameters.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::sampling::{weighted_random_walks, RandomWalkConfig};
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .edges_with_values(vec![(0, 1, 1.0), (0, 2, 0.0), (1, 0, 1.0)])
+///     .build();
+///
+/// let walks = weighted_random_walks(&graph, RandomWalkConfig::new(5, 1));
+///
+/// assert_eq!(walks.walk(0), &[0, 1, 0, 1, 0]);
+/// assert_eq!(walks.walk(2), &[2]);
+/// ```
+pub fn weighted_random_walks<NI, EV, G>(graph: &G, config: RandomWalkConfig) -> RandomWalks<NI>
+where
+    NI: Idx,
+    EV: EdgeWeight,
+    G: WalkNeighbors<NI, EV>,
+{
+    let start = Instant::now();
+    let weights = CumulativeWeights::new(graph);
+    info!("Computed cumulative edge weights in {:?}", start.elapsed());
+
+    generate_walks(graph, Some(&weights), config)
+}
+
+/// Prefix sums of the edge values of each node.
+struct CumulativeWeights {
+    offsets: Vec<usize>,
+    sums: Vec<f64>,
+}
+
+impl CumulativeWeights {
+    fn new<NI, EV, G>(graph: &G) -> Self
+    where
+        NI: Idx,
+        EV: EdgeWeight,
+        G: WalkNeighbors<NI, EV>,
+    {
+        let node_count = graph.node_count().index();
+
+        let mut offsets = Vec::with_capacity(node_count + 1);
+        offsets.push(0);
+        for node in 0..node_count {
+            let degree = graph.walk_neighbors(NI::new(node)).len();
+            offsets.push(offsets[node] + degree);
+        }
+
+        let sums = (0..node_count)
+            .into_par_iter()
+            .flat_map_iter(|node| {
+                graph
+                    .walk_neighbors(NI::new(node))
+                    .iter()
+                    .scan(0.0, |sum, target| {
+                        *sum += target.value.weight();
+                        Some(*sum)
+                    })
+            })
+            .collect();
+
+        Self { offsets, sums }
+    }
+
+    fn of<NI: Idx>(&self, node: NI) -> &[f64] {
+        &self.sums[self.offsets[node.index()]..self.offsets[node.index() + 1]]
+    }
+}
+
+fn generate_walks<NI, EV, G>(
+    graph: &G,
+    weights: Option<&CumulativeWeights>,
+    config: RandomWalkConfig,
+) -> RandomWalks<NI>
+where
+    NI: Idx,
+    G: WalkNeighbors<NI, EV>,
+{
+    assert!(config.walk_length > 0, "walk length must be positive");
+    assert!(
+        config.return_factor.is_finite() && config.return_factor > 0.0,
+        "return factor must be finite and positive"
+    );
+    assert!(
+        config.in_out_factor.is_finite() && config.in_out_factor > 0.0,
+        "in-out factor must be finite and positive"
+    );
+
+    let start = Instant::now();
+    let node_count = graph.node_count().index();
+    let walk_count = node_count * config.walks_per_node;
+    let walk_length = config.walk_length;
+
+    let mut nodes = vec![NI::zero(); walk_count * walk_length];
+
+    let lengths = nodes
+        .par_chunks_mut(walk_length)
+        .enumerate()
+        .map(|(i, walk)| {
+            let mut rng = rng(config.seed, i as u64);
+            fill_walk(
+                graph,
+                weights,
+                NI::new(i % node_count),
+                walk,
+                &mut rng,
+                config,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut offsets = Vec::with_capacity(walk_count + 1);
+    offsets.push(0);
+
+    // Move walks that ended early to the front.
+    let mut end = 0;
+    for (i, length) in lengths.into_iter().enumerate() {
+        let begin = i * walk_length;
+        if begin != end {
+            nodes.copy_within(begin..begin + length, end);
+        }
+        end += length;
+        offsets.push(end);
+    }
+    nodes.truncate(end);
+
+    info!(
+        "Generated {} random walks in {:?}",
+        walk_count,
+        start.elapsed()
+    );
+
+    RandomWalks { offsets, nodes }
+}
+
+/// Fills the walk starting at `start` and returns its length.
+fn fill_walk<NI, EV, G>(
+    graph: &G,
+    weights: Option<&CumulativeWeights>,
+    start: NI,
+    walk: &mut [NI],
+    rng: &mut WyRand,
+    config: RandomWalkConfig,
+) -> usize
+where
+    NI: Idx,
+    G: WalkNeighbors<NI, EV>,
+{
+    let return_weight = 1.0 / config.return_factor;
+    let in_out_weight = 1.0 / config.in_out_factor;
+    let max_weight = return_weight.max(in_out_weight).max(1.0);
+
+    walk[0] = start;
+    let mut previous = None;
+
+    for length in 1..walk.len() {
+        if config.restart_probability > 0.0 && rng.generate::<f64>() < config.restart_probability {
+            walk[length] = start;
+            previous = None;
+            continue;
+        }
+
+        let current = walk[length - 1];
+
+        let next = match previous {
+            Some(previous) if config.is_second_order() => loop {
+                // node2vec via rejection sampling: propose a neighbor of the
+                // first-order distribution and accept it with a probability
+                // proportional to its node2vec weight.
+                let candidate = match sample_neighbor(graph, weights, current, rng) {
+                    Some(candidate) => candidate,
+                    None => return length,
+                };
+
+                let weight = if candidate == previous {
+                    return_weight
+                } else if graph
+                    .walk_neighbors(previous)
+                    .binary_search_by(|t| t.target.cmp(&candidate))
+                    .is_ok()
+                {
+                    1.0
+                } else {
+                    in_out_weight
+                };
+
+                if rng.generate::<f64>() * max_weight < weight {
+                    break candidate;
+                }
+            },
+            _ => match sample_neighbor(graph, weights, current, rng) {
+                Some(next) => next,
+                None => return length,
+            },
+        };
+
+        walk[length] = next;
+        previous = Some(current);
+    }
+
+    walk.len()
+}
+
+fn sample_neighbor<NI, EV, G>(
+    graph: &G,
+    weights: Option<&CumulativeWeights>,
+    node: NI,
+    rng: &mut WyRand,
+) -> Option<NI>
+where
+    NI: Idx,
+    G: WalkNeighbors<NI, EV>,
+{
+    let neighbors = graph.walk_neighbors(node);
+
+    if neighbors.is_empty() {
+        return None;
+    }
+
+    let index = match weights {
+        None => rng.generate_range(0..neighbors.len()),
+        Some(weights) => {
+            let sums = weights.of(node);
+            let total = sums[sums.len() - 1];
+            if total <= 0.0 {
+                return None;
+            }
+            let x = rng.generate::<f64>() * total;
+            sums.partition_point(|sum| *sum <= x).min(sums.len() - 1)
+        }
+    };
+
+    Some(neighbors[index].target)
+}
+
+/// The neighbors sampled for one layer of a GraphSAGE-style neighborhood.
+///
+/// The sampled neighbors of `nodes[i]` are stored in
+/// `neighbors[offsets[i]..offsets[i + 1]]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SampledLayer<NI> {
+    pub nodes: Vec<NI>,
+    pub offsets: Vec<usize>,
+    pub neighbors: Vec<NI>,
+}
+
+impl<NI: Idx> SampledLayer<NI> {
+    /// Returns the sampled neighbors of the node at the given index.
+    pub fn neighbors_of(&self, index: usize) -> &[NI] {
+        &self.neighbors[self.offsets[index]..self.offsets[index + 1]]
+    }
+}
+
+/// Samples a multi-layer neighborhood around the given seed nodes.
+///
+/// For each entry in `fan_outs`, the function samples up to that many
+/// distinct neighbors of every node in the current layer, uniformly at random
+/// and without replacement. Nodes with fewer neighbors keep all of them. The
+/// nodes of the next layer are the distinct sampled neighbors in ascending
+/// order.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::sampling::sample_neighbors;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .csr_layout(CsrLayout::Sorted)
+///     .edges(vec![(0, 1), (0, 2), (0, 3), (1, 2)])
+///     .build();
+///
+/// let layers = sample_neighbors(&graph, &[0], &[2, 5], 42);
+///
+/// assert_eq!(layers.len(), 2);
+/// assert_eq!(layers[0].neighbors_of(0).len(), 2);
+/// assert_eq!(layers[1].nodes, layers[0].neighbors);
+/// ```
+pub fn sample_neighbors<NI, EV, G>(
+    graph: &G,
+    seeds: &[NI],
+    fan_outs: &[usize],
+    seed: u64,
+) -> Vec<SampledLayer<NI>>
+where
+    NI: Idx,
+    G: WalkNeighbors<NI, EV>,
+{
+    let start = Instant::now();
+
+    let mut layers: Vec<SampledLayer<NI>> = Vec::with_capacity(fan_outs.len());
+    let mut nodes = seeds.to_vec();
+
+    for (layer, &fan_out) in fan_outs.iter().enumerate() {
+        let samples = nodes
+            .par_iter()
+            .enumerate()
+            .map(|(i, &node)| {
+                let mut rng = rng(seed, ((layer as u64) << 48) ^ i as u64);
+                sample_distinct(graph.walk_neighbors(node), fan_out, &mut rng)
+            })
+            .collect::<Vec<_>>();
+
+        let mut offsets = Vec::with_capacity(nodes.len() + 1);
+        offsets.push(0);
+        for sample in &samples {
+            offsets.push(offsets[offsets.len() - 1] + sample.len());
+        }
+        let neighbors = samples.concat();
+
+        let mut next_nodes = neighbors.clone();
+        next_nodes.par_sort_unstable();
+        next_nodes.dedup();
+
+        layers.push(SampledLayer {
+            nodes: std::mem::replace(&mut nodes, next_nodes),
+            offsets,
+            neighbors,
+        });
+    }
+
+    info!(
+        "Sampled {} layers for {} seed nodes in {:?}",
+        fan_outs.len(),
+        seeds.len(),
+        start.elapsed()
+    );
+
+    layers
+}
+
+/// Samples `count` distinct targets using Floyd's algorithm.
+fn sample_distinct<NI: Idx, EV>(
+    neighbors: &[Target<NI, EV>],
+    count: usize,
+    rng: &mut WyRand,
+) -> Vec<NI> {
+    if neighbors.len() <= count {
+        return neighbors.iter().map(|t| t.target).collect();
+    }
+
+    let mut indices = Vec::with_capacity(count);
+    for j in neighbors.len() - count..neighbors.len() {
+        let index = rng.generate_range(0..=j);
+        indices.push(if indices.contains(&index) { j } else { index });
+    }
+    indices.sort_unstable();
+
+    indices.into_iter().map(|i| neighbors[i].target).collect()
+}
+
+/// Creates the generator for the given stream, e.g., a walk index, such
+/// that results do not depend on how work is distributed across threads.
+fn rng(seed: u64, stream: u64) -> WyRand {
+    WyRand::new_seed(split_mix(seed ^ split_mix(stream)))
+}
+
+pub(crate) fn split_mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_walk<EV, G: WalkNeighbors<usize, EV>>(graph: &G, walk: &[usize]) -> bool {
+        walk.windows(2)
+            .all(|w| graph.walk_neighbors(w[0]).iter().any(|t| t.target == w[1]))
+    }
+
+    fn graph() -> UndirectedCsrGraph<usize> {
+        GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (4, 5), (5, 3)])
+            .build()
+    }
+
+    #[test]
+    fn uniform_walks_test() {
+        let graph = graph();
+        let walks = random_walks(&graph, RandomWalkConfig::new(10, 3));
+
+        assert_eq!(walks.len(), 18);
+        assert_eq!(walks.nodes().len(), 180);
+        for (i, walk) in walks.iter().enumerate() {
+            assert_eq!(walk[0], i % 6);
+            assert!(is_walk(&graph, walk));
+        }
+    }
+
+    #[test]
+    fn deterministic_test() {
+        let graph = graph();
+        let config = RandomWalkConfig {
+            return_factor: 0.5,
+            in_out_factor: 2.0,
+            ..RandomWalkConfig::new(20, 5)
+        };
+
+        let walks = random_walks(&graph, config);
+        assert_eq!(walks, random_walks(&graph, config));
+        assert!(walks.iter().all(|walk| is_walk(&graph, walk)));
+
+        let other = random_walks(
+            &graph,
+            RandomWalkConfig {
+                seed: 1337,
+                ..config
+            },
+        );
+        assert_ne!(walks, other);
+    }
+
+    #[test]
+    fn dead_end_test() {
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2), (3, 0)])
+            .build();
+
+        let walks = random_walks(&graph, RandomWalkConfig::new(5, 1));
+
+        assert_eq!(walks.offsets(), &[0, 3, 5, 6, 10]);
+        assert_eq!(walks.walk(0), &[0, 1, 2]);
+        assert_eq!(walks.walk(2), &[2]);
+        assert_eq!(walks.walk(3), &[3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn return_factor_test() {
+        // With a tiny return factor, walks almost always go back.
+        let graph = graph();
+        let config = RandomWalkConfig {
+            return_factor: 1E-6,
+            ..RandomWalkConfig::new(10, 1)
+        };
+
+        let walks = random_walks(&graph, config);
+
+        for walk in walks.iter() {
+            assert!(walk.windows(3).all(|w| w[0] == w[2]));
+        }
+    }
+
+    #[test]
+    fn restart_test() {
+        let graph = graph();
+        let config = RandomWalkConfig {
+            restart_probability: 1.0,
+            ..RandomWalkConfig::new(5, 1)
+        };
+
+        let walks = random_walks(&graph, config);
+
+        for (i, walk) in walks.iter().enumerate() {
+            assert_eq!(walk, &[i; 5]);
+        }
+    }
+
+    #[test]
+    fn weighted_walks_test() {
+        let graph: UndirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+            .edges_with_values(vec![(0, 1, 1.0), (0, 2, 0.0), (1, 3, 0.0)])
+            .build();
+
+        let walks = weighted_random_walks(&graph, RandomWalkConfig::new(6, 2));
+
+        assert_eq!(walks.walk(0), &[0, 1, 0, 1, 0, 1]);
+        assert_eq!(walks.walk(1), &[1, 0, 1, 0, 1, 0]);
+        // all edges of nodes 2 and 3 have weight zero
+        assert_eq!(walks.walk(2), &[2]);
+        assert_eq!(walks.walk(3), &[3]);
+    }
+
+    #[test]
+    fn sample_neighbors_test() {
+        let graph = graph();
+        let layers = sample_neighbors(&graph, &[0, 3], &[1, 2], 42);
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].nodes, vec![0, 3]);
+        assert_eq!(layers[0].offsets, vec![0, 1, 2]);
+
+        for layer in &layers {
+            for (i, &node) in layer.nodes.iter().enumerate() {
+                let sample = layer.neighbors_of(i);
+                assert!(sample.len() <= 2);
+                assert!(sample.windows(2).all(|w| w[0] < w[1]));
+                assert!(sample.iter().all(|n| is_walk(&graph, &[node, *n])));
+            }
+        }
+
+        let mut expected = layers[0].neighbors.clone();
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(layers[1].nodes, expected);
+    }
+
+    #[test]
+    fn sample_distinct_test() {
+        let neighbors = (0..100)
+            .map(|target| Target::new(target, ()))
+            .collect::<Vec<Target<usize, ()>>>();
+        let mut rng = rng(42, 0);
+
+        for count in [0, 1, 10, 99, 100, 200] {
+            let sample = sample_distinct(&neighbors, count, &mut rng);
+            assert_eq!(sample.len(), count.min(100));
+            assert!(sample.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+}
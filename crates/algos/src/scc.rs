@@ -0,0 +1,624 @@
+//! Strongly Connected Components (SCC) algorithm.
+//!
+//! The algorithm finds all strongly connected components of a directed
+//! graph. Strongly connected means that all nodes of a component can reach
+//! each other via a directed path.
+//!
+//! The implementation follows the Multistep approach [1]:
+//!
+//! - Trimming removes nodes without incoming or outgoing edges, each of
+//!   which forms a component on its own.
+//! - A single forward-backward search from the node with the largest degree
+//!   product finds the giant component, which is common in real-world graphs.
+//! - The remaining nodes are colored by propagating the largest node id
+//!   along outgoing edges. Each node that keeps its own color is the root of
+//!   a component that contains all nodes of the same color that can reach
+//!   the root. Coloring is repeated until all nodes are assigned.
+//!
+//! Each component is identified by one of its nodes.
+//!
+//! [1] George M. Slota, Sivasankaran Rajamanickam, Kamesh Madduri:
+//! "BFS and Coloring-based Parallel Algorithms for Strongly Connected
+//! Components and Related Problems", IPDPS 2014
+use crate::frontier::{edge_map, EdgeMapFn, Frontier};
+use crate::prelude::*;
+use crate::view::transpose;
+
+use graph_builder::graph::csr::{self, Csr};
+
+use log::info;
+use rayon::prelude::*;
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+const UNASSIGNED: usize = usize::MAX;
+
+/// Computes the strongly connected components of a directed graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 0), (1, 2), (2, 3), (3, 2)])
+///     .build();
+///
+/// let components = scc(&graph);
+///
+/// assert_eq!(components.component(0), components.component(1));
+/// assert_eq!(components.component(2), components.component(3));
+/// assert_ne!(components.component(1), components.component(2));
+/// ```
+pub fn scc<NI, G>(graph: &G) -> impl Components<NI>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let start = Instant::now();
+
+    let node_count = graph.node_count().index();
+    let components = (0..node_count)
+        .into_par_iter()
+        .map(|_| AtomicUsize::new(UNASSIGNED))
+        .collect::<Vec<_>>();
+
+    let trimmed = trim(graph, &components);
+    let giant = forward_backward(graph, &components);
+    info!("Trimmed {trimmed} nodes, found component with {giant} nodes");
+
+    let mut round = 0;
+    loop {
+        let remaining = components
+            .par_iter()
+            .filter(|c| c.load(Ordering::Relaxed) == UNASSIGNED)
+            .count();
+
+        if remaining == 0 {
+            break;
+        }
+
+        round += 1;
+        let trimmed = trim(graph, &components);
+        let roots = coloring(graph, &components);
+        info!("Round {round}: trimmed {trimmed} of {remaining} nodes, found {roots} components");
+    }
+
+    info!("Computed SCC in {:?}", start.elapsed());
+
+    StronglyConnectedComponents {
+        components: components
+            .into_par_iter()
+            .map(|c| NI::new(c.into_inner()))
+            .collect(),
+    }
+}
+
+/// Assigns a node to its own component if it has no incoming or no outgoing
+/// edges from unassigned nodes. Returns the number of trimmed nodes.
+fn trim<NI, G>(graph: &G, components: &[AtomicUsize]) -> usize
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let node_count = graph.node_count().index();
+    let is_unassigned = |node: NI| components[node.index()].load(Ordering::Acquire) == UNASSIGNED;
+    let assign = |node: NI| {
+        components[node.index()]
+            .compare_exchange(
+                UNASSIGNED,
+                node.index(),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    };
+
+    let degrees = |degree: &(dyn Fn(NI) -> usize + Sync)| {
+        (0..node_count)
+            .into_par_iter()
+            .map(NI::new)
+            .map(|node| AtomicUsize::new(if is_unassigned(node) { degree(node) } else { 0 }))
+            .collect::<Vec<_>>()
+    };
+
+    let in_degrees = &degrees(&|node| {
+        graph
+            .in_neighbors(node)
+            .filter(|n| is_unassigned(**n))
+            .count()
+    });
+    let out_degrees = &degrees(&|node| {
+        graph
+            .out_neighbors(node)
+            .filter(|n| is_unassigned(**n))
+            .count()
+    });
+
+    let mut frontier = (0..node_count)
+        .into_par_iter()
+        .map(NI::new)
+        .filter(|node| {
+            is_unassigned(*node)
+                && (in_degrees[node.index()].load(Ordering::Relaxed) == 0
+                    || out_degrees[node.index()].load(Ordering::Relaxed) == 0)
+        })
+        .collect::<Vec<_>>();
+
+    frontier.par_iter().for_each(|node| {
+        assign(*node);
+    });
+
+    let mut trimmed = 0;
+
+    while !frontier.is_empty() {
+        trimmed += frontier.len();
+
+        frontier = frontier
+            .par_iter()
+            .flat_map_iter(|&node| {
+                let targets = graph.out_neighbors(node).filter(move |target| {
+                    is_unassigned(**target)
+                        && in_degrees[target.index()].fetch_sub(1, Ordering::AcqRel) == 1
+                        && assign(**target)
+                });
+                let sources = graph.in_neighbors(node).filter(move |source| {
+                    is_unassigned(**source)
+                        && out_degrees[source.index()].fetch_sub(1, Ordering::AcqRel) == 1
+                        && assign(**source)
+                });
+                targets.chain(sources).copied()
+            })
+            .collect();
+    }
+
+    trimmed
+}
+
+/// Marks unassigned nodes that are reachable from the frontier.
+struct Reach<'a> {
+    components: &'a [AtomicUsize],
+    reached: &'a [AtomicBool],
+}
+
+impl<NI: Idx> EdgeMapFn<NI> for Reach<'_> {
+    fn update_atomic(&self, _source: NI, target: NI) -> bool {
+        !self.reached[target.index()].swap(true, Ordering::AcqRel)
+    }
+
+    fn cond(&self, target: NI) -> bool {
+        !self.reached[target.index()].load(Ordering::Acquire)
+            && self.components[target.index()].load(Ordering::Acquire) == UNASSIGNED
+    }
+}
+
+/// Assigns unassigned nodes that reach the frontier backwards to the
+/// component of the frontier node, if they have the same color.
+struct Assign<'a> {
+    components: &'a [AtomicUsize],
+    colors: &'a [AtomicUsize],
+}
+
+impl<NI: Idx> EdgeMapFn<NI> for Assign<'_> {
+    fn update_atomic(&self, source: NI, target: NI) -> bool {
+        let color = self.colors[source.index()].load(Ordering::Relaxed);
+
+        self.colors[target.index()].load(Ordering::Relaxed) == color
+            && self.components[target.index()]
+                .compare_exchange(UNASSIGNED, color, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+    }
+
+    fn cond(&self, target: NI) -> bool {
+        self.components[target.index()].load(Ordering::Acquire) == UNASSIGNED
+    }
+}
+
+fn run_edge_map<NI, G, F>(graph: &G, mut frontier: Frontier<NI>, f: &F)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+    F: EdgeMapFn<NI>,
+{
+    while !frontier.is_empty() {
+        frontier = edge_map(graph, &frontier, f);
+    }
+}
+
+/// Finds the component of the unassigned node with the largest degree
+/// product and returns its size.
+fn forward_backward<NI, G>(graph: &G, components: &[AtomicUsize]) -> usize
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let node_count = graph.node_count().index();
+
+    let pivot = (0..node_count)
+        .into_par_iter()
+        .map(NI::new)
+        .filter(|node| components[node.index()].load(Ordering::Relaxed) == UNASSIGNED)
+        .max_by_key(|node| graph.out_degree(*node).index() * graph.in_degree(*node).index());
+
+    let pivot = match pivot {
+        Some(pivot) => pivot,
+        None => return 0,
+    };
+
+    let reached = (0..node_count)
+        .into_par_iter()
+        .map(|node| AtomicBool::new(node == pivot.index()))
+        .collect::<Vec<_>>();
+
+    let forward = Reach {
+        components,
+        reached: &reached,
+    };
+    run_edge_map(graph, Frontier::single(node_count, pivot), &forward);
+
+    // Nodes that have not been reached in forward direction can not belong
+    // to the component of the pivot. Keeping their own color restricts the
+    // backward search to the forward reachable nodes.
+    let colors = reached
+        .into_par_iter()
+        .enumerate()
+        .map(|(node, reached)| match reached.into_inner() {
+            true => AtomicUsize::new(pivot.index()),
+            false => AtomicUsize::new(node),
+        })
+        .collect::<Vec<_>>();
+
+    components[pivot.index()].store(pivot.index(), Ordering::Release);
+    let backward = Assign {
+        components,
+        colors: &colors,
+    };
+    run_edge_map(
+        &transpose(graph),
+        Frontier::single(node_count, pivot),
+        &backward,
+    );
+
+    components
+        .par_iter()
+        .filter(|c| c.load(Ordering::Relaxed) == pivot.index())
+        .count()
+}
+
+/// Runs one coloring round and returns the number of found components.
+fn coloring<NI, G>(graph: &G, components: &[AtomicUsize]) -> usize
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedDegrees<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let node_count = graph.node_count().index();
+    let is_unassigned = |node: NI| components[node.index()].load(Ordering::Relaxed) == UNASSIGNED;
+
+    let colors = &(0..node_count)
+        .into_par_iter()
+        .map(AtomicUsize::new)
+        .collect::<Vec<_>>();
+
+    let mut frontier = (0..node_count)
+        .into_par_iter()
+        .map(NI::new)
+        .filter(|node| is_unassigned(*node))
+        .collect::<Vec<_>>();
+
+    while !frontier.is_empty() {
+        frontier = frontier
+            .par_iter()
+            .flat_map_iter(|&node| {
+                let color = colors[node.index()].load(Ordering::Relaxed);
+                graph.out_neighbors(node).copied().filter(move |target| {
+                    is_unassigned(*target)
+                        && colors[target.index()].fetch_max(color, Ordering::AcqRel) < color
+                })
+            })
+            .collect::<Vec<_>>();
+
+        frontier.par_sort_unstable();
+        frontier.dedup();
+    }
+
+    let roots = (0..node_count)
+        .into_par_iter()
+        .map(NI::new)
+        .filter(|node| {
+            is_unassigned(*node) && colors[node.index()].load(Ordering::Relaxed) == node.index()
+        })
+        .collect::<Vec<_>>();
+
+    roots.par_iter().for_each(|root| {
+        components[root.index()].store(root.index(), Ordering::Release);
+    });
+
+    let root_count = roots.len();
+    let assign = Assign { components, colors };
+    run_edge_map(
+        &transpose(graph),
+        Frontier::from_nodes(node_count, roots),
+        &assign,
+    );
+
+    root_count
+}
+
+struct StronglyConnectedComponents<NI> {
+    components: Vec<NI>,
+}
+
+impl<NI: Idx> Components<NI> for StronglyConnectedComponents<NI> {
+    fn component(&self, node: NI) -> NI {
+        self.components[node.index()]
+    }
+
+    fn to_vec(self) -> Vec<NI> {
+        self.components
+    }
+}
+
+/// The condensation of a directed graph, see [`CondensationOp`].
+pub struct Condensation<NI: Idx> {
+    /// The directed acyclic graph with one node per component.
+    pub graph: DirectedCsrGraph<NI>,
+    /// The node in `graph` for each node of the original graph.
+    pub components: Vec<NI>,
+}
+
+pub trait CondensationOp<NI: Idx> {
+    /// Creates the condensation of the graph, i.e., a graph that contains a
+    /// node for each component and an edge between two components if the
+    /// original graph contains an edge between their nodes.
+    ///
+    /// `components` contains the component of each node, e.g., the result of
+    /// [`scc`]. Components are numbered in the order of their first node.
+    /// The condensation of strongly connected components is acyclic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph::prelude::*;
+    ///
+    /// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+    ///     .edges(vec![(0, 1), (1, 0), (1, 2), (2, 3), (3, 2), (3, 4)])
+    ///     .build();
+    ///
+    /// let condensation = graph.condensation(&scc(&graph).to_vec());
+    ///
+    /// assert_eq!(condensation.components, vec![0, 0, 1, 1, 2]);
+    /// assert_eq!(condensation.graph.node_count(), 3);
+    /// assert_eq!(condensation.graph.edge_count(), 2);
+    /// assert_eq!(condensation.graph.out_neighbors(0).as_slice(), &[1]);
+    /// ```
+    fn condensation(&self, components: &[NI]) -> Condensation<NI>;
+}
+
+impl<NI, NV, EV> CondensationOp<NI> for DirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: Send + Sync,
+    EV: Send + Sync,
+{
+    fn condensation(&self, components: &[NI]) -> Condensation<NI> {
+        let start = Instant::now();
+
+        let node_count = self.node_count().index();
+        assert_eq!(
+            components.len(),
+            node_count,
+            "components must contain one entry per node"
+        );
+
+        let mut ids = vec![UNASSIGNED; node_count];
+        let mut component_count = 0;
+        for component in components {
+            if ids[component.index()] == UNASSIGNED {
+                ids[component.index()] = component_count;
+                component_count += 1;
+            }
+        }
+
+        let components = components
+            .par_iter()
+            .map(|component| NI::new(ids[component.index()]))
+            .collect::<Vec<_>>();
+
+        let edges = (0..node_count)
+            .into_par_iter()
+            .map(NI::new)
+            .flat_map_iter(|node| {
+                let source = components[node.index()];
+                let components = &components;
+                self.out_neighbors_with_values(node)
+                    .map(move |target| components[target.target.index()])
+                    .filter(move |target| *target != source)
+                    .map(move |target| (source, target, ()))
+            })
+            .collect::<Vec<_>>();
+
+        let graph = if component_count == 0 {
+            empty_graph()
+        } else {
+            let max_node_id = NI::new(component_count - 1);
+            let edge_list = EdgeList::with_max_node_id(edges, max_node_id);
+            DirectedCsrGraph::from((edge_list, CsrLayout::Deduplicated))
+        };
+
+        info!(
+            "Created condensation with {} nodes in {:?}",
+            component_count,
+            start.elapsed()
+        );
+
+        Condensation { graph, components }
+    }
+}
+
+/// Creates a graph without nodes. Graphs created from an edge list have at
+/// least one node, since the node count is derived from the largest node id.
+fn empty_graph<NI: Idx>() -> DirectedCsrGraph<NI> {
+    let edges = EdgeList::<NI, ()>::new(Vec::new());
+    let empty_csr = |direction| Csr::from((&edges, NI::zero(), direction, CsrLayout::Deduplicated));
+
+    DirectedCsrGraph::new(
+        csr::NodeValues::new(Vec::new()),
+        empty_csr(Direction::Outgoing),
+        empty_csr(Direction::Incoming),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_partition(components: &[usize], expected: &[&[usize]]) {
+        for group in expected {
+            for node in group.iter() {
+                assert_eq!(components[*node], components[group[0]], "node {}", node);
+            }
+        }
+        for (i, a) in expected.iter().enumerate() {
+            for b in &expected[i + 1..] {
+                assert_ne!(components[a[0]], components[b[0]]);
+            }
+        }
+    }
+
+    #[test]
+    fn scc_test() {
+        // https://en.wikipedia.org/wiki/Strongly_connected_component#/media/File:Scc-1.svg
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![
+                (0, 1),
+                (1, 2),
+                (1, 4),
+                (1, 5),
+                (2, 3),
+                (2, 6),
+                (3, 2),
+                (3, 7),
+                (4, 0),
+                (4, 5),
+                (5, 6),
+                (6, 5),
+                (7, 3),
+                (7, 6),
+            ])
+            .build();
+
+        let components = scc(&graph).to_vec();
+
+        assert_partition(&components, &[&[0, 1, 4], &[2, 3, 7], &[5, 6]]);
+    }
+
+    #[test]
+    fn chain_test() {
+        // Long chains of cycles are not removed by trimming and need
+        // multiple coloring rounds.
+        let mut edges = Vec::new();
+        for i in 0..50 {
+            edges.push((2 * i, 2 * i + 1));
+            edges.push((2 * i + 1, 2 * i));
+            edges.push((2 * i + 1, 2 * i + 2));
+        }
+        edges.push((100, 100));
+
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+        let components = scc(&graph).to_vec();
+
+        let expected = (0..50).map(|i| vec![2 * i, 2 * i + 1]).collect::<Vec<_>>();
+        let mut expected = expected.iter().map(|c| c.as_slice()).collect::<Vec<_>>();
+        expected.push(&[100]);
+
+        assert_partition(&components, &expected);
+    }
+
+    /// Returns for each node the set of nodes it can reach.
+    fn reachability(graph: &DirectedCsrGraph<usize>) -> Vec<Vec<bool>> {
+        let node_count = graph.node_count();
+        (0..node_count)
+            .map(|source| {
+                let mut reached = vec![false; node_count];
+                let mut stack = vec![source];
+                reached[source] = true;
+                while let Some(node) = stack.pop() {
+                    for &target in graph.out_neighbors(node) {
+                        if !reached[target] {
+                            reached[target] = true;
+                            stack.push(target);
+                        }
+                    }
+                }
+                reached
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_graph_test() {
+        use nanorand::{Rng, WyRand};
+
+        let mut rng = WyRand::new_seed(42);
+        let edges = (0..300)
+            .map(|_| {
+                (
+                    rng.generate_range(0..150_usize),
+                    rng.generate_range(0..150_usize),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new().edges(edges).build();
+        let components = scc(&graph).to_vec();
+        let reached = reachability(&graph);
+
+        for u in 0..graph.node_count() {
+            for v in 0..graph.node_count() {
+                let strongly_connected = reached[u][v] && reached[v][u];
+                assert_eq!(components[u] == components[v], strongly_connected);
+            }
+        }
+    }
+
+    #[test]
+    fn condensation_test() {
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 0), (1, 2), (0, 2), (2, 3), (3, 2), (4, 5)])
+            .build();
+
+        let condensation = graph.condensation(&scc(&graph).to_vec());
+
+        assert_eq!(condensation.components, vec![0, 0, 1, 1, 2, 3]);
+
+        let dag = condensation.graph;
+        assert_eq!(dag.node_count(), 4);
+        assert_eq!(dag.edge_count(), 2);
+        assert_eq!(dag.out_neighbors(0).as_slice(), &[1]);
+        assert_eq!(dag.out_neighbors(2).as_slice(), &[3]);
+        assert_eq!(dag.out_degree(3), 0);
+    }
+
+    #[test]
+    fn condensation_isolated_test() {
+        let graph: DirectedCsrGraph<usize> =
+            GraphBuilder::new().edges(vec![(0, 1), (2, 2)]).build();
+
+        let condensation = graph.condensation(&scc(&graph).to_vec());
+
+        assert_eq!(condensation.graph.node_count(), 3);
+        assert_eq!(condensation.graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn condensation_empty_test() {
+        let graph = empty_graph::<usize>();
+
+        let condensation = graph.condensation(&[]);
+
+        assert!(condensation.components.is_empty());
+        assert_eq!(condensation.graph.node_count(), 0);
+        assert_eq!(condensation.graph.edge_count(), 0);
+    }
+}
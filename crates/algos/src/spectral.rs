@@ -0,0 +1,432 @@
+//! Spectral centralities: eigenvector centrality, HITS [1] and Katz
+//! centrality [2].
+//!
+//! All centralities are computed by power iteration. In each iteration, every
+//! node pulls the scores of its neighbors from the previous iteration, which
+//! allows processing all nodes in parallel without synchronization. Like
+//! [`page_rank`](crate::page_rank::page_rank), the functions return the
+//! scores, the number of iterations and the sum of the absolute score changes
+//! in the last iteration.
+//!
+//! The functions follow incoming edges, i.e., a node is central if it is
+//! pointed to by central nodes. For undirected graphs, use
+//! [`symmetric`](crate::view::symmetric).
+//!
+//! [1] Jon M. Kleinberg:
+//! "Authoritative Sources in a Hyperlinked Environment", JACM 1999
+//!
+//! [2] Leo Katz:
+//! "A New Status Index Derived from Sociometric Analysis",
+//! Psychometrika, 1953
+use crate::prelude::*;
+
+use log::info;
+use rayon::prelude::*;
+
+use std::time::Instant;
+
+const CHUNK_SIZE: usize = 16384;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct EigenvectorConfig {
+    /// The maximum number of iterations.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = EigenvectorConfig::DEFAULT_MAX_ITERATIONS))]
+    pub max_iterations: usize,
+
+    /// If the sum of score deltas per iteration is below
+    /// the tolerance value, the computation stops.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = EigenvectorConfig::DEFAULT_TOLERANCE))]
+    pub tolerance: f64,
+}
+
+impl Default for EigenvectorConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: Self::DEFAULT_MAX_ITERATIONS,
+            tolerance: Self::DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+impl EigenvectorConfig {
+    pub const DEFAULT_MAX_ITERATIONS: usize = 100;
+    pub const DEFAULT_TOLERANCE: f64 = 1E-4;
+
+    pub fn new(max_iterations: usize, tolerance: f64) -> Self {
+        Self {
+            max_iterations,
+            tolerance,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "clap", derive(clap::Args))]
+pub struct KatzConfig {
+    /// The maximum number of iterations.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = KatzConfig::DEFAULT_MAX_ITERATIONS))]
+    pub max_iterations: usize,
+
+    /// If the sum of score deltas per iteration is below
+    /// the tolerance value, the computation stops.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = KatzConfig::DEFAULT_TOLERANCE))]
+    pub tolerance: f64,
+
+    /// The attenuation factor of longer walks. It must be
+    /// smaller than the inverse of the largest eigenvalue
+    /// of the adjacency matrix for the scores to converge.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = KatzConfig::DEFAULT_ALPHA))]
+    pub alpha: f32,
+
+    /// The base score of each node.
+    #[cfg_attr(feature = "clap", clap(long, default_value_t = KatzConfig::DEFAULT_BETA))]
+    pub beta: f32,
+}
+
+impl Default for KatzConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: Self::DEFAULT_MAX_ITERATIONS,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            alpha: Self::DEFAULT_ALPHA,
+            beta: Self::DEFAULT_BETA,
+        }
+    }
+}
+
+impl KatzConfig {
+    pub const DEFAULT_MAX_ITERATIONS: usize = 100;
+    pub const DEFAULT_TOLERANCE: f64 = 1E-4;
+    pub const DEFAULT_ALPHA: f32 = 0.1;
+    pub const DEFAULT_BETA: f32 = 1.0;
+
+    pub fn new(max_iterations: usize, tolerance: f64, alpha: f32, beta: f32) -> Self {
+        Self {
+            max_iterations,
+            tolerance,
+            alpha,
+            beta,
+        }
+    }
+}
+
+/// The hub and authority scores computed by [`hits`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hits {
+    /// A node is a good hub if it points to good authorities.
+    pub hubs: Vec<f32>,
+    /// A node is a good authority if it is pointed to by good hubs.
+    pub authorities: Vec<f32>,
+}
+
+/// Computes the eigenvector centrality of each node, i.e., the principal
+/// eigenvector of the transposed adjacency matrix with unit length.
+///
+/// The iteration is shifted by the identity, i.e., it computes
+/// `x = x + A^T x`, which has the same principal eigenvector but also
+/// converges on bipartite graphs.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::view::symmetric;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2), (0, 3), (0, 4)])
+///     .build();
+///
+/// let (scores, _, _) = eigenvector_centrality(&symmetric(&graph), EigenvectorConfig::default());
+///
+/// assert!((scores[0] - 0.5_f32.sqrt()).abs() < 1E-4);
+/// assert!((scores[1] - 0.125_f32.sqrt()).abs() < 1E-4);
+/// ```
+pub fn eigenvector_centrality<NI, G>(graph: &G, config: EigenvectorConfig) -> (Vec<f32>, usize, f64)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let EigenvectorConfig {
+        max_iterations,
+        tolerance,
+    } = config;
+
+    let node_count = graph.node_count().index();
+    let init_score = 1_f32 / (node_count as f32).sqrt();
+
+    let mut scores = vec![init_score; node_count];
+    let mut next_scores = vec![0_f32; node_count];
+    let mut iteration = 0;
+
+    loop {
+        let start = Instant::now();
+
+        pull_iteration(&mut next_scores, |node: NI| {
+            scores[node.index()] + incoming_sum(graph, &scores, node)
+        });
+        normalize(&mut next_scores);
+
+        let error = distance(&scores, &next_scores);
+        std::mem::swap(&mut scores, &mut next_scores);
+
+        info!(
+            "Finished iteration {} with an error of {:.6} in {:?}",
+            iteration,
+            error,
+            start.elapsed()
+        );
+
+        iteration += 1;
+
+        if error < tolerance || iteration == max_iterations {
+            return (scores, iteration, error);
+        }
+    }
+}
+
+/// Computes the hub and authority scores of each node. Both score vectors
+/// have unit length. The error is the sum of the errors of both scores.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 2), (1, 2), (1, 3)])
+///     .build();
+///
+/// let (hits, _, _) = hits(&graph, EigenvectorConfig::default());
+///
+/// assert!(hits.hubs[1] > hits.hubs[0]);
+/// assert!(hits.authorities[2] > hits.authorities[3]);
+/// ```
+pub fn hits<NI, G>(graph: &G, config: EigenvectorConfig) -> (Hits, usize, f64)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let EigenvectorConfig {
+        max_iterations,
+        tolerance,
+    } = config;
+
+    let node_count = graph.node_count().index();
+    let init_score = 1_f32 / (node_count as f32).sqrt();
+
+    let mut hubs = vec![init_score; node_count];
+    let mut authorities = vec![init_score; node_count];
+    let mut next_hubs = vec![0_f32; node_count];
+    let mut next_authorities = vec![0_f32; node_count];
+    let mut iteration = 0;
+
+    loop {
+        let start = Instant::now();
+
+        pull_iteration(&mut next_authorities, |node: NI| {
+            incoming_sum(graph, &hubs, node)
+        });
+        normalize(&mut next_authorities);
+
+        pull_iteration(&mut next_hubs, |node: NI| {
+            graph
+                .out_neighbors(node)
+                .map(|v| next_authorities[v.index()])
+                .sum::<f32>()
+        });
+        normalize(&mut next_hubs);
+
+        let error = distance(&hubs, &next_hubs) + distance(&authorities, &next_authorities);
+        std::mem::swap(&mut hubs, &mut next_hubs);
+        std::mem::swap(&mut authorities, &mut next_authorities);
+
+        info!(
+            "Finished iteration {} with an error of {:.6} in {:?}",
+            iteration,
+            error,
+            start.elapsed()
+        );
+
+        iteration += 1;
+
+        if error < tolerance || iteration == max_iterations {
+            let hits = Hits { hubs, authorities };
+            return (hits, iteration, error);
+        }
+    }
+}
+
+/// Computes the Katz centrality of each node, i.e., `x = alpha * A^T x + beta`.
+/// The scores are not normalized.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (1, 2)])
+///     .build();
+///
+/// let (scores, _, _) = katz_centrality(&graph, KatzConfig::default());
+///
+/// assert_eq!(scores, vec![1.0, 1.1, 1.11]);
+/// ```
+pub fn katz_centrality<NI, G>(graph: &G, config: KatzConfig) -> (Vec<f32>, usize, f64)
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighbors<NI> + Sync,
+{
+    let KatzConfig {
+        max_iterations,
+        tolerance,
+        alpha,
+        beta,
+    } = config;
+
+    let node_count = graph.node_count().index();
+
+    let mut scores = vec![0_f32; node_count];
+    let mut next_scores = vec![0_f32; node_count];
+    let mut iteration = 0;
+
+    loop {
+        let start = Instant::now();
+
+        pull_iteration(&mut next_scores, |node: NI| {
+            alpha * incoming_sum(graph, &scores, node) + beta
+        });
+
+        let error = distance(&scores, &next_scores);
+        std::mem::swap(&mut scores, &mut next_scores);
+
+        info!(
+            "Finished iteration {} with an error of {:.6} in {:?}",
+            iteration,
+            error,
+            start.elapsed()
+        );
+
+        iteration += 1;
+
+        if error < tolerance || iteration == max_iterations {
+            return (scores, iteration, error);
+        }
+    }
+}
+
+/// Sets the score of each node to the result of `f` in parallel.
+fn pull_iteration<NI, F>(scores: &mut [f32], f: F)
+where
+    NI: Idx,
+    F: Fn(NI) -> f32 + Sync,
+{
+    scores
+        .par_chunks_mut(CHUNK_SIZE)
+        .enumerate()
+        .for_each(|(chunk, scores)| {
+            let offset = chunk * CHUNK_SIZE;
+            for (i, score) in scores.iter_mut().enumerate() {
+                *score = f(NI::new(offset + i));
+            }
+        });
+}
+
+fn incoming_sum<NI, G>(graph: &G, scores: &[f32], node: NI) -> f32
+where
+    NI: Idx,
+    G: DirectedNeighbors<NI>,
+{
+    graph
+        .in_neighbors(node)
+        .map(|v| scores[v.index()])
+        .sum::<f32>()
+}
+
+/// Scales the scores to unit length unless they are all zero.
+fn normalize(scores: &mut [f32]) {
+    let norm = scores
+        .par_iter()
+        .map(|score| (*score as f64).powi(2))
+        .sum::<f64>()
+        .sqrt();
+
+    if norm > 0.0 {
+        scores
+            .par_iter_mut()
+            .for_each(|score| *score = (*score as f64 / norm) as f32);
+    }
+}
+
+/// Returns the sum of the absolute differences of the scores.
+fn distance(scores: &[f32], next_scores: &[f32]) -> f64 {
+    scores
+        .par_iter()
+        .zip(next_scores)
+        .map(|(a, b)| (a - b).abs() as f64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::symmetric;
+
+    #[test]
+    fn eigenvector_bipartite_test() {
+        // A cycle of even length is bipartite, all nodes are equally central.
+        let edges = (0..6_u32).map(|n| (n, (n + 1) % 6)).collect::<Vec<_>>();
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new().edges(edges).build();
+
+        let config = EigenvectorConfig::new(100, 1E-6);
+        let (scores, iterations, _) = eigenvector_centrality(&symmetric(&graph), config);
+
+        assert!(iterations < 100);
+        for score in scores {
+            assert!((score - (1.0_f32 / 6.0).sqrt()).abs() < 1E-5);
+        }
+    }
+
+    #[test]
+    fn hits_test() {
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+            .edges(vec![(0, 2), (1, 2), (1, 3)])
+            .build();
+
+        let config = EigenvectorConfig::new(100, 1E-7);
+        let (hits, _, _) = hits(&graph, config);
+
+        // The authorities are the principal eigenvector of [[2, 1], [1, 1]].
+        let ratio = (5.0_f32.sqrt() - 1.0) / 2.0;
+        let authority = 1.0 / (1.0 + ratio * ratio).sqrt();
+
+        assert_eq!(hits.authorities[0], 0.0);
+        assert!((hits.authorities[2] - authority).abs() < 1E-4);
+        assert!((hits.authorities[3] - authority * ratio).abs() < 1E-4);
+        assert_eq!(hits.hubs[2], 0.0);
+        assert!(hits.hubs[1] > hits.hubs[0]);
+    }
+
+    #[test]
+    fn katz_test() {
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+            .edges(vec![(0, 2), (1, 2), (2, 3)])
+            .build();
+
+        let config = KatzConfig::new(100, 1E-6, 0.5, 2.0);
+        let (scores, iterations, error) = katz_centrality(&graph, config);
+
+        assert_eq!(scores, vec![2.0, 2.0, 4.0, 4.0]);
+        assert_eq!(error, 0.0);
+        assert_eq!(iterations, 4);
+
+        let config = KatzConfig::new(2, 1E-6, 0.5, 2.0);
+        let (_, iterations, error) = katz_centrality(&graph, config);
+
+        assert_eq!(iterations, 2);
+        assert!(error > 0.0);
+    }
+}
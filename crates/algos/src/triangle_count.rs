@@ -1,7 +1,9 @@
+use crate::intersect::{intersect_below, intersection_count_below};
 use crate::prelude::*;
 
 use log::info;
 use num_format::{Locale, ToFormattedString};
+use rayon::prelude::*;
 
 use std::sync::atomic::AtomicU64;
 use std::{sync::atomic::Ordering, time::Instant};
@@ -18,6 +20,10 @@ where
     info!("Relabeled graph in {:?}", start.elapsed());
 }
 
+/// Counts the triangles of the graph. The neighbor lists must be sorted.
+///
+/// Each thread copies the neighbors of the nodes it intersects into reusable
+/// buffers, so the neighbors do not need to be stored as slices.
 pub fn global_triangle_count<NI, G>(graph: &G) -> u64
 where
     NI: Idx,
@@ -32,6 +38,8 @@ where
         for _ in 0..rayon::current_num_threads() {
             s.spawn(|_| {
                 let mut triangles = 0;
+                let mut u_neighbors = Vec::new();
+                let mut v_neighbors = Vec::new();
 
                 loop {
                     let start = NI::fetch_add(&next_chunk, NI::new(CHUNK_SIZE), Ordering::AcqRel);
@@ -42,27 +50,14 @@ where
                     let end = (start + NI::new(CHUNK_SIZE)).min(graph.node_count());
 
                     for u in start..end {
-                        for &v in graph.neighbors(u) {
-                            if v > u {
-                                break;
-                            }
-
-                            let mut it = put_back_iterator(graph.neighbors(u));
-
-                            for &w in graph.neighbors(v) {
-                                if w > v {
-                                    break;
-                                }
-                                while let Some(x) = it.next() {
-                                    if x >= &w {
-                                        if x == &w {
-                                            triangles += 1;
-                                        }
-                                        it.put_back(x);
-                                        break;
-                                    }
-                                }
-                            }
+                        u_neighbors.clear();
+                        u_neighbors.extend(graph.neighbors(u).copied().take_while(|&v| v <= u));
+
+                        for &v in &u_neighbors {
+                            v_neighbors.clear();
+                            v_neighbors.extend(graph.neighbors(v).copied().take_while(|&w| w < v));
+                            triangles +=
+                                intersection_count_below(&u_neighbors, &v_neighbors, v) as u64;
                         }
                     }
                 }
@@ -82,6 +77,141 @@ where
     tc
 }
 
+/// Counts the triangles that each node is part of.
+///
+/// Like [`global_triangle_count`], every triangle is found once from its
+/// largest node and counted for all three nodes. The neighbor lists must be
+/// sorted. Running [`relabel_graph`] first is not required but speeds up the
+/// computation on graphs with skewed degree distributions. The counts always
+/// refer to the node ids of the given graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .csr_layout(CsrLayout::Deduplicated)
+///     .edges(vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)])
+///     .build();
+///
+/// assert_eq!(local_triangle_count(&graph), vec![1, 2, 2, 1]);
+/// ```
+pub fn local_triangle_count<'g, NI, G>(graph: &'g G) -> Vec<u64>
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedNeighbors<NI, NeighborsIterator<'g> = std::slice::Iter<'g, NI>> + Sync,
+{
+    let start = Instant::now();
+
+    let next_chunk = Atomic::new(NI::zero());
+    let triangles = (0..graph.node_count().index())
+        .map(|_| AtomicU64::new(0))
+        .collect::<Vec<_>>();
+
+    rayon::scope(|s| {
+        for _ in 0..rayon::current_num_threads() {
+            s.spawn(|_| loop {
+                let start = NI::fetch_add(&next_chunk, NI::new(CHUNK_SIZE), Ordering::AcqRel);
+                if start >= graph.node_count() {
+                    break;
+                }
+
+                let end = (start + NI::new(CHUNK_SIZE)).min(graph.node_count());
+
+                for u in start..end {
+                    let u_neighbors = graph.neighbors(u).as_slice();
+                    let mut u_triangles = 0;
+
+                    for &v in u_neighbors {
+                        if v > u {
+                            break;
+                        }
+
+                        let v_neighbors = graph.neighbors(v).as_slice();
+                        let mut v_triangles = 0;
+
+                        intersect_below(u_neighbors, v_neighbors, v, |w| {
+                            triangles[w.index()].fetch_add(1, Ordering::AcqRel);
+                            v_triangles += 1;
+                        });
+
+                        if v_triangles > 0 {
+                            triangles[v.index()].fetch_add(v_triangles, Ordering::AcqRel);
+                            u_triangles += v_triangles;
+                        }
+                    }
+
+                    triangles[u.index()].fetch_add(u_triangles, Ordering::AcqRel);
+                }
+            });
+        }
+    });
+
+    info!("Computed local triangle counts in {:?}", start.elapsed());
+
+    triangles.into_iter().map(AtomicU64::into_inner).collect()
+}
+
+/// Computes the local clustering coefficient of each node, i.e., the number
+/// of triangles of the node divided by the number of pairs of its neighbors.
+/// Nodes with less than two neighbors have a coefficient of zero.
+///
+/// See [`local_triangle_count`] for the requirements on the graph.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .csr_layout(CsrLayout::Deduplicated)
+///     .edges(vec![(0, 1), (0, 2), (1, 2), (2, 3)])
+///     .build();
+///
+/// let coefficients = local_clustering_coefficient(&graph);
+///
+/// assert_eq!(coefficients, vec![1.0, 1.0, 1.0 / 3.0, 0.0]);
+/// ```
+pub fn local_clustering_coefficient<'g, NI, G>(graph: &'g G) -> Vec<f64>
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedNeighbors<NI, NeighborsIterator<'g> = std::slice::Iter<'g, NI>> + Sync,
+{
+    let triangles = local_triangle_count(graph);
+
+    triangles
+        .into_par_iter()
+        .enumerate()
+        .map(|(node, triangles)| {
+            let degree = graph.neighbors(NI::new(node)).len() as f64;
+            if degree < 2.0 {
+                0.0
+            } else {
+                2.0 * triangles as f64 / (degree * (degree - 1.0))
+            }
+        })
+        .collect()
+}
+
+/// Computes the average of the local clustering coefficients of all nodes,
+/// including the nodes with less than two neighbors.
+pub fn average_clustering_coefficient<'g, NI, G>(graph: &'g G) -> f64
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedNeighbors<NI, NeighborsIterator<'g> = std::slice::Iter<'g, NI>> + Sync,
+{
+    let node_count = graph.node_count().index();
+    if node_count == 0 {
+        return 0.0;
+    }
+
+    local_clustering_coefficient(graph)
+        .into_par_iter()
+        .sum::<f64>()
+        / node_count as f64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,4 +255,57 @@ mod tests {
 
         assert_eq!(global_triangle_count(&graph), 2);
     }
+
+    #[test]
+    fn test_local_tc_diamond() {
+        let gdl = "(a)-->(b)-->(c)<--(a),(b)-->(d)<--(c)";
+
+        let graph: UndirectedCsrGraph<usize> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Deduplicated)
+            .gdl_str::<usize, _>(gdl)
+            .build()
+            .unwrap();
+
+        assert_eq!(local_triangle_count(&graph), vec![1, 2, 2, 1]);
+        assert_eq!(
+            local_clustering_coefficient(&graph),
+            vec![1.0, 2.0 / 3.0, 2.0 / 3.0, 1.0]
+        );
+        assert!((average_clustering_coefficient(&graph) - 5.0 / 6.0).abs() < 1E-9);
+    }
+
+    #[test]
+    fn test_local_tc_relabeled() {
+        // A clique of size 5 with a tail of length 3 and many chunks
+        let mut edges = Vec::new();
+        for offset in (0..1000).step_by(8) {
+            for u in 0..5 {
+                for v in u + 1..5 {
+                    edges.push((offset + u, offset + v));
+                }
+            }
+            for u in 4..7 {
+                edges.push((offset + u, offset + u + 1));
+            }
+        }
+
+        let mut graph: UndirectedCsrGraph<usize> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Deduplicated)
+            .edges(edges)
+            .build();
+
+        let expected = (0..1000)
+            .map(|node| if node % 8 < 5 { 6 } else { 0 })
+            .collect::<Vec<_>>();
+        assert_eq!(local_triangle_count(&graph), expected);
+
+        relabel_graph(&mut graph);
+
+        let triangles = local_triangle_count(&graph);
+        assert_eq!(
+            triangles.iter().sum::<u64>(),
+            3 * global_triangle_count(&graph)
+        );
+        assert_eq!(triangles.iter().filter(|t| **t == 6).count(), 5 * 125);
+    }
 }
@@ -99,3 +99,40 @@ where
         self.iter.fold(accum, f)
     }
 }
+
+/// Edge values that can be used as edge weights.
+///
+/// Graphs without edge values, i.e., with `()` as edge value type, have a
+/// weight of one on every edge.
+pub trait EdgeWeight: Copy + Send + Sync {
+    /// `true` if the edge values carry weights, `false` if every edge has a
+    /// weight of one.
+    const WEIGHTED: bool;
+
+    /// Returns the weight of the edge.
+    fn weight(self) -> f64;
+}
+
+impl EdgeWeight for () {
+    const WEIGHTED: bool = false;
+
+    fn weight(self) -> f64 {
+        1.0
+    }
+}
+
+macro_rules! impl_edge_weight {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl EdgeWeight for $ty {
+                const WEIGHTED: bool = true;
+
+                fn weight(self) -> f64 {
+                    f64::from(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_edge_weight!(f32, f64, i8, i16, i32, u8, u16, u32);
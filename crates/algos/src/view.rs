@@ -0,0 +1,269 @@
+//! Views that present a graph with a different orientation of its edges
+//! without copying it.
+//!
+//! Each view borrows the original graph and implements the graph traits by
+//! delegating to it, which allows running an algorithm written for one kind
+//! of graph on another, e.g., [`edge_map`](crate::frontier::edge_map) on an
+//! undirected graph using [`symmetric`], pulling along the outgoing edges of
+//! a directed graph using [`transpose`], or label propagation on a directed
+//! graph using [`undirected`].
+use crate::prelude::*;
+
+use std::iter::Chain;
+
+/// Returns a view of an undirected graph as a directed graph in which every
+/// edge exists in both directions, which allows running `edge_map` on
+/// undirected graphs.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::view::symmetric;
+///
+/// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2)])
+///     .build();
+///
+/// let directed = symmetric(&graph);
+///
+/// assert_eq!(directed.out_degree(1), 1);
+/// assert_eq!(directed.in_neighbors(0).as_slice(), &[1, 2]);
+/// ```
+pub fn symmetric<G>(graph: &G) -> Symmetric<'_, G> {
+    Symmetric(graph)
+}
+
+/// An undirected graph viewed as a directed graph, see [`symmetric`].
+#[derive(Debug)]
+pub struct Symmetric<'g, G>(&'g G);
+
+impl<G> Clone for Symmetric<'_, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for Symmetric<'_, G> {}
+
+impl<'g, G> Symmetric<'g, G> {
+    /// Returns the original graph.
+    pub fn inner(&self) -> &'g G {
+        self.0
+    }
+}
+
+impl<NI: Idx, G: Graph<NI>> Graph<NI> for Symmetric<'_, G> {
+    fn node_count(&self) -> NI {
+        self.0.node_count()
+    }
+
+    fn edge_count(&self) -> NI {
+        NI::new(self.0.edge_count().index() * 2)
+    }
+}
+
+impl<NI: Idx, G: UndirectedDegrees<NI>> DirectedDegrees<NI> for Symmetric<'_, G> {
+    fn out_degree(&self, node: NI) -> NI {
+        self.0.degree(node)
+    }
+
+    fn in_degree(&self, node: NI) -> NI {
+        self.0.degree(node)
+    }
+}
+
+impl<NI: Idx, G: UndirectedNeighbors<NI>> DirectedNeighbors<NI> for Symmetric<'_, G> {
+    type NeighborsIterator<'a> = G::NeighborsIterator<'a> where Self: 'a;
+
+    fn out_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.neighbors(node)
+    }
+
+    fn in_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.neighbors(node)
+    }
+}
+
+/// Returns a view of the graph with all edges reversed, i.e., the transposed
+/// adjacency matrix.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::view::transpose;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2)])
+///     .build();
+///
+/// let transposed = transpose(&graph);
+///
+/// assert_eq!(transposed.out_degree(0), 0);
+/// assert_eq!(transposed.out_neighbors(2).as_slice(), &[0]);
+/// ```
+pub fn transpose<G>(graph: &G) -> Transposed<'_, G> {
+    Transposed(graph)
+}
+
+/// A directed graph with all edges reversed, see [`transpose`].
+#[derive(Debug)]
+pub struct Transposed<'g, G>(&'g G);
+
+impl<G> Clone for Transposed<'_, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for Transposed<'_, G> {}
+
+impl<'g, G> Transposed<'g, G> {
+    /// Returns the original graph.
+    pub fn inner(&self) -> &'g G {
+        self.0
+    }
+}
+
+impl<NI: Idx, G: Graph<NI>> Graph<NI> for Transposed<'_, G> {
+    fn node_count(&self) -> NI {
+        self.0.node_count()
+    }
+
+    fn edge_count(&self) -> NI {
+        self.0.edge_count()
+    }
+}
+
+impl<NI: Idx, G: DirectedDegrees<NI>> DirectedDegrees<NI> for Transposed<'_, G> {
+    fn out_degree(&self, node: NI) -> NI {
+        self.0.in_degree(node)
+    }
+
+    fn in_degree(&self, node: NI) -> NI {
+        self.0.out_degree(node)
+    }
+}
+
+impl<NI: Idx, G: DirectedNeighbors<NI>> DirectedNeighbors<NI> for Transposed<'_, G> {
+    type NeighborsIterator<'a> = G::NeighborsIterator<'a> where Self: 'a;
+
+    fn out_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.in_neighbors(node)
+    }
+
+    fn in_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.out_neighbors(node)
+    }
+}
+
+impl<NI, EV, G> DirectedNeighborsWithValues<NI, EV> for Transposed<'_, G>
+where
+    NI: Idx,
+    G: DirectedNeighborsWithValues<NI, EV>,
+{
+    type NeighborsIterator<'a> = G::NeighborsIterator<'a> where Self: 'a, EV: 'a;
+
+    fn out_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.in_neighbors_with_values(node)
+    }
+
+    fn in_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.out_neighbors_with_values(node)
+    }
+}
+
+/// Returns a view of a directed graph as an undirected graph in which the
+/// neighbors of a node are its outgoing neighbors followed by its incoming
+/// neighbors, which allows running algorithms for undirected graphs, e.g.,
+/// label propagation, on directed graphs.
+///
+/// # Example
+///
+/// ```
+/// use graph::prelude::*;
+/// use graph::view::undirected;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (2, 0)])
+///     .build();
+///
+/// let undirected = undirected(&graph);
+///
+/// assert_eq!(undirected.degree(0), 2);
+/// assert_eq!(undirected.neighbors(0).copied().collect::<Vec<_>>(), vec![1, 2]);
+/// ```
+pub fn undirected<G>(graph: &G) -> Undirected<'_, G> {
+    Undirected(graph)
+}
+
+/// A directed graph viewed as an undirected graph, see [`undirected`].
+#[derive(Debug)]
+pub struct Undirected<'g, G>(&'g G);
+
+impl<G> Clone for Undirected<'_, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for Undirected<'_, G> {}
+
+impl<'g, G> Undirected<'g, G> {
+    /// Returns the original graph.
+    pub fn inner(&self) -> &'g G {
+        self.0
+    }
+}
+
+impl<NI: Idx, G: Graph<NI>> Graph<NI> for Undirected<'_, G> {
+    fn node_count(&self) -> NI {
+        self.0.node_count()
+    }
+
+    fn edge_count(&self) -> NI {
+        self.0.edge_count()
+    }
+}
+
+impl<NI: Idx, NV, G: NodeValues<NI, NV>> NodeValues<NI, NV> for Undirected<'_, G> {
+    fn node_value(&self, node: NI) -> &NV {
+        self.0.node_value(node)
+    }
+}
+
+impl<NI: Idx, G: DirectedDegrees<NI>> UndirectedDegrees<NI> for Undirected<'_, G> {
+    fn degree(&self, node: NI) -> NI {
+        self.0.out_degree(node) + self.0.in_degree(node)
+    }
+}
+
+impl<NI: Idx, G: DirectedNeighbors<NI>> UndirectedNeighbors<NI> for Undirected<'_, G> {
+    type NeighborsIterator<'a>
+        = Chain<G::NeighborsIterator<'a>, G::NeighborsIterator<'a>>
+    where
+        Self: 'a;
+
+    fn neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0.out_neighbors(node).chain(self.0.in_neighbors(node))
+    }
+}
+
+impl<NI, EV, G> UndirectedNeighborsWithValues<NI, EV> for Undirected<'_, G>
+where
+    NI: Idx,
+    G: DirectedNeighborsWithValues<NI, EV>,
+{
+    type NeighborsIterator<'a>
+        = Chain<G::NeighborsIterator<'a>, G::NeighborsIterator<'a>>
+    where
+        Self: 'a,
+        EV: 'a;
+
+    fn neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.0
+            .out_neighbors_with_values(node)
+            .chain(self.0.in_neighbors_with_values(node))
+    }
+}
@@ -323,4 +323,17 @@ mod tests {
         assert_eq!(res.component(2), res.component(3));
         assert_ne!(res.component(1), res.component(2));
     }
+
+    #[test]
+    fn two_components_afforest_temporal_snapshot() {
+        let graph: TemporalCsrGraph<usize, u32> = GraphBuilder::new()
+            .edges_with_values(vec![(0, 1, 1), (1, 2, 10), (2, 3, 1)])
+            .build();
+
+        let res = wcc_afforest(&graph.snapshot(0, 5), WccConfig::default());
+
+        assert_eq!(res.component(0), res.component(1));
+        assert_eq!(res.component(2), res.component(3));
+        assert_ne!(res.component(1), res.component(2));
+    }
 }
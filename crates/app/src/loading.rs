@@ -73,3 +73,17 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_compressed_edge_list() {
+        let path = [env!("CARGO_MANIFEST_DIR"), "..", "builder", "resources", "test.el.gz"]
+            .iter()
+            .collect::<PathBuf>();
+
+        assert!(load::<DirectedCsrGraph<u32, (), ()>, u32, ()>(path).is_ok());
+    }
+}
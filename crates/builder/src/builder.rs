@@ -1,7 +1,12 @@
 use std::{convert::TryFrom, marker::PhantomData};
 
+#[cfg(feature = "gdl")]
+use crate::input::gdl::{edge_list_from_gdl, node_values_from_gdl, GdlValue, MyCypherValue};
 use crate::{
-    graph::csr::{CsrLayout, NodeValues},
+    graph::{
+        bipartite::Partition,
+        csr::{CsrLayout, NodeValues},
+    },
     index::Idx,
     input::{edgelist::EdgeList, InputCapabilities, InputPath},
     prelude::edgelist::{EdgeIterator, EdgeWithValueIterator},
@@ -42,6 +47,28 @@ where
     _node: PhantomData<NI>,
 }
 
+pub struct FromBipartiteEdges<NI, Edges>
+where
+    NI: Idx,
+    Edges: IntoIterator<Item = (NI, NI)>,
+{
+    csr_layout: CsrLayout,
+    source_partition: Partition,
+    edges: Edges,
+    _node: PhantomData<NI>,
+}
+
+pub struct FromBipartiteEdgesWithValues<NI, Edges, EV>
+where
+    NI: Idx,
+    Edges: IntoIterator<Item = (NI, NI, EV)>,
+{
+    csr_layout: CsrLayout,
+    source_partition: Partition,
+    edges: Edges,
+    _node: PhantomData<NI>,
+}
+
 #[cfg(feature = "gdl")]
 #[doc(cfg(feature = "gdl"))]
 pub struct FromGdlString<NI>
@@ -50,6 +77,20 @@ where
 {
     csr_layout: CsrLayout,
     gdl: String,
+    edge_value: Option<GdlValue>,
+    _node: PhantomData<NI>,
+}
+
+#[cfg(feature = "gdl")]
+#[doc(cfg(feature = "gdl"))]
+pub struct FromGdlStringAndNodeValues<NI>
+where
+    NI: Idx,
+{
+    csr_layout: CsrLayout,
+    gdl: String,
+    edge_value: Option<GdlValue>,
+    node_value: GdlValue,
     _node: PhantomData<NI>,
 }
 
@@ -234,6 +275,84 @@ impl GraphBuilder<Uninitialized> {
         }
     }
 
+    /// Create a bipartite graph from the given edge tuples.
+    ///
+    /// Each tuple connects a node of the `source_partition` with a node of the
+    /// opposite partition. Left and right nodes use separate id spaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// // user -> item interactions, items form the left partition
+    /// let graph: BipartiteCsrGraph<usize> = GraphBuilder::new()
+    ///     .bipartite_edges(vec![(0, 0), (0, 1), (1, 1), (2, 0)], Partition::Right)
+    ///     .build();
+    ///
+    /// assert_eq!(graph.left_node_count(), 2);
+    /// assert_eq!(graph.right_node_count(), 3);
+    /// assert_eq!(graph.left_degree(1), 2);
+    /// ```
+    pub fn bipartite_edges<NI, Edges>(
+        self,
+        edges: Edges,
+        source_partition: Partition,
+    ) -> GraphBuilder<FromBipartiteEdges<NI, Edges>>
+    where
+        NI: Idx,
+        Edges: IntoIterator<Item = (NI, NI)>,
+    {
+        GraphBuilder {
+            state: FromBipartiteEdges {
+                csr_layout: self.state.csr_layout,
+                source_partition,
+                edges,
+                _node: PhantomData,
+            },
+        }
+    }
+
+    /// Create a bipartite graph from the given edge triplets.
+    ///
+    /// Each triplet connects a node of the `source_partition` with a node of
+    /// the opposite partition. Left and right nodes use separate id spaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let graph: BipartiteCsrGraph<usize, f32> = GraphBuilder::new()
+    ///     .bipartite_edges_with_values(vec![(0, 0, 0.5), (0, 1, 1.5), (2, 0, 2.5)], Partition::Left)
+    ///     .build();
+    ///
+    /// assert_eq!(graph.left_node_count(), 3);
+    /// assert_eq!(graph.right_node_count(), 2);
+    /// assert_eq!(
+    ///     graph.right_neighbors_with_values(0).as_slice(),
+    ///     &[Target::new(0, 0.5), Target::new(2, 2.5)]
+    /// );
+    /// ```
+    pub fn bipartite_edges_with_values<NI, Edges, EV>(
+        self,
+        edges: Edges,
+        source_partition: Partition,
+    ) -> GraphBuilder<FromBipartiteEdgesWithValues<NI, Edges, EV>>
+    where
+        NI: Idx,
+        Edges: IntoIterator<Item = (NI, NI, EV)>,
+    {
+        GraphBuilder {
+            state: FromBipartiteEdgesWithValues {
+                csr_layout: self.state.csr_layout,
+                source_partition,
+                edges,
+                _node: PhantomData,
+            },
+        }
+    }
+
     /// Creates a graph using Graph Definition Language (GDL).
     ///
     /// Creating graphs from GDL is recommended for small graphs only, e.g.,
@@ -256,9 +375,10 @@ impl GraphBuilder<Uninitialized> {
     /// assert_eq!(g.edge_count(), 2);
     /// ```
     ///
-    /// One can also create weighted graphs using GDL. There needs to be exactly
-    /// one edge property with the same type as specified for the edge value.
-    /// The property key is not relevant.
+    /// One can also create weighted graphs using GDL. By default, there needs
+    /// to be at most one edge property with the same type as specified for the
+    /// edge value. The property key is not relevant. Use
+    /// [`GraphBuilder::edge_values`] to select a property by key instead.
     ///
     /// ```
     /// use graph_builder::prelude::*;
@@ -288,6 +408,7 @@ impl GraphBuilder<Uninitialized> {
             state: FromGdlString {
                 csr_layout: self.state.csr_layout,
                 gdl: gdl.into(),
+                edge_value: None,
                 _node: PhantomData,
             },
         }
@@ -487,19 +608,156 @@ impl<NI: Idx, NV, EV> GraphBuilder<FromEdgeListAndNodeValues<NI, NV, EV>> {
     }
 }
 
+impl<NI, Edges> GraphBuilder<FromBipartiteEdges<NI, Edges>>
+where
+    NI: Idx,
+    Edges: IntoIterator<Item = (NI, NI)>,
+{
+    /// Build the bipartite graph from the given vec of edges.
+    pub fn build<Graph>(self) -> Graph
+    where
+        Graph: From<(EdgeList<NI, ()>, Partition, CsrLayout)>,
+    {
+        Graph::from((
+            EdgeList::from(EdgeIterator(self.state.edges)),
+            self.state.source_partition,
+            self.state.csr_layout,
+        ))
+    }
+}
+
+impl<NI, Edges, EV> GraphBuilder<FromBipartiteEdgesWithValues<NI, Edges, EV>>
+where
+    NI: Idx,
+    EV: Sync,
+    Edges: IntoIterator<Item = (NI, NI, EV)>,
+{
+    /// Build the bipartite graph from the given vec of edges.
+    pub fn build<Graph>(self) -> Graph
+    where
+        Graph: From<(EdgeList<NI, EV>, Partition, CsrLayout)>,
+    {
+        Graph::from((
+            EdgeList::new(self.state.edges.into_iter().collect()),
+            self.state.source_partition,
+            self.state.csr_layout,
+        ))
+    }
+}
+
 #[cfg(feature = "gdl")]
 #[doc(cfg(feature = "gdl"))]
 impl<NI> GraphBuilder<FromGdlString<NI>>
 where
     NI: Idx,
 {
+    /// Selects the relationship property or type from which edge values are
+    /// read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let g: DirectedCsrGraph<usize, (), f32> = GraphBuilder::new()
+    ///     .gdl_str::<usize, _>("(a)-[{w: 0.42, c: 2}]->(b)")
+    ///     .edge_values(GdlValue::property("w"))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(g.out_neighbors_with_values(0).as_slice(), &[Target::new(1, 0.42)]);
+    ///
+    /// // relationship types are mapped to ids in lexicographic order
+    /// let g: DirectedCsrGraph<usize, (), usize> = GraphBuilder::new()
+    ///     .gdl_str::<usize, _>("(a)-[:KNOWS]->(b),(a)-[:BLOCKS]->(c)")
+    ///     .edge_values(GdlValue::Label)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut types = g
+    ///     .out_neighbors_with_values(0)
+    ///     .map(|t| t.value)
+    ///     .collect::<Vec<_>>();
+    /// types.sort_unstable();
+    ///
+    /// assert_eq!(types, vec![0, 1]);
+    /// ```
+    pub fn edge_values(mut self, edge_value: GdlValue) -> Self {
+        self.state.edge_value = Some(edge_value);
+        self
+    }
+
+    /// Selects the node property or label from which node values are read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let g: DirectedCsrGraph<usize, i64> = GraphBuilder::new()
+    ///     .gdl_str::<usize, _>("(a { age: 42 })-->(b { age: 23 })")
+    ///     .node_values(GdlValue::property("age"))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(*g.node_value(0), 42);
+    /// assert_eq!(*g.node_value(1), 23);
+    /// ```
+    pub fn node_values(self, node_value: GdlValue) -> GraphBuilder<FromGdlStringAndNodeValues<NI>> {
+        GraphBuilder {
+            state: FromGdlStringAndNodeValues {
+                csr_layout: self.state.csr_layout,
+                gdl: self.state.gdl,
+                edge_value: self.state.edge_value,
+                node_value,
+                _node: PhantomData,
+            },
+        }
+    }
+
     /// Builds the graph from the given GDL string.
-    pub fn build<Graph>(self) -> Result<Graph, Error>
+    ///
+    /// Returns an error if the GDL string cannot be parsed or if a selected
+    /// value is missing or has an unexpected type.
+    pub fn build<Graph, EV>(self) -> Result<Graph, Error>
     where
-        Graph: From<(gdl::Graph, CsrLayout)>,
+        Graph: From<(EdgeList<NI, EV>, CsrLayout)>,
+        EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Send + Sync,
+    {
+        let gdl_graph = self.state.gdl.parse::<gdl::Graph>()?;
+        let edge_list = edge_list_from_gdl(&gdl_graph, self.state.edge_value.as_ref())?;
+        let graph = Graph::from((edge_list, self.state.csr_layout));
+        Ok(graph)
+    }
+}
+
+#[cfg(feature = "gdl")]
+#[doc(cfg(feature = "gdl"))]
+impl<NI> GraphBuilder<FromGdlStringAndNodeValues<NI>>
+where
+    NI: Idx,
+{
+    /// Selects the relationship property or type from which edge values are
+    /// read.
+    pub fn edge_values(mut self, edge_value: GdlValue) -> Self {
+        self.state.edge_value = Some(edge_value);
+        self
+    }
+
+    /// Builds the graph from the given GDL string.
+    ///
+    /// Returns an error if the GDL string cannot be parsed or if a selected
+    /// value is missing or has an unexpected type.
+    pub fn build<Graph, NV, EV>(self) -> Result<Graph, Error>
+    where
+        Graph: From<(NodeValues<NV>, EdgeList<NI, EV>, CsrLayout)>,
+        NV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error>,
+        EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Send + Sync,
     {
         let gdl_graph = self.state.gdl.parse::<gdl::Graph>()?;
-        let graph = Graph::from((gdl_graph, self.state.csr_layout));
+        let node_values = node_values_from_gdl(&gdl_graph, &self.state.node_value)?;
+        let edge_list = edge_list_from_gdl(&gdl_graph, self.state.edge_value.as_ref())?;
+        let graph = Graph::from((node_values, edge_list, self.state.csr_layout));
         Ok(graph)
     }
 }
@@ -513,9 +771,13 @@ where
     /// Build the graph from the given GDL graph.
     pub fn build<Graph>(self) -> Result<Graph, Error>
     where
-        Graph: From<(&'a gdl::Graph, CsrLayout)>,
+        Graph: TryFrom<(&'a gdl::Graph, CsrLayout)>,
+        Error: From<Graph::Error>,
     {
-        Ok(Graph::from((self.state.gdl_graph, self.state.csr_layout)))
+        Ok(Graph::try_from((
+            self.state.gdl_graph,
+            self.state.csr_layout,
+        ))?)
     }
 }
 
@@ -554,6 +816,7 @@ where
         crate::Error: From<Graph::Error>,
     {
         let input = Format::GraphInput::try_from(InputPath(self.state.path))?;
+        let input = self.state.format.configure(input);
         let graph = Graph::try_from((input, self.state.csr_layout))?;
 
         Ok(graph)
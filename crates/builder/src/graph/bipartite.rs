@@ -0,0 +1,385 @@
+use log::info;
+use std::time::Instant;
+
+use rayon::prelude::*;
+
+use crate::{
+    graph::csr::{Csr, CsrLayout, DirectedCsrGraph, NodeValues, Target, UndirectedCsrGraph},
+    graph_ops::{ToDirectedOp, ToUndirectedOp},
+    index::Idx,
+    input::{edgelist::Edges, Direction, Graph500},
+    memory::{MemoryFootprint, MemoryUsage},
+    BipartiteDegrees, BipartiteGraph, BipartiteNeighbors, BipartiteNeighborsWithValues,
+};
+
+/// Identifies one of the two node sets of a bipartite graph.
+///
+/// When building a [`BipartiteCsrGraph`] from an edge list, the partition
+/// determines which node set the *source* ids of the edge tuples belong to.
+/// The target ids belong to the other partition.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Partition {
+    /// Source ids are left nodes. This is the default.
+    #[default]
+    Left,
+    /// Source ids are right nodes.
+    Right,
+}
+
+/// A bipartite graph with separate id spaces for left and right nodes.
+///
+/// The graph stores two CSRs: one that maps each left node to its right
+/// neighbors and one that maps each right node to its left neighbors.
+pub struct BipartiteCsrGraph<NI: Idx, EV = ()> {
+    csr_left: Csr<NI, NI, EV>,
+    csr_right: Csr<NI, NI, EV>,
+}
+
+impl<NI: Idx, EV> BipartiteCsrGraph<NI, EV> {
+    pub fn new(csr_left: Csr<NI, NI, EV>, csr_right: Csr<NI, NI, EV>) -> Self {
+        let g = Self {
+            csr_left,
+            csr_right,
+        };
+        info!(
+            "Created bipartite graph (left_node_count = {:?}, right_node_count = {:?}, edge_count = {:?})",
+            g.left_node_count(),
+            g.right_node_count(),
+            g.edge_count()
+        );
+
+        g
+    }
+
+    /// Returns the same graph with left and right partition swapped.
+    ///
+    /// This is a constant time operation.
+    #[must_use]
+    pub fn swap_partitions(self) -> Self {
+        Self {
+            csr_left: self.csr_right,
+            csr_right: self.csr_left,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.left_node_count() + self.right_node_count() == NI::zero()
+    }
+}
+
+impl<NI: Idx, EV> MemoryUsage for BipartiteCsrGraph<NI, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        self.csr_left.memory_usage() + self.csr_right.memory_usage()
+    }
+}
+
+impl<NI: Idx, EV> BipartiteGraph<NI> for BipartiteCsrGraph<NI, EV> {
+    fn left_node_count(&self) -> NI {
+        self.csr_left.node_count()
+    }
+
+    fn right_node_count(&self) -> NI {
+        self.csr_right.node_count()
+    }
+
+    fn edge_count(&self) -> NI {
+        self.csr_left.edge_count()
+    }
+}
+
+impl<NI: Idx, EV> BipartiteDegrees<NI> for BipartiteCsrGraph<NI, EV> {
+    fn left_degree(&self, node: NI) -> NI {
+        self.csr_left.degree(node)
+    }
+
+    fn right_degree(&self, node: NI) -> NI {
+        self.csr_right.degree(node)
+    }
+}
+
+impl<NI: Idx> BipartiteNeighbors<NI> for BipartiteCsrGraph<NI, ()> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, NI>;
+
+    fn left_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_left.targets(node).iter()
+    }
+
+    fn right_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_right.targets(node).iter()
+    }
+}
+
+impl<NI: Idx, EV> BipartiteNeighborsWithValues<NI, EV> for BipartiteCsrGraph<NI, EV> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, Target<NI, EV>> where EV: 'a;
+
+    fn left_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_left.targets_with_values(node).iter()
+    }
+
+    fn right_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_right.targets_with_values(node).iter()
+    }
+}
+
+impl<NI, EV> ToDirectedOp for BipartiteCsrGraph<NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    type Directed = DirectedCsrGraph<NI, (), EV>;
+
+    fn to_directed(&self, layout: impl Into<Option<CsrLayout>>) -> Self::Directed {
+        if self.is_empty() {
+            return DirectedCsrGraph::new(NodeValues::new(vec![]), empty_csr(), empty_csr());
+        }
+
+        let layout = layout.into().unwrap_or_default();
+        let edges = SingleIdSpaceEdges { g: self };
+
+        DirectedCsrGraph::from((edges, layout))
+    }
+}
+
+impl<NI, EV> ToUndirectedOp for BipartiteCsrGraph<NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    type Undirected = UndirectedCsrGraph<NI, (), EV>;
+
+    fn to_undirected(&self, layout: impl Into<Option<CsrLayout>>) -> Self::Undirected {
+        if self.is_empty() {
+            return UndirectedCsrGraph::new(NodeValues::new(vec![]), empty_csr());
+        }
+
+        let layout = layout.into().unwrap_or_default();
+        let edges = SingleIdSpaceEdges { g: self };
+
+        UndirectedCsrGraph::from((edges, layout))
+    }
+}
+
+fn empty_csr<NI: Idx, EV>() -> Csr<NI, NI, EV> {
+    Csr::new(Box::new([NI::zero()]), Box::new([]))
+}
+
+// Exposes the edges of a bipartite graph in a single id space, where left
+// nodes keep their id and right node `v` becomes `left_node_count + v`.
+// Must not be used for empty graphs, which have no max node id.
+struct SingleIdSpaceEdges<'g, NI: Idx, EV> {
+    g: &'g BipartiteCsrGraph<NI, EV>,
+}
+
+impl<'g, NI, EV> Edges for SingleIdSpaceEdges<'g, NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    type NI = NI;
+
+    type EV = EV;
+
+    type EdgeIter<'a> = impl ParallelIterator<Item = (Self::NI, Self::NI, Self::EV)>
+    where
+        Self: 'a;
+
+    fn edges(&self) -> Self::EdgeIter<'_> {
+        let offset = self.g.left_node_count();
+
+        (0..self.g.left_node_count().index())
+            .into_par_iter()
+            .flat_map_iter(move |n| {
+                let n = NI::new(n);
+                self.g
+                    .left_neighbors_with_values(n)
+                    .map(move |t| (n, offset + t.target, t.value))
+            })
+    }
+
+    fn max_node_id(&self) -> Self::NI {
+        self.g.left_node_count() + self.g.right_node_count() - NI::new(1)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        unimplemented!("This type is not used in tests")
+    }
+}
+
+impl<NI, EV, E> From<(E, Partition, CsrLayout)> for BipartiteCsrGraph<NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+    E: Edges<NI = NI, EV = EV>,
+{
+    fn from((edge_list, source_partition, csr_option): (E, Partition, CsrLayout)) -> Self {
+        info!("Creating bipartite graph");
+        let start = Instant::now();
+        // Counts are computed from the largest ids, so an empty edge list
+        // results in empty partitions.
+        let (source_count, target_count) = edge_list
+            .edges()
+            .map(|(s, t, _)| (s + NI::new(1), t + NI::new(1)))
+            .reduce(
+                || (NI::zero(), NI::zero()),
+                |(s1, t1), (s2, t2)| (NI::max(s1, s2), NI::max(t1, t2)),
+            );
+        info!("Computed node counts in {:?}", start.elapsed());
+
+        let (left, right) = match source_partition {
+            Partition::Left => (
+                (source_count, Direction::Outgoing),
+                (target_count, Direction::Incoming),
+            ),
+            Partition::Right => (
+                (target_count, Direction::Incoming),
+                (source_count, Direction::Outgoing),
+            ),
+        };
+
+        let start = Instant::now();
+        let csr_left = Csr::from((&edge_list, left.0, left.1, csr_option));
+        info!("Created left csr in {:?}.", start.elapsed());
+
+        let start = Instant::now();
+        let csr_right = Csr::from((&edge_list, right.0, right.1, csr_option));
+        info!("Created right csr in {:?}.", start.elapsed());
+
+        BipartiteCsrGraph::new(csr_left, csr_right)
+    }
+}
+
+impl<NI, EV, E> From<(E, CsrLayout)> for BipartiteCsrGraph<NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+    E: Edges<NI = NI, EV = EV>,
+{
+    fn from((edge_list, csr_option): (E, CsrLayout)) -> Self {
+        BipartiteCsrGraph::from((edge_list, Partition::Left, csr_option))
+    }
+}
+
+impl<NI: Idx> From<(Graph500<NI>, CsrLayout)> for BipartiteCsrGraph<NI> {
+    fn from((graph500, csr_layout): (Graph500<NI>, CsrLayout)) -> Self {
+        BipartiteCsrGraph::from((graph500.0, csr_layout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        builder::GraphBuilder, DirectedDegrees, DirectedNeighbors, Graph, UndirectedNeighbors,
+    };
+
+    use super::*;
+
+    #[test]
+    fn bipartite_from_edges_test() {
+        let g: BipartiteCsrGraph<usize> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 2), (0, 0), (1, 2), (3, 1)])
+            .build();
+
+        assert_eq!(g.left_node_count(), 4);
+        assert_eq!(g.right_node_count(), 3);
+        assert_eq!(g.edge_count(), 4);
+
+        assert_eq!(g.left_degree(0), 2);
+        assert_eq!(g.left_degree(2), 0);
+        assert_eq!(g.right_degree(2), 2);
+
+        assert_eq!(g.left_neighbors(0).as_slice(), &[0, 2]);
+        assert_eq!(g.left_neighbors(3).as_slice(), &[1]);
+        assert_eq!(g.right_neighbors(2).as_slice(), &[0, 1]);
+        assert_eq!(g.right_neighbors(1).as_slice(), &[3]);
+    }
+
+    #[test]
+    fn bipartite_from_empty_edges_test() {
+        let g: BipartiteCsrGraph<usize> = GraphBuilder::new()
+            .edges(Vec::<(usize, usize)>::new())
+            .build();
+
+        assert_eq!(g.left_node_count(), 0);
+        assert_eq!(g.right_node_count(), 0);
+        assert_eq!(g.edge_count(), 0);
+    }
+
+    #[test]
+    fn bipartite_empty_to_directed_and_undirected_test() {
+        let g: BipartiteCsrGraph<u32> = GraphBuilder::new().edges(Vec::<(u32, u32)>::new()).build();
+
+        let d = g.to_directed(CsrLayout::Sorted);
+        assert_eq!(d.node_count(), 0);
+        assert_eq!(d.edge_count(), 0);
+
+        let u = g.to_undirected(CsrLayout::Sorted);
+        assert_eq!(u.node_count(), 0);
+        assert_eq!(u.edge_count(), 0);
+    }
+
+    #[test]
+    fn bipartite_source_partition_test() {
+        let edges = vec![(0, 2, 0.5), (0, 0, 1.5), (1, 2, 2.5)];
+
+        let left: BipartiteCsrGraph<usize, f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .bipartite_edges_with_values(edges.clone(), Partition::Left)
+            .build();
+
+        let right: BipartiteCsrGraph<usize, f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .bipartite_edges_with_values(edges, Partition::Right)
+            .build();
+
+        assert_eq!(left.left_node_count(), right.right_node_count());
+        assert_eq!(left.right_node_count(), right.left_node_count());
+
+        assert_eq!(
+            left.left_neighbors_with_values(0).as_slice(),
+            right.right_neighbors_with_values(0).as_slice()
+        );
+        assert_eq!(
+            right.left_neighbors_with_values(2).as_slice(),
+            &[Target::new(0, 0.5), Target::new(1, 2.5)]
+        );
+
+        let swapped = left.swap_partitions();
+        assert_eq!(
+            swapped.left_neighbors_with_values(2).as_slice(),
+            right.left_neighbors_with_values(2).as_slice()
+        );
+    }
+
+    #[test]
+    fn bipartite_to_directed_test() {
+        let g: BipartiteCsrGraph<u32> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 0), (2, 1)])
+            .build();
+
+        let d = g.to_directed(CsrLayout::Sorted);
+
+        assert_eq!(d.node_count(), 5);
+        assert_eq!(d.edge_count(), 3);
+        assert_eq!(d.out_neighbors(0).as_slice(), &[4]);
+        assert_eq!(d.out_neighbors(1).as_slice(), &[3]);
+        assert_eq!(d.out_degree(3), 0);
+        assert_eq!(d.in_neighbors(4).as_slice(), &[0, 2]);
+        assert_eq!(d.in_degree(0), 0);
+    }
+
+    #[test]
+    fn bipartite_to_undirected_test() {
+        let g: BipartiteCsrGraph<u32> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 0), (2, 1)])
+            .build();
+
+        let u = g.to_undirected(CsrLayout::Sorted);
+
+        assert_eq!(u.node_count(), 5);
+        assert_eq!(u.edge_count(), 3);
+        assert_eq!(u.neighbors(1).as_slice(), &[3]);
+        assert_eq!(u.neighbors(4).as_slice(), &[0, 2]);
+    }
+}
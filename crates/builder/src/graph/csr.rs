@@ -16,12 +16,16 @@ use std::{
 use rayon::prelude::*;
 
 use crate::{
-    graph_ops::{DeserializeGraphOp, SerializeGraphOp, ToUndirectedOp},
+    graph_ops::{
+        DeserializeGraphOp, DirectedEdgeIdMappingOp, SerializeGraphOp, ToUndirectedOp,
+        UndirectedEdgeIdMappingOp,
+    },
     index::Idx,
     input::{edgelist::Edges, Direction, DotGraph, Graph500},
-    DirectedDegrees, DirectedNeighbors, DirectedNeighborsWithValues, Error, Graph,
-    NodeValues as NodeValuesTrait, SharedMut, UndirectedDegrees, UndirectedNeighbors,
-    UndirectedNeighborsWithValues,
+    memory::{MemoryFootprint, MemoryUsage},
+    DirectedDegrees, DirectedNeighbors, DirectedNeighborsWithEdgeIds, DirectedNeighborsWithValues,
+    Error, Graph, NodeValues as NodeValuesTrait, SharedMut, UndirectedDegrees, UndirectedNeighbors,
+    UndirectedNeighborsWithEdgeIds, UndirectedNeighborsWithValues,
 };
 
 /// Defines how the neighbor list of individual nodes are organized within the
@@ -99,6 +103,11 @@ impl<Index: Idx, NI, EV> Csr<Index, NI, EV> {
         Self { offsets, targets }
     }
 
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn into_parts(self) -> (Box<[Index]>, Box<[Target<NI, EV>]>) {
+        (self.offsets, self.targets)
+    }
+
     #[inline]
     pub(crate) fn node_count(&self) -> Index {
         Index::new(self.offsets.len() - 1)
@@ -124,8 +133,96 @@ impl<Index: Idx, NI, EV> Csr<Index, NI, EV> {
 
         &self.targets[from.index()..to.index()]
     }
+
+    #[inline]
+    pub(crate) fn targets_with_edge_ids(&self, i: Index) -> EdgeIdIter<'_, Index, NI, EV> {
+        let from = self.offsets[i.index()];
+        let to = self.offsets[(i + Index::new(1)).index()];
+
+        (from..to).zip(self.targets[from.index()..to.index()].iter())
+    }
+}
+
+impl<NI, EV> Csr<NI, NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    /// Sorts and optionally deduplicates the neighbor lists according to the
+    /// given layout.
+    pub(crate) fn with_layout(self, csr_layout: CsrLayout) -> Self {
+        let Csr {
+            offsets,
+            mut targets,
+        } = self;
+
+        match csr_layout {
+            CsrLayout::Unsorted => Csr::new(offsets, targets),
+            CsrLayout::Sorted => {
+                sort_targets(&offsets, &mut targets);
+                Csr::new(offsets, targets)
+            }
+            CsrLayout::Deduplicated => {
+                let (offsets, targets) = sort_and_deduplicate_targets(&offsets, &mut targets);
+                Csr::new(offsets.into_boxed_slice(), targets.into_boxed_slice())
+            }
+        }
+    }
+
+    /// Creates the CSR of the reversed edges, i.e., the incoming neighbor
+    /// lists of a CSR that stores outgoing neighbor lists.
+    pub(crate) fn transpose(&self, csr_layout: CsrLayout) -> Self {
+        if self.node_count() == NI::zero() {
+            return Csr::new(Box::new([NI::zero()]), Box::new([]));
+        }
+        let edges = CsrEdges { csr: self };
+        Csr::from((&edges, self.node_count(), Direction::Incoming, csr_layout))
+    }
 }
 
+struct CsrEdges<'c, NI: Idx, EV> {
+    csr: &'c Csr<NI, NI, EV>,
+}
+
+impl<'c, NI, EV> Edges for CsrEdges<'c, NI, EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    type NI = NI;
+
+    type EV = EV;
+
+    type EdgeIter<'a> = impl ParallelIterator<Item = (Self::NI, Self::NI, Self::EV)>
+    where
+        Self: 'a;
+
+    fn edges(&self) -> Self::EdgeIter<'_> {
+        (0..self.csr.node_count().index())
+            .into_par_iter()
+            .flat_map_iter(|n| {
+                let n = NI::new(n);
+                self.csr
+                    .targets_with_values(n)
+                    .iter()
+                    .map(move |t| (n, t.target, t.value))
+            })
+    }
+
+    fn max_node_id(&self) -> Self::NI {
+        self.csr.node_count() - NI::new(1)
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.csr.edge_count().index()
+    }
+}
+
+/// Iterates the targets of a node together with the ids of the connecting edges.
+pub type EdgeIdIter<'a, Index, NI, EV> =
+    std::iter::Zip<std::ops::Range<Index>, std::slice::Iter<'a, Target<NI, EV>>>;
+
 impl<Index: Idx, NI> Csr<Index, NI, ()> {
     #[inline]
     pub(crate) fn targets(&self, i: Index) -> &[NI] {
@@ -151,6 +248,13 @@ impl<Index: Idx, NI> Csr<Index, NI, ()> {
     }
 }
 
+impl<Index: Idx, NI, EV> MemoryUsage for Csr<Index, NI, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        MemoryFootprint::of_offsets::<Index>(self.offsets.len())
+            + MemoryFootprint::of_targets::<NI, EV>(self.targets.len())
+    }
+}
+
 pub trait SwapCsr<Index: Idx, NI, EV> {
     fn swap_csr(&mut self, csr: Csr<Index, NI, EV>) -> &mut Self;
 }
@@ -283,7 +387,7 @@ where
     NI: Idx + ToByteSlice,
     EV: ToByteSlice,
 {
-    fn serialize<W: Write>(&self, output: &mut W) -> Result<(), Error> {
+    pub(crate) fn serialize<W: Write>(&self, output: &mut W) -> Result<(), Error> {
         let type_name = std::any::type_name::<NI>().as_bytes();
         output.write_all([type_name.len()].as_byte_slice())?;
         output.write_all(type_name)?;
@@ -305,7 +409,7 @@ where
     NI: Idx + ToMutByteSlice,
     EV: ToMutByteSlice,
 {
-    fn deserialize<R: Read>(read: &mut R) -> Result<Csr<NI, NI, EV>, Error> {
+    pub(crate) fn deserialize<R: Read>(read: &mut R) -> Result<Csr<NI, NI, EV>, Error> {
         let mut type_name_len = [0_usize; 1];
         read.read_exact(type_name_len.as_mut_byte_slice())?;
         let [type_name_len] = type_name_len;
@@ -355,6 +459,12 @@ impl<NV> NodeValues<NV> {
     }
 }
 
+impl<NV> MemoryUsage for NodeValues<NV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        MemoryFootprint::of_node_values::<NV>(self.0.len())
+    }
+}
+
 impl<NV> FromIterator<NV> for NodeValues<NV> {
     fn from_iter<T: IntoIterator<Item = NV>>(iter: T) -> Self {
         Self(iter.into_iter().collect::<Vec<_>>().into_boxed_slice())
@@ -365,7 +475,7 @@ impl<NV> NodeValues<NV>
 where
     NV: ToByteSlice,
 {
-    fn serialize<W: Write>(&self, output: &mut W) -> Result<(), Error> {
+    pub(crate) fn serialize<W: Write>(&self, output: &mut W) -> Result<(), Error> {
         let node_count = self.0.len();
         let meta = [node_count];
         output.write_all(meta.as_byte_slice())?;
@@ -378,7 +488,7 @@ impl<NV> NodeValues<NV>
 where
     NV: ToMutByteSlice,
 {
-    fn deserialize<R: Read>(read: &mut R) -> Result<Self, Error> {
+    pub(crate) fn deserialize<R: Read>(read: &mut R) -> Result<Self, Error> {
         let mut meta = [0_usize; 1];
         read.read_exact(meta.as_mut_byte_slice())?;
         let [node_count] = meta;
@@ -422,6 +532,12 @@ impl<NI: Idx, NV, EV> DirectedCsrGraph<NI, NV, EV> {
     }
 }
 
+impl<NI: Idx, NV, EV> MemoryUsage for DirectedCsrGraph<NI, NV, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        self.node_values.memory_usage() + self.csr_out.memory_usage() + self.csr_inc.memory_usage()
+    }
+}
+
 impl<NI, NV, EV> ToUndirectedOp for DirectedCsrGraph<NI, NV, EV>
 where
     NI: Idx,
@@ -527,6 +643,44 @@ impl<NI: Idx, NV, EV> DirectedNeighborsWithValues<NI, EV> for DirectedCsrGraph<N
     }
 }
 
+impl<NI: Idx, NV, EV> DirectedNeighborsWithEdgeIds<NI, EV> for DirectedCsrGraph<NI, NV, EV> {
+    type NeighborsIterator<'a> = EdgeIdIter<'a, NI, NI, EV> where NV: 'a, EV: 'a;
+
+    fn out_neighbors_with_edge_ids(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_out.targets_with_edge_ids(node)
+    }
+
+    fn in_neighbors_with_edge_ids(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_inc.targets_with_edge_ids(node)
+    }
+}
+
+impl<NI, NV, EV> DirectedEdgeIdMappingOp<NI> for DirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    EV: Sync,
+{
+    fn in_to_out_edge_ids(&self) -> Vec<NI> {
+        let start = Instant::now();
+        let mapping = edge_id_mapping(&self.csr_inc, &self.csr_out);
+        info!(
+            "Computed incoming to outgoing edge ids in {:?}",
+            start.elapsed()
+        );
+        mapping
+    }
+
+    fn out_to_in_edge_ids(&self) -> Vec<NI> {
+        let start = Instant::now();
+        let mapping = edge_id_mapping(&self.csr_out, &self.csr_inc);
+        info!(
+            "Computed outgoing to incoming edge ids in {:?}",
+            start.elapsed()
+        );
+        mapping
+    }
+}
+
 impl<NI, EV, E> From<(E, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
 where
     NI: Idx,
@@ -700,6 +854,12 @@ impl<NI: Idx, NV, EV> UndirectedCsrGraph<NI, NV, EV> {
     }
 }
 
+impl<NI: Idx, NV, EV> MemoryUsage for UndirectedCsrGraph<NI, NV, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        self.node_values.memory_usage() + self.csr.memory_usage()
+    }
+}
+
 impl<NI: Idx, NV, EV> Graph<NI> for UndirectedCsrGraph<NI, NV, EV> {
     fn node_count(&self) -> NI {
         self.csr.node_count()
@@ -738,6 +898,27 @@ impl<NI: Idx, NV, EV> UndirectedNeighborsWithValues<NI, EV> for UndirectedCsrGra
     }
 }
 
+impl<NI: Idx, NV, EV> UndirectedNeighborsWithEdgeIds<NI, EV> for UndirectedCsrGraph<NI, NV, EV> {
+    type NeighborsIterator<'a> = EdgeIdIter<'a, NI, NI, EV> where NV: 'a, EV: 'a;
+
+    fn neighbors_with_edge_ids(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr.targets_with_edge_ids(node)
+    }
+}
+
+impl<NI, NV, EV> UndirectedEdgeIdMappingOp<NI> for UndirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    EV: Sync,
+{
+    fn reverse_edge_ids(&self) -> Vec<NI> {
+        let start = Instant::now();
+        let mapping = edge_id_mapping(&self.csr, &self.csr);
+        info!("Computed reverse edge ids in {:?}", start.elapsed());
+        mapping
+    }
+}
+
 impl<NI: Idx, NV, EV> SwapCsr<NI, NI, EV> for UndirectedCsrGraph<NI, NV, EV> {
     fn swap_csr(&mut self, mut csr: Csr<NI, NI, EV>) -> &mut Self {
         std::mem::swap(&mut self.csr, &mut csr);
@@ -872,7 +1053,7 @@ where
 }
 
 fn prefix_sum_atomic<NI: Idx>(degrees: Vec<Atomic<NI>>) -> Vec<Atomic<NI>> {
-    let mut last = degrees.last().unwrap().load(Acquire);
+    let mut last = degrees.last().map_or(NI::zero(), |d| d.load(Acquire));
     let mut sums = degrees
         .into_iter()
         .scan(NI::zero(), |total, degree| {
@@ -882,14 +1063,14 @@ fn prefix_sum_atomic<NI: Idx>(degrees: Vec<Atomic<NI>>) -> Vec<Atomic<NI>> {
         })
         .collect::<Vec<_>>();
 
-    last += sums.last().unwrap().load(Acquire);
+    last += sums.last().map_or(NI::zero(), |s| s.load(Acquire));
     sums.push(Atomic::new(last));
 
     sums
 }
 
 pub(crate) fn prefix_sum<NI: Idx>(degrees: Vec<NI>) -> Vec<NI> {
-    let mut last = *degrees.last().unwrap();
+    let mut last = degrees.last().copied().unwrap_or_else(NI::zero);
     let mut sums = degrees
         .into_iter()
         .scan(NI::zero(), |total, degree| {
@@ -898,7 +1079,7 @@ pub(crate) fn prefix_sum<NI: Idx>(degrees: Vec<NI>) -> Vec<NI> {
             Some(value)
         })
         .collect::<Vec<_>>();
-    last += *sums.last().unwrap();
+    last += sums.last().copied().unwrap_or_else(NI::zero);
     sums.push(last);
     sums
 }
@@ -967,7 +1148,88 @@ where
     (new_offsets, new_targets)
 }
 
-fn to_mut_slices<'targets, NI: Idx, T>(
+// For each edge id `e` in `from`, where `e` connects node `v` to its neighbor
+// `u`, finds the id of the edge in `to` that connects `u` to its neighbor `v`.
+//
+// The edges of `to` are first bucketed by their target node. Since `from` and
+// `to` describe the same set of edges, the bucket of a node `v` has the same
+// size as the neighbor list of `v` in `from`, which allows us to reuse the
+// offsets of `from`. After sorting both, the bucket and the neighbor list, by
+// the neighbor id, matching entries are at the same position. Parallel edges
+// are matched in the order of their edge ids.
+fn edge_id_mapping<NI, EV>(from: &Csr<NI, NI, EV>, to: &Csr<NI, NI, EV>) -> Vec<NI>
+where
+    NI: Idx,
+    EV: Sync,
+{
+    let node_count = from.node_count().index();
+    let edge_count = from.edge_count().index();
+
+    assert_eq!(
+        edge_count,
+        to.edge_count().index(),
+        "both csrs must contain the same edges"
+    );
+
+    let cursors = from.offsets[..node_count]
+        .par_iter()
+        .map(|offset| Atomic::new(*offset))
+        .collect::<Vec<_>>();
+
+    let mut buckets = Vec::<(NI, NI)>::with_capacity(edge_count);
+    let buckets_ptr = SharedMut::new(buckets.as_mut_ptr());
+
+    (0..node_count).into_par_iter().map(NI::new).for_each(|u| {
+        for (edge_id, t) in to.targets_with_edge_ids(u) {
+            let position = NI::get_and_increment(&cursors[t.target.index()], Acquire);
+            debug_assert!(position < from.offsets[t.target.index() + 1]);
+            // SAFETY: Each edge in `to` that points to `t.target` is written to
+            // a unique position within the bucket of `t.target`.
+            unsafe {
+                buckets_ptr.add(position.index()).write((u, edge_id));
+            }
+        }
+    });
+
+    // SAFETY: We inserted every edge of `to`, of which there are edge_count many.
+    unsafe {
+        buckets.set_len(edge_count);
+    }
+
+    let mut mapping = Vec::<NI>::with_capacity(edge_count);
+    let mapping_ptr = SharedMut::new(mapping.as_mut_ptr());
+
+    to_mut_slices(&from.offsets, &mut buckets)
+        .into_par_iter()
+        .enumerate()
+        .for_each_init(Vec::new, |neighbors, (v, bucket)| {
+            bucket.sort_unstable();
+
+            neighbors.clear();
+            neighbors.extend(
+                from.targets_with_edge_ids(NI::new(v))
+                    .map(|(edge_id, t)| (t.target, edge_id)),
+            );
+            neighbors.sort_unstable();
+
+            for (&(_, from_id), &(_, to_id)) in neighbors.iter().zip(bucket.iter()) {
+                // SAFETY: Each edge id in `from` belongs to exactly one node
+                // and is therefore written by exactly one thread.
+                unsafe {
+                    mapping_ptr.add(from_id.index()).write(to_id);
+                }
+            }
+        });
+
+    // SAFETY: We wrote a value for every edge id in `from`.
+    unsafe {
+        mapping.set_len(edge_count);
+    }
+
+    mapping
+}
+
+pub(crate) fn to_mut_slices<'targets, NI: Idx, T>(
     offsets: &[NI],
     targets: &'targets mut [T],
 ) -> Vec<&'targets mut [T]> {
@@ -1211,6 +1473,90 @@ mod tests {
         assert!(matches!(res, _expected));
     }
 
+    #[test]
+    fn neighbors_with_edge_ids_test() {
+        let g: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![(0, 1, 0.1), (0, 2, 0.2), (1, 2, 0.3), (2, 0, 0.4)])
+            .build();
+
+        let out = g
+            .out_neighbors_with_edge_ids(0)
+            .map(|(id, t)| (id, t.target, t.value))
+            .collect::<Vec<_>>();
+        assert_eq!(out, vec![(0, 1, 0.1), (1, 2, 0.2)]);
+
+        let inc = g
+            .in_neighbors_with_edge_ids(2)
+            .map(|(id, t)| (id, t.target, t.value))
+            .collect::<Vec<_>>();
+        assert_eq!(inc, vec![(2, 0, 0.2), (3, 1, 0.3)]);
+
+        let ug: UndirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (0, 2), (1, 2)])
+            .build();
+
+        let ids = ug
+            .neighbors_with_edge_ids(1)
+            .map(|(id, t)| (id, t.target))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![(2, 0), (3, 2)]);
+    }
+
+    #[test]
+    fn directed_edge_id_mapping_test() {
+        let g: DirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Unsorted)
+            .edges(vec![(0, 1), (2, 1), (0, 1), (1, 1), (3, 0), (1, 3), (2, 0)])
+            .build();
+
+        let in_to_out = g.in_to_out_edge_ids();
+        let out_to_in = g.out_to_in_edge_ids();
+
+        assert_eq!(in_to_out.len(), 7);
+        assert_eq!(out_to_in.len(), 7);
+
+        let out_edges = (0..g.node_count())
+            .flat_map(|u| {
+                g.out_neighbors_with_edge_ids(u)
+                    .map(move |(id, t)| (id, (u, t.target)))
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
+        for v in 0..g.node_count() {
+            for (in_id, t) in g.in_neighbors_with_edge_ids(v) {
+                let out_id = in_to_out[in_id as usize];
+                assert_eq!(out_edges[&out_id], (t.target, v));
+                assert_eq!(out_to_in[out_id as usize], in_id);
+            }
+        }
+    }
+
+    #[test]
+    fn undirected_edge_id_mapping_test() {
+        let g: UndirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Unsorted)
+            .edges(vec![(0, 1), (2, 1), (1, 0), (1, 1), (3, 0)])
+            .build();
+
+        let reverse = g.reverse_edge_ids();
+        assert_eq!(reverse.len(), 10);
+
+        let edges = (0..g.node_count())
+            .flat_map(|u| {
+                g.neighbors_with_edge_ids(u)
+                    .map(move |(id, t)| (id, (u, t.target)))
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
+        for (id, &(u, v)) in edges.iter() {
+            let reverse_id = reverse[*id as usize];
+            assert_eq!(edges[&reverse_id], (v, u));
+            assert_eq!(reverse[reverse_id as usize], *id);
+        }
+    }
+
     #[test]
     fn test_to_undirected() {
         // we need a deterministic order of loading, so we're doing stuff in serial
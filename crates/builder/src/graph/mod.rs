@@ -1 +1,4 @@
+pub mod bipartite;
 pub mod csr;
+pub mod sharded;
+pub mod temporal;
@@ -0,0 +1,484 @@
+use byte_slice_cast::{AsByteSlice, AsMutByteSlice, ToByteSlice, ToMutByteSlice};
+use log::info;
+use std::{
+    io::{Read, Write},
+    ops::Range,
+    time::Instant,
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    graph::csr::{Csr, Target},
+    graph_ops::{DeserializeGraphOp, OutDegreePartitionOp, SerializeGraphOp, ToShardedOp},
+    index::Idx,
+    memory::{MemoryFootprint, MemoryUsage},
+    DirectedDegrees, DirectedNeighbors, DirectedNeighborsWithValues, Error, Graph,
+};
+
+/// A single partition of a [`ShardedCsrGraph`].
+///
+/// A shard owns a contiguous range of global node ids and stores all outgoing
+/// and incoming edges of those nodes. Within the shard, nodes are identified
+/// by local ids: the owned node `u` has the local id `u - node_range().start`
+/// and is in `0..node_count()`. Neighbors that are owned by other shards are
+/// called *ghost* nodes and are assigned the local ids
+/// `node_count()..node_count() + ghost_count()`. The global ids of ghost nodes
+/// are stored in the ghost table, which is sorted in ascending order.
+///
+/// The shard implements the directed graph traits using local ids, i.e., the
+/// neighbors of an owned node may be owned nodes or ghost nodes. The shard
+/// does not store the edges of ghost nodes, so ghost ids are not valid
+/// arguments for the degree and neighbor methods, which panic if called with
+/// a local id outside of `0..node_count()`. Use [`GraphShard::is_ghost`] to
+/// check a neighbor before following it.
+pub struct GraphShard<NI: Idx, EV = ()> {
+    node_range: Range<NI>,
+    ghosts: Box<[NI]>,
+    csr_out: Csr<NI, NI, EV>,
+    csr_inc: Csr<NI, NI, EV>,
+}
+
+impl<NI: Idx, EV> GraphShard<NI, EV> {
+    /// Returns the range of global node ids owned by this shard.
+    pub fn node_range(&self) -> Range<NI> {
+        self.node_range.clone()
+    }
+
+    /// Returns the number of ghost nodes, i.e., nodes that are connected to
+    /// an owned node but are owned by another shard.
+    pub fn ghost_count(&self) -> NI {
+        NI::new(self.ghosts.len())
+    }
+
+    /// Returns the global ids of all ghost nodes in ascending order.
+    pub fn ghosts(&self) -> &[NI] {
+        &self.ghosts
+    }
+
+    /// Returns `true`, if the given local id refers to a ghost node.
+    pub fn is_ghost(&self, local: NI) -> bool {
+        local >= self.node_count()
+    }
+
+    /// Translates a local id into the corresponding global id.
+    pub fn to_global(&self, local: NI) -> NI {
+        let node_count = self.node_count();
+
+        if local < node_count {
+            self.node_range.start + local
+        } else {
+            self.ghosts[(local - node_count).index()]
+        }
+    }
+
+    /// Translates a global id into the corresponding local id. Returns `None`
+    /// if the node is neither owned by this shard nor a ghost node.
+    pub fn to_local(&self, global: NI) -> Option<NI> {
+        if self.node_range.contains(&global) {
+            Some(global - self.node_range.start)
+        } else {
+            self.ghosts
+                .binary_search(&global)
+                .ok()
+                .map(|i| self.node_count() + NI::new(i))
+        }
+    }
+}
+
+impl<NI: Idx, EV> MemoryUsage for GraphShard<NI, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        self.csr_out.memory_usage()
+            + self.csr_inc.memory_usage()
+            + MemoryFootprint::of_auxiliary::<NI>(self.ghosts.len())
+    }
+}
+
+impl<NI: Idx, EV> Graph<NI> for GraphShard<NI, EV> {
+    /// Returns the number of nodes owned by this shard.
+    fn node_count(&self) -> NI {
+        self.csr_out.node_count()
+    }
+
+    /// Returns the number of outgoing edges of the nodes owned by this shard.
+    fn edge_count(&self) -> NI {
+        self.csr_out.edge_count()
+    }
+}
+
+impl<NI: Idx, EV> DirectedDegrees<NI> for GraphShard<NI, EV> {
+    fn out_degree(&self, node: NI) -> NI {
+        self.csr_out.degree(node)
+    }
+
+    fn in_degree(&self, node: NI) -> NI {
+        self.csr_inc.degree(node)
+    }
+}
+
+impl<NI: Idx> DirectedNeighbors<NI> for GraphShard<NI, ()> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, NI>;
+
+    fn out_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_out.targets(node).iter()
+    }
+
+    fn in_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_inc.targets(node).iter()
+    }
+}
+
+impl<NI: Idx, EV> DirectedNeighborsWithValues<NI, EV> for GraphShard<NI, EV> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, Target<NI, EV>> where EV: 'a;
+
+    fn out_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_out.targets_with_values(node).iter()
+    }
+
+    fn in_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_inc.targets_with_values(node).iter()
+    }
+}
+
+impl<W, NI, EV> SerializeGraphOp<W> for GraphShard<NI, EV>
+where
+    W: Write,
+    NI: Idx + ToByteSlice,
+    EV: ToByteSlice,
+{
+    fn serialize(&self, mut output: W) -> Result<(), Error> {
+        let GraphShard {
+            node_range,
+            ghosts,
+            csr_out,
+            csr_inc,
+        } = self;
+
+        csr_out.serialize(&mut output)?;
+        csr_inc.serialize(&mut output)?;
+
+        let meta = [node_range.start, node_range.end];
+        output.write_all(meta.as_byte_slice())?;
+        output.write_all([ghosts.len()].as_byte_slice())?;
+        output.write_all(ghosts.as_byte_slice())?;
+
+        Ok(())
+    }
+}
+
+impl<R, NI, EV> DeserializeGraphOp<R, Self> for GraphShard<NI, EV>
+where
+    R: Read,
+    NI: Idx + ToMutByteSlice,
+    EV: ToMutByteSlice,
+{
+    fn deserialize(mut read: R) -> Result<Self, Error> {
+        let csr_out: Csr<NI, NI, EV> = Csr::deserialize(&mut read)?;
+        let csr_inc: Csr<NI, NI, EV> = Csr::deserialize(&mut read)?;
+
+        let mut meta = [NI::zero(); 2];
+        read.read_exact(meta.as_mut_byte_slice())?;
+        let [start, end] = meta;
+
+        let mut ghost_count = [0_usize; 1];
+        read.read_exact(ghost_count.as_mut_byte_slice())?;
+        let [ghost_count] = ghost_count;
+
+        let mut ghosts = vec![NI::zero(); ghost_count].into_boxed_slice();
+        read.read_exact(ghosts.as_mut_byte_slice())?;
+
+        Ok(GraphShard {
+            node_range: start..end,
+            ghosts,
+            csr_out,
+            csr_inc,
+        })
+    }
+}
+
+/// A directed graph that is split into multiple [`GraphShard`]s.
+///
+/// Each shard owns a contiguous range of node ids and can be processed,
+/// serialized and deserialized independently from the other shards.
+pub struct ShardedCsrGraph<NI: Idx, EV = ()> {
+    node_count: NI,
+    edge_count: NI,
+    shards: Vec<GraphShard<NI, EV>>,
+}
+
+impl<NI: Idx, EV> ShardedCsrGraph<NI, EV> {
+    /// Creates a sharded graph from the given shards.
+    ///
+    /// The node ranges of the shards need to be consecutive and start at
+    /// node `0`, otherwise [`Error::InvalidPartitioning`] is returned.
+    pub fn new(shards: Vec<GraphShard<NI, EV>>) -> Result<Self, Error> {
+        let mut node_count = NI::zero();
+        let mut edge_count = NI::zero();
+
+        for shard in shards.iter() {
+            if shard.node_range.start != node_count {
+                return Err(Error::InvalidPartitioning);
+            }
+            node_count = shard.node_range.end;
+            edge_count += shard.edge_count();
+        }
+
+        info!(
+            "Created sharded graph (node_count = {:?}, edge_count = {:?}, shard_count = {:?})",
+            node_count,
+            edge_count,
+            shards.len()
+        );
+
+        Ok(Self {
+            node_count,
+            edge_count,
+            shards,
+        })
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the shard at the given position.
+    pub fn shard(&self, index: usize) -> &GraphShard<NI, EV> {
+        &self.shards[index]
+    }
+
+    /// Returns an iterator over all shards in node id order.
+    pub fn shards(&self) -> std::slice::Iter<'_, GraphShard<NI, EV>> {
+        self.shards.iter()
+    }
+
+    /// Returns the position of the shard that owns the given global node id.
+    pub fn shard_of(&self, node: NI) -> usize {
+        self.shards
+            .partition_point(|shard| shard.node_range.end <= node)
+    }
+
+    /// Consumes the graph and returns its shards.
+    pub fn into_shards(self) -> Vec<GraphShard<NI, EV>> {
+        self.shards
+    }
+}
+
+impl<NI: Idx, EV> MemoryUsage for ShardedCsrGraph<NI, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        self.shards.iter().map(MemoryUsage::memory_usage).sum()
+    }
+}
+
+impl<NI: Idx, EV> Graph<NI> for ShardedCsrGraph<NI, EV> {
+    fn node_count(&self) -> NI {
+        self.node_count
+    }
+
+    fn edge_count(&self) -> NI {
+        self.edge_count
+    }
+}
+
+impl<NI, EV, G> ToShardedOp<NI, EV> for G
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+    G: Graph<NI>
+        + DirectedDegrees<NI>
+        + DirectedNeighborsWithValues<NI, EV>
+        + OutDegreePartitionOp<NI, EV>
+        + Sync,
+{
+    type Sharded = ShardedCsrGraph<NI, EV>;
+
+    fn to_sharded(&self, shard_count: usize) -> Self::Sharded {
+        let start = Instant::now();
+        let partition = self.out_degree_partition(shard_count);
+        info!("Computed out degree partition in {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let shards = partition
+            .into_par_iter()
+            .map(|node_range| shard(self, node_range))
+            .collect::<Vec<_>>();
+        info!("Created {} shards in {:?}", shards.len(), start.elapsed());
+
+        ShardedCsrGraph::new(shards).expect("out degree partition is consecutive")
+    }
+}
+
+fn shard<NI, EV, G>(graph: &G, node_range: Range<NI>) -> GraphShard<NI, EV>
+where
+    NI: Idx,
+    EV: Copy,
+    G: DirectedNeighborsWithValues<NI, EV>,
+{
+    let mut ghosts = Vec::new();
+
+    for node in node_range.clone() {
+        let neighbors = graph
+            .out_neighbors_with_values(node)
+            .chain(graph.in_neighbors_with_values(node));
+
+        ghosts.extend(
+            neighbors
+                .map(|t| t.target)
+                .filter(|t| !node_range.contains(t)),
+        );
+    }
+
+    ghosts.sort_unstable();
+    ghosts.dedup();
+
+    let node_count = node_range.end - node_range.start;
+
+    let to_local = &|global: NI| {
+        if node_range.contains(&global) {
+            global - node_range.start
+        } else {
+            let ghost = ghosts
+                .binary_search(&global)
+                .expect("neighbor is either owned or a ghost");
+            node_count + NI::new(ghost)
+        }
+    };
+
+    let csr_out = local_csr(node_range.clone(), to_local, |n| {
+        graph.out_neighbors_with_values(n)
+    });
+    let csr_inc = local_csr(node_range.clone(), to_local, |n| {
+        graph.in_neighbors_with_values(n)
+    });
+
+    GraphShard {
+        node_range,
+        ghosts: ghosts.into_boxed_slice(),
+        csr_out,
+        csr_inc,
+    }
+}
+
+// Creates a CSR for the given node range, where node and target ids are
+// translated into shard-local ids.
+fn local_csr<'g, NI, EV, I>(
+    node_range: Range<NI>,
+    to_local: &dyn Fn(NI) -> NI,
+    neighbors: impl Fn(NI) -> I,
+) -> Csr<NI, NI, EV>
+where
+    NI: Idx,
+    EV: Copy + 'g,
+    I: Iterator<Item = &'g Target<NI, EV>>,
+{
+    let mut offsets = Vec::with_capacity((node_range.end - node_range.start).index() + 1);
+    let mut targets = Vec::new();
+
+    offsets.push(NI::zero());
+
+    for node in node_range {
+        targets.extend(neighbors(node).map(|t| Target::new(to_local(t.target), t.value)));
+        offsets.push(NI::new(targets.len()));
+    }
+
+    Csr::new(offsets.into_boxed_slice(), targets.into_boxed_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{builder::GraphBuilder, graph::csr::DirectedCsrGraph};
+
+    use super::*;
+
+    fn graph() -> DirectedCsrGraph<usize, (), f32> {
+        GraphBuilder::new()
+            .edges_with_values(vec![
+                (0, 1, 0.1),
+                (0, 2, 0.2),
+                (1, 2, 0.3),
+                (2, 3, 0.4),
+                (3, 0, 0.5),
+                (3, 4, 0.6),
+                (4, 5, 0.7),
+                (5, 3, 0.8),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn to_sharded_test() {
+        let g = graph();
+        let sharded = g.to_sharded(3);
+
+        assert_eq!(sharded.node_count(), g.node_count());
+        assert_eq!(sharded.edge_count(), g.edge_count());
+        assert!(sharded.shard_count() <= 3);
+
+        for (i, shard) in sharded.shards().enumerate() {
+            for local in 0..shard.node_count() {
+                let global = shard.to_global(local);
+
+                assert!(!shard.is_ghost(local));
+                assert_eq!(sharded.shard_of(global), i);
+                assert_eq!(shard.to_local(global), Some(local));
+
+                let out = shard
+                    .out_neighbors_with_values(local)
+                    .map(|t| Target::new(shard.to_global(t.target), t.value))
+                    .collect::<Vec<_>>();
+                assert_eq!(out, g.out_neighbors_with_values(global).as_slice());
+
+                let inc = shard
+                    .in_neighbors_with_values(local)
+                    .map(|t| Target::new(shard.to_global(t.target), t.value))
+                    .collect::<Vec<_>>();
+                assert_eq!(inc, g.in_neighbors_with_values(global).as_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn ghosts_test() {
+        let g = graph();
+        let sharded = g.to_sharded(2);
+        let shard = sharded.shard(0);
+
+        for &ghost in shard.ghosts() {
+            assert!(!shard.node_range().contains(&ghost));
+
+            let local = shard.to_local(ghost).unwrap();
+            assert!(shard.is_ghost(local));
+            assert_eq!(shard.to_global(local), ghost);
+        }
+
+        assert!(shard.ghosts().windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn serialize_shard_test() {
+        let g = graph();
+        let sharded = g.to_sharded(2);
+
+        for shard in sharded.shards() {
+            let mut buf = Vec::new();
+            shard.serialize(&mut buf).unwrap();
+
+            let actual = GraphShard::<usize, f32>::deserialize(buf.as_slice()).unwrap();
+
+            assert_eq!(actual.node_range(), shard.node_range());
+            assert_eq!(actual.ghosts(), shard.ghosts());
+            assert_eq!(actual.edge_count(), shard.edge_count());
+
+            for n in 0..shard.node_count() {
+                assert_eq!(
+                    actual.out_neighbors_with_values(n).as_slice(),
+                    shard.out_neighbors_with_values(n).as_slice()
+                );
+                assert_eq!(
+                    actual.in_neighbors_with_values(n).as_slice(),
+                    shard.in_neighbors_with_values(n).as_slice()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use log::info;
+use std::{ops::Range, time::Instant};
+
+use rayon::prelude::*;
+
+use crate::{
+    graph::csr::{to_mut_slices, Csr, CsrLayout},
+    index::Idx,
+    input::{edgelist::Edges, Direction},
+    memory::{MemoryFootprint, MemoryUsage},
+    DirectedDegrees, DirectedNeighbors, Graph, TemporalNeighbors,
+};
+
+/// A CSR variant which stores a timestamp for each target and keeps each
+/// neighbor list sorted by timestamp.
+pub struct TemporalCsr<NI: Idx, T> {
+    offsets: Box<[NI]>,
+    targets: Box<[NI]>,
+    timestamps: Box<[T]>,
+}
+
+impl<NI: Idx, T: Copy + Ord> TemporalCsr<NI, T> {
+    #[inline]
+    fn node_count(&self) -> NI {
+        NI::new(self.offsets.len() - 1)
+    }
+
+    #[inline]
+    fn edge_count(&self) -> NI {
+        NI::new(self.targets.len())
+    }
+
+    #[inline]
+    fn degree(&self, i: NI) -> NI {
+        let from = self.offsets[i.index()];
+        let to = self.offsets[(i + NI::new(1)).index()];
+
+        to - from
+    }
+
+    #[inline]
+    fn targets(&self, i: NI) -> &[NI] {
+        let from = self.offsets[i.index()];
+        let to = self.offsets[(i + NI::new(1)).index()];
+
+        &self.targets[from.index()..to.index()]
+    }
+
+    // Returns the positions of all targets of node `i` within `[start, end)`.
+    #[inline]
+    fn window(&self, i: NI, start: T, end: T) -> Range<usize> {
+        let from = self.offsets[i.index()].index();
+        let to = self.offsets[(i + NI::new(1)).index()].index();
+
+        let timestamps = &self.timestamps[from..to];
+        let lo = timestamps.partition_point(|&t| t < start);
+        let hi = lo + timestamps[lo..].partition_point(|&t| t < end);
+
+        from + lo..from + hi
+    }
+
+    #[inline]
+    fn targets_between(&self, i: NI, start: T, end: T) -> &[NI] {
+        &self.targets[self.window(i, start, end)]
+    }
+}
+
+impl<NI: Idx, T> MemoryUsage for TemporalCsr<NI, T> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        MemoryFootprint::of_offsets::<NI>(self.offsets.len())
+            + MemoryFootprint::of_targets::<NI, ()>(self.targets.len())
+            + MemoryFootprint::of_auxiliary::<T>(self.timestamps.len())
+    }
+}
+
+impl<NI, T> From<Csr<NI, NI, T>> for TemporalCsr<NI, T>
+where
+    NI: Idx,
+    T: Copy + Ord + Send + Sync,
+{
+    fn from(csr: Csr<NI, NI, T>) -> Self {
+        let (offsets, mut targets) = csr.into_parts();
+
+        let start = Instant::now();
+        to_mut_slices(&offsets, &mut targets)
+            .par_iter_mut()
+            .for_each(|list| list.sort_unstable_by_key(|t| (t.value, t.target)));
+        info!("Sorted targets by timestamp in {:?}", start.elapsed());
+
+        let start = Instant::now();
+        let (targets, timestamps): (Vec<_>, Vec<_>) =
+            targets.par_iter().map(|t| (t.target, t.value)).unzip();
+        info!("Split targets and timestamps in {:?}", start.elapsed());
+
+        TemporalCsr {
+            offsets,
+            targets: targets.into_boxed_slice(),
+            timestamps: timestamps.into_boxed_slice(),
+        }
+    }
+}
+
+/// A directed graph where each edge carries a timestamp of type `T`.
+///
+/// Outgoing and incoming neighbor lists are sorted by timestamp which allows
+/// answering time window queries via [`TemporalNeighbors`] using binary
+/// search. A [`TemporalSnapshot`] restricts the whole graph to a time window
+/// without copying any data.
+///
+/// # Example
+///
+/// ```
+/// use graph_builder::prelude::*;
+///
+/// let graph: TemporalCsrGraph<usize, u64> = GraphBuilder::new()
+///     .edges_with_values(vec![(0, 1, 42), (0, 2, 7), (0, 3, 13), (1, 2, 9)])
+///     .build();
+///
+/// assert_eq!(graph.out_neighbors(0).as_slice(), &[2, 3, 1]);
+/// assert_eq!(graph.out_neighbors_between(0, 7, 42).as_slice(), &[2, 3]);
+///
+/// let snapshot = graph.snapshot(8, 20);
+///
+/// assert_eq!(snapshot.edge_count(), 2);
+/// assert_eq!(snapshot.out_neighbors(0).as_slice(), &[3]);
+/// assert_eq!(snapshot.in_neighbors(2).as_slice(), &[1]);
+/// ```
+pub struct TemporalCsrGraph<NI: Idx, T> {
+    csr_out: TemporalCsr<NI, T>,
+    csr_inc: TemporalCsr<NI, T>,
+}
+
+impl<NI: Idx, T: Copy + Ord> TemporalCsrGraph<NI, T> {
+    pub fn new(csr_out: TemporalCsr<NI, T>, csr_inc: TemporalCsr<NI, T>) -> Self {
+        let g = Self { csr_out, csr_inc };
+        info!(
+            "Created temporal graph (node_count = {:?}, edge_count = {:?})",
+            g.node_count(),
+            g.edge_count()
+        );
+
+        g
+    }
+
+    /// Returns a view of the graph that only contains edges with a timestamp
+    /// in `[start, end)`.
+    ///
+    /// The view implements the same traits as a directed graph and can be
+    /// passed to any algorithm that operates on those. Creating the view
+    /// requires one pass over all nodes to compute the number of edges in the
+    /// window.
+    pub fn snapshot(&self, start: T, end: T) -> TemporalSnapshot<'_, NI, T>
+    where
+        T: Sync,
+    {
+        let edge_count = (0..self.node_count().index())
+            .into_par_iter()
+            .map(|n| self.csr_out.window(NI::new(n), start, end).len())
+            .sum::<usize>();
+
+        TemporalSnapshot {
+            g: self,
+            start,
+            end,
+            edge_count: NI::new(edge_count),
+        }
+    }
+}
+
+impl<NI: Idx, T> MemoryUsage for TemporalCsrGraph<NI, T> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        self.csr_out.memory_usage() + self.csr_inc.memory_usage()
+    }
+}
+
+impl<NI: Idx, T: Copy + Ord> Graph<NI> for TemporalCsrGraph<NI, T> {
+    delegate::delegate! {
+        to self.csr_out {
+            fn node_count(&self) -> NI;
+            fn edge_count(&self) -> NI;
+        }
+    }
+}
+
+impl<NI: Idx, T: Copy + Ord> DirectedDegrees<NI> for TemporalCsrGraph<NI, T> {
+    fn out_degree(&self, node: NI) -> NI {
+        self.csr_out.degree(node)
+    }
+
+    fn in_degree(&self, node: NI) -> NI {
+        self.csr_inc.degree(node)
+    }
+}
+
+impl<NI: Idx, T: Copy + Ord> DirectedNeighbors<NI> for TemporalCsrGraph<NI, T> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, NI> where T: 'a;
+
+    fn out_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_out.targets(node).iter()
+    }
+
+    fn in_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.csr_inc.targets(node).iter()
+    }
+}
+
+impl<NI: Idx, T: Copy + Ord> TemporalNeighbors<NI, T> for TemporalCsrGraph<NI, T> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, NI> where T: 'a;
+
+    fn out_neighbors_between(&self, node: NI, start: T, end: T) -> Self::NeighborsIterator<'_> {
+        self.csr_out.targets_between(node, start, end).iter()
+    }
+
+    fn in_neighbors_between(&self, node: NI, start: T, end: T) -> Self::NeighborsIterator<'_> {
+        self.csr_inc.targets_between(node, start, end).iter()
+    }
+}
+
+/// A time window view on a [`TemporalCsrGraph`].
+///
+/// The view only contains edges with a timestamp in `[start, end)`, the node
+/// set is the same as in the underlying graph.
+pub struct TemporalSnapshot<'g, NI: Idx, T> {
+    g: &'g TemporalCsrGraph<NI, T>,
+    start: T,
+    end: T,
+    edge_count: NI,
+}
+
+impl<'g, NI: Idx, T: Copy + Ord> Graph<NI> for TemporalSnapshot<'g, NI, T> {
+    fn node_count(&self) -> NI {
+        self.g.node_count()
+    }
+
+    fn edge_count(&self) -> NI {
+        self.edge_count
+    }
+}
+
+impl<'g, NI: Idx, T: Copy + Ord> DirectedDegrees<NI> for TemporalSnapshot<'g, NI, T> {
+    fn out_degree(&self, node: NI) -> NI {
+        NI::new(self.g.csr_out.window(node, self.start, self.end).len())
+    }
+
+    fn in_degree(&self, node: NI) -> NI {
+        NI::new(self.g.csr_inc.window(node, self.start, self.end).len())
+    }
+}
+
+impl<'g, NI: Idx, T: Copy + Ord> DirectedNeighbors<NI> for TemporalSnapshot<'g, NI, T> {
+    type NeighborsIterator<'a> = std::slice::Iter<'a, NI> where Self: 'a;
+
+    fn out_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.g.out_neighbors_between(node, self.start, self.end)
+    }
+
+    fn in_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_> {
+        self.g.in_neighbors_between(node, self.start, self.end)
+    }
+}
+
+impl<NI, T, E> From<(E, CsrLayout)> for TemporalCsrGraph<NI, T>
+where
+    NI: Idx,
+    T: Copy + Ord + Send + Sync,
+    E: Edges<NI = NI, EV = T>,
+{
+    /// Creates a temporal graph from an edge list where the edge value is
+    /// interpreted as timestamp. The given [`CsrLayout`] is ignored since
+    /// neighbor lists are always sorted by timestamp.
+    fn from((edge_list, _): (E, CsrLayout)) -> Self {
+        info!("Creating temporal graph");
+        let node_count = edge_list.max_node_id() + NI::new(1);
+
+        let start = Instant::now();
+        let csr_out = Csr::from((
+            &edge_list,
+            node_count,
+            Direction::Outgoing,
+            CsrLayout::Unsorted,
+        ));
+        let csr_out = TemporalCsr::from(csr_out);
+        info!("Created outgoing temporal csr in {:?}.", start.elapsed());
+
+        let start = Instant::now();
+        let csr_inc = Csr::from((
+            &edge_list,
+            node_count,
+            Direction::Incoming,
+            CsrLayout::Unsorted,
+        ));
+        let csr_inc = TemporalCsr::from(csr_inc);
+        info!("Created incoming temporal csr in {:?}.", start.elapsed());
+
+        TemporalCsrGraph::new(csr_out, csr_inc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::GraphBuilder;
+
+    use super::*;
+
+    fn graph() -> TemporalCsrGraph<usize, u32> {
+        GraphBuilder::new()
+            .edges_with_values(vec![
+                (0, 1, 5),
+                (0, 2, 1),
+                (0, 1, 3),
+                (1, 2, 5),
+                (2, 0, 8),
+                (3, 0, 2),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn sorted_by_timestamp_test() {
+        let g = graph();
+
+        assert_eq!(g.node_count(), 4);
+        assert_eq!(g.edge_count(), 6);
+
+        assert_eq!(g.out_neighbors(0).as_slice(), &[2, 1, 1]);
+        assert_eq!(g.in_neighbors(0).as_slice(), &[3, 2]);
+        assert_eq!(g.in_neighbors(2).as_slice(), &[0, 1]);
+    }
+
+    #[test]
+    fn neighbors_between_test() {
+        let g = graph();
+
+        assert_eq!(g.out_neighbors_between(0, 0, 10).as_slice(), &[2, 1, 1]);
+        assert_eq!(g.out_neighbors_between(0, 1, 5).as_slice(), &[2, 1]);
+        assert_eq!(g.out_neighbors_between(0, 2, 6).as_slice(), &[1, 1]);
+        assert_eq!(
+            g.out_neighbors_between(0, 6, 10).as_slice(),
+            &[] as &[usize]
+        );
+        assert_eq!(g.out_neighbors_between(0, 5, 5).as_slice(), &[] as &[usize]);
+        assert_eq!(g.in_neighbors_between(2, 5, 6).as_slice(), &[1]);
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let g = graph();
+        let s = g.snapshot(2, 6);
+
+        assert_eq!(s.node_count(), 4);
+        assert_eq!(s.edge_count(), 4);
+
+        assert_eq!(s.out_degree(0), 2);
+        assert_eq!(s.out_degree(2), 0);
+        assert_eq!(s.in_degree(0), 1);
+        assert_eq!(s.out_neighbors(0).as_slice(), &[1, 1]);
+        assert_eq!(s.in_neighbors(0).as_slice(), &[3]);
+        assert_eq!(s.in_neighbors(2).as_slice(), &[1]);
+    }
+}
@@ -228,6 +228,148 @@ pub trait ToUndirectedOp {
     fn to_undirected(&self, layout: impl Into<Option<CsrLayout>>) -> Self::Undirected;
 }
 
+pub trait ToDirectedOp {
+    type Directed;
+
+    /// Creates a new directed graph from the edges of an existing graph.
+    ///
+    /// Note, that this method creates a new graph with the same space
+    /// requirements as the input graph.
+    ///
+    /// # Example
+    ///
+    /// For a [`crate::BipartiteCsrGraph`], left nodes keep their ids and right
+    /// nodes are placed behind them. Edges point from left to right nodes.
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let graph: BipartiteCsrGraph<u32> = GraphBuilder::new()
+    ///     .edges(vec![(0, 1), (1, 0), (1, 1)])
+    ///     .build();
+    ///
+    /// assert_eq!(graph.left_node_count(), 2);
+    /// assert_eq!(graph.right_node_count(), 2);
+    ///
+    /// let graph = graph.to_directed(CsrLayout::Sorted);
+    ///
+    /// assert_eq!(graph.node_count(), 4);
+    /// assert_eq!(graph.out_neighbors(1).as_slice(), &[2, 3]);
+    /// assert_eq!(graph.in_neighbors(3).as_slice(), &[0, 1]);
+    /// ```
+    ///
+    /// This method accepts an optional [`CsrLayout`] as second parameter,
+    /// which has the same effect as described in [`GraphBuilder::csr_layout`]
+    fn to_directed(&self, layout: impl Into<Option<CsrLayout>>) -> Self::Directed;
+}
+
+pub trait DirectedEdgeIdMappingOp<NI: Idx> {
+    /// Maps each edge id of the incoming CSR to the id of the same edge in the
+    /// outgoing CSR.
+    ///
+    /// The returned vector has `edge_count` entries and can be used to access
+    /// an edge-aligned array, e.g. edge properties computed by an algorithm,
+    /// that has been created using the outgoing edge ids.
+    ///
+    /// Parallel edges, i.e., multiple edges between the same pair of nodes, are
+    /// mapped to distinct edge ids in an arbitrary but consistent way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+    ///     .csr_layout(CsrLayout::Sorted)
+    ///     .edges(vec![(0, 2), (1, 0), (1, 2)])
+    ///     .build();
+    ///
+    /// let in_to_out = graph.in_to_out_edge_ids();
+    ///
+    /// // (1, 0) is the first incoming edge and the second outgoing edge
+    /// let (in_id, _) = graph.in_neighbors_with_edge_ids(0).next().unwrap();
+    /// assert_eq!(in_id, 0);
+    /// assert_eq!(in_to_out[in_id as usize], 1);
+    ///
+    /// let (out_id, _) = graph.out_neighbors_with_edge_ids(1).next().unwrap();
+    /// assert_eq!(out_id, 1);
+    /// ```
+    fn in_to_out_edge_ids(&self) -> Vec<NI>;
+
+    /// Maps each edge id of the outgoing CSR to the id of the same edge in the
+    /// incoming CSR.
+    ///
+    /// This is the inverse mapping of [`DirectedEdgeIdMappingOp::in_to_out_edge_ids`].
+    fn out_to_in_edge_ids(&self) -> Vec<NI>;
+}
+
+pub trait UndirectedEdgeIdMappingOp<NI: Idx> {
+    /// Maps each edge id to the id of the opposite edge.
+    ///
+    /// An undirected edge `(u, v)` is stored twice, once in the neighbor list
+    /// of `u` and once in the neighbor list of `v`. For an edge id that
+    /// represents `(u, v)`, the returned vector contains the edge id which
+    /// represents `(v, u)`. Applying the mapping twice results in the original
+    /// edge id.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+    ///     .csr_layout(CsrLayout::Sorted)
+    ///     .edges(vec![(0, 1), (0, 2), (1, 2)])
+    ///     .build();
+    ///
+    /// let reverse = graph.reverse_edge_ids();
+    ///
+    /// // (0, 2) is stored at position 1, (2, 0) at position 4
+    /// assert_eq!(reverse[1], 4);
+    /// assert_eq!(reverse[4], 1);
+    /// ```
+    fn reverse_edge_ids(&self) -> Vec<NI>;
+}
+
+pub trait ToShardedOp<NI: Idx, EV> {
+    type Sharded;
+
+    /// Splits the graph into at most `shard_count` shards.
+    ///
+    /// The node set is partitioned into consecutive ranges with roughly equal
+    /// total out degree, see [`OutDegreePartitionOp`]. Each shard stores the
+    /// outgoing and incoming edges of its nodes as well as a table of ghost
+    /// nodes, i.e., neighbors that are owned by a different shard.
+    ///
+    /// Note, that this method creates a new graph with the same space
+    /// requirements as the input graph plus the ghost tables.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::prelude::*;
+    ///
+    /// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+    ///     .csr_layout(CsrLayout::Sorted)
+    ///     .edges(vec![(0, 1), (0, 2), (2, 1), (2, 3)])
+    ///     .build();
+    ///
+    /// let sharded = graph.to_sharded(2);
+    ///
+    /// assert_eq!(sharded.shard_count(), 2);
+    ///
+    /// let shard = sharded.shard(1);
+    /// assert_eq!(shard.node_range(), 1..4);
+    /// assert_eq!(shard.ghosts(), &[0]);
+    ///
+    /// // node 2 has the local id 1, ghost node 0 has the local id 3
+    /// assert_eq!(shard.out_neighbors(1).as_slice(), &[0, 2]);
+    /// assert_eq!(shard.in_neighbors(1).as_slice(), &[3]);
+    /// assert_eq!(shard.to_global(3), 0);
+    /// ```
+    fn to_sharded(&self, shard_count: usize) -> Self::Sharded;
+}
+
 pub trait SerializeGraphOp<W> {
     fn serialize(&self, write: W) -> Result<(), Error>;
 }
@@ -0,0 +1,681 @@
+//! Reads and writes graphs in the adjacency graph formats used by
+//! [Ligra](https://github.com/jshun/ligra#input-format-for-ligra-applications-and-the-ligra-encoder)
+//! and [GBBS](https://github.com/ParAlg/gbbs#input-formats).
+use std::{
+    convert::TryFrom,
+    io::{BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+    time::Instant,
+};
+
+use log::info;
+use rayon::prelude::*;
+
+use crate::{
+    graph::csr::{Csr, CsrLayout, DirectedCsrGraph, NodeValues, Target, UndirectedCsrGraph},
+    index::Idx,
+    input::{
+        compression::{self, Compression},
+        InputCapabilities, InputPath, ParseValue, TextValue,
+    },
+    DirectedNeighborsWithValues, Error, Graph, UndirectedNeighborsWithValues,
+};
+
+const HEADER: &[u8] = b"AdjacencyGraph";
+const WEIGHTED_HEADER: &[u8] = b"WeightedAdjacencyGraph";
+
+const BINARY_HEADER_SIZE: usize = 3 * std::mem::size_of::<u64>();
+const BINARY_OFFSET_SIZE: usize = std::mem::size_of::<u64>();
+const BINARY_TARGET_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Reads a graph that is stored in one of the adjacency graph formats used by
+/// Ligra and GBBS. The input is loaded directly into the CSR representation,
+/// without creating an intermediate edge list.
+///
+/// The text formats start with either `AdjacencyGraph` or
+/// `WeightedAdjacencyGraph`, followed by the node count `n`, the edge count
+/// `m`, the `n` offsets into the target array, the `m` targets and, for the
+/// weighted format, the `m` edge weights. All entries are separated by
+/// whitespace, typically a new line. If the input is not weighted, edge values
+/// are set to `Default::default()`.
+///
+/// Inputs that do not start with one of the text headers are read as the
+/// binary CSR format of GBBS: the node count, the edge count and the file size
+/// in bytes as little-endian `u64`, followed by `n + 1` offsets as `u64` and
+/// `m` targets as `u32`. The binary format does not store edge weights.
+///
+/// Compressed files are detected by their extension. Files without such an
+/// extension are only decompressed if the decompressed content starts with
+/// one of the text headers, since a binary file may start with any bytes.
+///
+/// Ligra stores undirected graphs symmetrically, i.e., each edge is contained
+/// in the neighbor lists of both of its nodes. Inputs for an undirected graph
+/// are expected to be stored that way.
+///
+/// # Example
+///
+/// ```ignore
+/// > cat my_graph.adj
+/// AdjacencyGraph
+/// 5
+/// 6
+/// 0
+/// 2
+/// 4
+/// 5
+/// 6
+/// 1
+/// 2
+/// 2
+/// 3
+/// 4
+/// 4
+/// ```
+pub struct AdjacencyGraphInput<NI: Idx, EV = ()> {
+    _idx: PhantomData<(NI, EV)>,
+}
+
+impl<NI: Idx, EV> Default for AdjacencyGraphInput<NI, EV> {
+    fn default() -> Self {
+        Self { _idx: PhantomData }
+    }
+}
+
+impl<NI: Idx, EV> InputCapabilities<NI> for AdjacencyGraphInput<NI, EV> {
+    type GraphInput = AdjacencyGraph<NI, EV>;
+}
+
+/// A graph in the adjacency graph format, stored as CSR.
+pub struct AdjacencyGraph<NI: Idx, EV> {
+    csr: Csr<NI, NI, EV>,
+}
+
+impl<NI, EV, P> TryFrom<InputPath<P>> for AdjacencyGraph<NI, EV>
+where
+    P: AsRef<Path>,
+    NI: Idx,
+    EV: ParseValue + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
+        let path = path.0.as_ref();
+        let bytes = compression::read_binary_bytes(path)?;
+
+        if !is_text(&bytes) && Compression::from_magic(&bytes) != Compression::None {
+            if let Some(text) = compression::read_bytes(path).ok().filter(|t| is_text(t)) {
+                return AdjacencyGraph::try_from(text.as_ref());
+            }
+        }
+
+        AdjacencyGraph::try_from(bytes.as_ref())
+    }
+}
+
+impl<NI, EV> TryFrom<&[u8]> for AdjacencyGraph<NI, EV>
+where
+    NI: Idx,
+    EV: ParseValue + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let start = Instant::now();
+
+        let csr: Csr<NI, NI, EV> = if is_text(bytes) {
+            parse_text(bytes)?
+        } else {
+            parse_binary(bytes)?
+        };
+
+        info!(
+            "Read adjacency graph with {} nodes and {} edges in {:?}",
+            csr.node_count().index(),
+            csr.edge_count().index(),
+            start.elapsed()
+        );
+
+        Ok(AdjacencyGraph { csr })
+    }
+}
+
+/// Returns whether the input starts with one of the text headers.
+fn is_text(bytes: &[u8]) -> bool {
+    let text = match bytes.iter().position(|b| !b.is_ascii_whitespace()) {
+        Some(start) => &bytes[start..],
+        None => bytes,
+    };
+    text.starts_with(HEADER) || text.starts_with(WEIGHTED_HEADER)
+}
+
+fn parse_text<NI, EV>(bytes: &[u8]) -> Result<Csr<NI, NI, EV>, Error>
+where
+    NI: Idx,
+    EV: ParseValue,
+{
+    let mut tokens = bytes
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|token| !token.is_empty());
+
+    let weighted = match tokens.next() {
+        Some(WEIGHTED_HEADER) => true,
+        Some(HEADER) => false,
+        _ => return Err(invalid("unknown header")),
+    };
+
+    let node_count = next::<usize>(&mut tokens, "node count")?;
+    let edge_count = next::<usize>(&mut tokens, "edge count")?;
+
+    let mut offsets = Vec::with_capacity(node_count + 1);
+    let mut last = 0;
+    for _ in 0..node_count {
+        let offset = next::<usize>(&mut tokens, "offset")?;
+        if offset < last || offset > edge_count || (offsets.is_empty() && offset != 0) {
+            return Err(invalid(format!("invalid offset {}", offset)));
+        }
+        offsets.push(NI::new(offset));
+        last = offset;
+    }
+    offsets.push(NI::new(edge_count));
+
+    let mut targets = Vec::with_capacity(edge_count);
+    for _ in 0..edge_count {
+        let target = next::<usize>(&mut tokens, "target")?;
+        if target >= node_count {
+            return Err(invalid(format!("invalid target {}", target)));
+        }
+        targets.push(Target::new(NI::new(target), EV::default()));
+    }
+
+    if weighted {
+        for target in targets.iter_mut() {
+            target.value = next::<EV>(&mut tokens, "weight")?;
+        }
+    }
+
+    if tokens.next().is_some() {
+        return Err(invalid("unexpected trailing content"));
+    }
+
+    Ok(Csr::new(
+        offsets.into_boxed_slice(),
+        targets.into_boxed_slice(),
+    ))
+}
+
+fn next<'a, T: ParseValue>(
+    tokens: &mut impl Iterator<Item = &'a [u8]>,
+    name: &str,
+) -> Result<T, Error> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| invalid(format!("missing {}", name)))?;
+
+    match T::parse(token) {
+        (value, len) if len == token.len() => Ok(value),
+        _ => Err(invalid(format!(
+            "invalid {} `{}`",
+            name,
+            String::from_utf8_lossy(token)
+        ))),
+    }
+}
+
+fn parse_binary<NI, EV>(bytes: &[u8]) -> Result<Csr<NI, NI, EV>, Error>
+where
+    NI: Idx,
+    EV: Default + Send,
+{
+    if bytes.len() < BINARY_HEADER_SIZE {
+        return Err(invalid("binary input is too short"));
+    }
+
+    let node_count = read_u64(&bytes[0..]) as usize;
+    let edge_count = read_u64(&bytes[8..]) as usize;
+
+    let offsets_end = node_count
+        .checked_add(1)
+        .and_then(|len| len.checked_mul(BINARY_OFFSET_SIZE))
+        .and_then(|size| size.checked_add(BINARY_HEADER_SIZE));
+    let targets_end = edge_count
+        .checked_mul(BINARY_TARGET_SIZE)
+        .zip(offsets_end)
+        .and_then(|(size, offsets_end)| size.checked_add(offsets_end));
+
+    let (offsets_end, targets_end) = match (offsets_end, targets_end) {
+        (Some(offsets_end), Some(targets_end)) if targets_end <= bytes.len() => {
+            (offsets_end, targets_end)
+        }
+        _ => {
+            return Err(invalid(format!(
+                "binary input with {} nodes and {} edges is too short",
+                node_count, edge_count
+            )))
+        }
+    };
+
+    let offsets = bytes[BINARY_HEADER_SIZE..offsets_end]
+        .par_chunks_exact(BINARY_OFFSET_SIZE)
+        .map(|offset| read_u64(offset) as usize)
+        .collect::<Vec<_>>();
+
+    if offsets[0] != 0
+        || offsets[node_count] != edge_count
+        || offsets.windows(2).any(|w| w[0] > w[1])
+    {
+        return Err(invalid("invalid offsets"));
+    }
+
+    let offsets = offsets.into_par_iter().map(NI::new).collect::<Vec<_>>();
+
+    let targets = bytes[offsets_end..targets_end]
+        .par_chunks_exact(BINARY_TARGET_SIZE)
+        .map(|target| {
+            let target = u32::from_le_bytes([target[0], target[1], target[2], target[3]]) as usize;
+            if target >= node_count {
+                return Err(invalid(format!("invalid target {}", target)));
+            }
+            Ok(Target::new(NI::new(target), EV::default()))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(Csr::new(
+        offsets.into_boxed_slice(),
+        targets.into_boxed_slice(),
+    ))
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0_u8; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(buf)
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidInput {
+        format: String::from("adjacency graph"),
+        reason: reason.into(),
+    }
+}
+
+impl<NI, EV> From<(AdjacencyGraph<NI, EV>, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    fn from((graph, csr_layout): (AdjacencyGraph<NI, EV>, CsrLayout)) -> Self {
+        let csr_out = graph.csr.with_layout(csr_layout);
+
+        let start = Instant::now();
+        let csr_inc = csr_out.transpose(csr_layout);
+        info!("Created incoming csr in {:?}.", start.elapsed());
+
+        let node_values = NodeValues::new(vec![(); csr_out.node_count().index()]);
+
+        DirectedCsrGraph::new(node_values, csr_out, csr_inc)
+    }
+}
+
+impl<NI, EV> From<(AdjacencyGraph<NI, EV>, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
+where
+    NI: Idx,
+    EV: Copy + Send + Sync,
+{
+    fn from((graph, csr_layout): (AdjacencyGraph<NI, EV>, CsrLayout)) -> Self {
+        UndirectedCsrGraph::from(graph.csr.with_layout(csr_layout))
+    }
+}
+
+/// Writes the outgoing neighbor lists of a directed graph in the adjacency
+/// graph text format.
+///
+/// If the graph has edge values, i.e., `EV` is not `()`, the graph is written
+/// as `WeightedAdjacencyGraph`.
+///
+/// # Example
+///
+/// ```
+/// use graph_builder::prelude::*;
+/// use graph_builder::input::adjacency_graph;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .csr_layout(CsrLayout::Sorted)
+///     .edges(vec![(0, 1), (0, 2), (1, 2)])
+///     .build();
+///
+/// let mut output = Vec::new();
+/// adjacency_graph::write_directed(&graph, &mut output).unwrap();
+///
+/// assert_eq!(output, b"AdjacencyGraph\n3\n3\n0\n2\n3\n1\n2\n2\n");
+/// ```
+pub fn write_directed<NI, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    EV: TextValue,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write_text(
+        graph.node_count(),
+        |n| graph.out_neighbors_with_values(n),
+        output,
+    )
+}
+
+/// Writes the neighbor lists of an undirected graph in the adjacency graph
+/// text format. Each edge is written twice, once for each of its nodes.
+///
+/// See [`write_directed`] for details on the output.
+pub fn write_undirected<NI, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    EV: TextValue,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write_text(
+        graph.node_count(),
+        |n| graph.neighbors_with_values(n),
+        output,
+    )
+}
+
+/// Writes the outgoing neighbor lists of a directed graph in the binary CSR
+/// format of GBBS. Edge values are not written.
+pub fn write_directed_binary<NI, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write_binary(
+        graph.node_count(),
+        |n| graph.out_neighbors_with_values(n),
+        output,
+    )
+}
+
+/// Writes the neighbor lists of an undirected graph in the binary CSR format
+/// of GBBS. Each edge is written twice, once for each of its nodes. Edge
+/// values are not written.
+pub fn write_undirected_binary<NI, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write_binary(
+        graph.node_count(),
+        |n| graph.neighbors_with_values(n),
+        output,
+    )
+}
+
+/// Computes the offsets of the neighbor lists returned by `neighbors`.
+fn offsets<'g, NI, EV, I, F>(node_count: NI, neighbors: &F) -> Vec<usize>
+where
+    NI: Idx,
+    EV: 'g,
+    I: Iterator<Item = &'g Target<NI, EV>>,
+    F: Fn(NI) -> I,
+{
+    let mut offsets = Vec::with_capacity(node_count.index() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for node in 0..node_count.index() {
+        offset += neighbors(NI::new(node)).count();
+        offsets.push(offset);
+    }
+    offsets
+}
+
+fn write_text<'g, NI, EV, I, F, W>(node_count: NI, neighbors: F, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    EV: TextValue + 'g,
+    I: Iterator<Item = &'g Target<NI, EV>>,
+    F: Fn(NI) -> I,
+    W: Write,
+{
+    let mut out = BufWriter::new(output);
+
+    let offsets = offsets(node_count, &neighbors);
+    let (edge_count, offsets) = offsets.split_last().unwrap();
+
+    let header = match EV::TYPE_NAME {
+        Some(_) => WEIGHTED_HEADER,
+        None => HEADER,
+    };
+    out.write_all(header)?;
+    writeln!(out)?;
+    writeln!(out, "{}", node_count.index())?;
+    writeln!(out, "{}", edge_count)?;
+
+    for offset in offsets {
+        writeln!(out, "{}", offset)?;
+    }
+
+    let nodes = || (0..node_count.index()).flat_map(|node| neighbors(NI::new(node)));
+
+    for target in nodes() {
+        writeln!(out, "{}", target.target.index())?;
+    }
+
+    if EV::TYPE_NAME.is_some() {
+        for target in nodes() {
+            writeln!(out, "{}", target.value.to_text())?;
+        }
+    }
+
+    out.flush()?;
+
+    Ok(())
+}
+
+fn write_binary<'g, NI, EV, I, F, W>(node_count: NI, neighbors: F, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    EV: 'g,
+    I: Iterator<Item = &'g Target<NI, EV>>,
+    F: Fn(NI) -> I,
+    W: Write,
+{
+    let mut out = BufWriter::new(output);
+
+    let offsets = offsets(node_count, &neighbors);
+    let edge_count = offsets[node_count.index()];
+    let size =
+        BINARY_HEADER_SIZE + offsets.len() * BINARY_OFFSET_SIZE + edge_count * BINARY_TARGET_SIZE;
+
+    for value in [node_count.index(), edge_count, size] {
+        out.write_all(&(value as u64).to_le_bytes())?;
+    }
+
+    for offset in offsets {
+        out.write_all(&(offset as u64).to_le_bytes())?;
+    }
+
+    for node in 0..node_count.index() {
+        for target in neighbors(NI::new(node)) {
+            let target = u32::try_from(target.target.index())?;
+            out.write_all(&target.to_le_bytes())?;
+        }
+    }
+
+    out.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::GraphBuilder, DirectedDegrees, DirectedNeighbors, UndirectedDegrees};
+
+    const WEIGHTED: &[u8] = b"WeightedAdjacencyGraph\n4\n5\n\
+        0\n2\n3\n5\n\
+        1\n2\n3\n0\n3\n\
+        42\n43\n44\n45\n46\n";
+
+    #[test]
+    fn parse_text_test() {
+        let graph: DirectedCsrGraph<u32, (), u64> = DirectedCsrGraph::from((
+            AdjacencyGraph::try_from(WEIGHTED).unwrap(),
+            CsrLayout::Sorted,
+        ));
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 5);
+        assert_eq!(
+            graph.out_neighbors_with_values(0).as_slice(),
+            &[Target::new(1, 42), Target::new(2, 43)]
+        );
+        assert_eq!(
+            graph.in_neighbors_with_values(3).as_slice(),
+            &[Target::new(1, 44), Target::new(2, 46)]
+        );
+        assert_eq!(graph.in_degree(0), 1);
+        assert_eq!(graph.out_degree(3), 0);
+    }
+
+    #[test]
+    fn parse_unweighted_text_test() {
+        let input = b"AdjacencyGraph 3 2 0 1 2 1 2";
+
+        let graph: DirectedCsrGraph<usize, (), f32> = DirectedCsrGraph::from((
+            AdjacencyGraph::try_from(&input[..]).unwrap(),
+            CsrLayout::Sorted,
+        ));
+
+        assert_eq!(
+            graph.out_neighbors_with_values(0).as_slice(),
+            &[Target::new(1, 0.0)]
+        );
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let inputs: &[&[u8]] = &[
+            b"AdjacencyGraph\n2\n1\n0\n0\n2\n",
+            b"AdjacencyGraph\n2\n1\n1\n1\n0\n",
+            b"AdjacencyGraph\n2\n2\n0\n1\n1\n",
+            b"AdjacencyGraph\n2\n1\n0\n1\n1\n1\n",
+            b"AdjacencyGraph\n2\nx\n",
+            b"\x02\x00",
+        ];
+
+        for input in inputs {
+            assert!(matches!(
+                AdjacencyGraph::<u32, ()>::try_from(*input),
+                Err(Error::InvalidInput { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn directed_round_trip_test() {
+        let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![(0, 1, 0.5), (0, 2, 1.5), (2, 2, 2.5), (3, 0, 3.5)])
+            .build();
+
+        let mut output = Vec::new();
+        write_directed(&graph, &mut output).unwrap();
+        assert!(output.starts_with(WEIGHTED_HEADER));
+
+        let actual: DirectedCsrGraph<u32, (), f32> = DirectedCsrGraph::from((
+            AdjacencyGraph::try_from(output.as_slice()).unwrap(),
+            CsrLayout::Sorted,
+        ));
+
+        for node in 0..graph.node_count() {
+            assert_eq!(
+                actual.out_neighbors_with_values(node).as_slice(),
+                graph.out_neighbors_with_values(node).as_slice()
+            );
+            assert_eq!(
+                actual.in_neighbors_with_values(node).as_slice(),
+                graph.in_neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn binary_round_trip_test() {
+        let graph: DirectedCsrGraph<u64> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 4), (3, 4)])
+            .build();
+
+        let mut output = Vec::new();
+        write_directed_binary(&graph, &mut output).unwrap();
+        assert_eq!(output.len(), 24 + 6 * 8 + 6 * 4);
+        assert_eq!(read_u64(&output[16..]) as usize, output.len());
+
+        let actual: DirectedCsrGraph<u64> = DirectedCsrGraph::from((
+            AdjacencyGraph::try_from(output.as_slice()).unwrap(),
+            CsrLayout::Sorted,
+        ));
+
+        assert_eq!(actual.node_count(), 5);
+        assert_eq!(actual.edge_count(), 6);
+        for node in 0..graph.node_count() {
+            assert_eq!(
+                actual.out_neighbors_with_values(node).as_slice(),
+                graph.out_neighbors_with_values(node).as_slice()
+            );
+            assert_eq!(
+                actual.in_neighbors_with_values(node).as_slice(),
+                graph.in_neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn binary_with_compression_magic_test() {
+        // The node count 0x8b1f is stored as `1f 8b`, the magic bytes of gzip.
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (1, 0x8b1e)])
+            .build();
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write_directed_binary(&graph, file.as_file_mut()).unwrap();
+
+        let actual: DirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .file_format(AdjacencyGraphInput::default())
+            .path(file.path())
+            .build()
+            .unwrap();
+
+        assert_eq!(actual.node_count(), 0x8b1f);
+        assert_eq!(actual.out_neighbors(1).as_slice(), &[0x8b1e]);
+    }
+
+    #[test]
+    fn undirected_round_trip_test() {
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (1, 2), (2, 0), (3, 1)])
+            .build();
+
+        let mut output = Vec::new();
+        write_undirected(&graph, &mut output).unwrap();
+
+        let actual: UndirectedCsrGraph<u32> = UndirectedCsrGraph::from((
+            AdjacencyGraph::try_from(output.as_slice()).unwrap(),
+            CsrLayout::Sorted,
+        ));
+
+        assert_eq!(actual.edge_count(), graph.edge_count());
+        for node in 0..graph.node_count() {
+            assert_eq!(actual.degree(node), graph.degree(node));
+            assert_eq!(
+                actual.neighbors_with_values(node).as_slice(),
+                graph.neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+}
@@ -0,0 +1,616 @@
+//! Reads and writes graphs stored in the Arrow IPC file format, also known as
+//! Feather (v2).
+use std::{
+    any::TypeId,
+    convert::TryFrom,
+    fs::File,
+    io::{Cursor, Write},
+    marker::PhantomData,
+    path::Path,
+    sync::Arc,
+    time::Instant,
+};
+
+use arrow::{
+    array::{Array, ArrayRef, PrimitiveArray},
+    datatypes::*,
+    ipc::{reader::FileReader, writer::FileWriter},
+    record_batch::RecordBatch,
+};
+use log::info;
+use memmap2::Mmap;
+use num::NumCast;
+
+use crate::{
+    graph::csr::{CsrLayout, DirectedCsrGraph, UndirectedCsrGraph},
+    index::Idx,
+    input::{edgelist::EdgeList, InputCapabilities, InputPath},
+    DirectedNeighborsWithValues, Error, Graph, NodeValues,
+};
+
+/// Reads a graph from an Arrow IPC file that stores one edge per row.
+///
+/// Source and target node ids are read from the columns `source` and
+/// `target`, edge values are only read if a value column is set. The id
+/// columns must have an integer type, the value column can have any integer or
+/// floating point type. Values are converted to the node id and edge value
+/// types of the graph. Columns must not contain nulls.
+///
+/// The file is memory-mapped and read one record batch at a time. The IPC
+/// reader of arrow copies every column out of the mapping into its own buffer,
+/// from which the values are copied into the edge list. Columns whose type
+/// matches the requested type are copied as a whole, other columns are
+/// converted value by value.
+///
+/// # Example
+///
+/// ```ignore
+/// use graph_builder::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .file_format(ArrowIpcInput::new("src", "dst").value_column("weight"))
+///     .path("edges.arrow")
+///     .build()
+///     .expect("loading failed");
+/// ```
+pub struct ArrowIpcInput<NI: Idx, EV = ()> {
+    columns: EdgeColumns,
+    _idx: PhantomData<(NI, EV)>,
+}
+
+impl<NI: Idx, EV> Default for ArrowIpcInput<NI, EV> {
+    fn default() -> Self {
+        Self {
+            columns: EdgeColumns::default(),
+            _idx: PhantomData,
+        }
+    }
+}
+
+impl<NI: Idx, EV> ArrowIpcInput<NI, EV> {
+    /// Creates an input that reads node ids from the given columns.
+    pub fn new(source_column: impl Into<String>, target_column: impl Into<String>) -> Self {
+        Self {
+            columns: EdgeColumns::new(source_column.into(), target_column.into()),
+            _idx: PhantomData,
+        }
+    }
+
+    /// Sets the column from which edge values are read.
+    pub fn value_column(mut self, value_column: impl Into<String>) -> Self {
+        self.columns.value = Some(value_column.into());
+        self
+    }
+}
+
+impl<NI: Idx, EV> InputCapabilities<NI> for ArrowIpcInput<NI, EV> {
+    type GraphInput = ArrowIpc<NI, EV>;
+
+    fn configure(&self, mut input: Self::GraphInput) -> Self::GraphInput {
+        input.columns = self.columns.clone();
+        input
+    }
+}
+
+/// A memory-mapped Arrow IPC file and the columns to read edges from.
+pub struct ArrowIpc<NI: Idx, EV> {
+    mmap: Mmap,
+    columns: EdgeColumns,
+    _idx: PhantomData<(NI, EV)>,
+}
+
+impl<NI, EV, P> TryFrom<InputPath<P>> for ArrowIpc<NI, EV>
+where
+    P: AsRef<Path>,
+    NI: Idx,
+{
+    type Error = Error;
+
+    fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
+        let file = File::open(path.0.as_ref())?;
+        let mmap = unsafe { memmap2::MmapOptions::new().populate().map(&file)? };
+
+        Ok(Self {
+            mmap,
+            columns: EdgeColumns::default(),
+            _idx: PhantomData,
+        })
+    }
+}
+
+impl<NI, EV> ArrowIpc<NI, EV>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Default + Sync,
+{
+    /// Reads the selected columns of all record batches into an edge list.
+    ///
+    /// Every call reads the file from the start.
+    pub fn edge_list(&self) -> Result<EdgeList<NI, EV>, Error> {
+        let start = Instant::now();
+        let reader = FileReader::try_new(Cursor::new(&self.mmap[..]))?;
+
+        let mut edges = Vec::new();
+
+        for batch in reader {
+            self.columns.read_edges(&batch?, &mut edges)?;
+        }
+
+        info!("Read {} edges in {:?}", edges.len(), start.elapsed());
+
+        Ok(EdgeList::new(edges))
+    }
+}
+
+/// The names of the columns that store source ids, target ids and, optionally,
+/// edge values.
+#[derive(Clone, Debug)]
+pub(crate) struct EdgeColumns {
+    pub(crate) source: String,
+    pub(crate) target: String,
+    pub(crate) value: Option<String>,
+}
+
+impl Default for EdgeColumns {
+    fn default() -> Self {
+        Self::new(String::from("source"), String::from("target"))
+    }
+}
+
+impl EdgeColumns {
+    pub(crate) fn new(source: String, target: String) -> Self {
+        Self {
+            source,
+            target,
+            value: None,
+        }
+    }
+
+    /// Reads the edges of the given record batch and appends them to `edges`.
+    pub(crate) fn read_edges<NI, EV>(
+        &self,
+        batch: &RecordBatch,
+        edges: &mut Vec<(NI, NI, EV)>,
+    ) -> Result<(), Error>
+    where
+        NI: ArrowValue,
+        EV: ArrowValue + Default,
+    {
+        let sources = NI::read_column(id_column(batch, &self.source)?, &self.source)?;
+        let targets = NI::read_column(id_column(batch, &self.target)?, &self.target)?;
+        let values = match &self.value {
+            Some(name) => EV::read_column(column(batch, name)?, name)?,
+            None => (0..batch.num_rows()).map(|_| EV::default()).collect(),
+        };
+
+        edges.extend(
+            sources
+                .into_iter()
+                .zip(targets)
+                .zip(values)
+                .map(|((s, t), v)| (s, t, v)),
+        );
+
+        Ok(())
+    }
+}
+
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef, Error> {
+    let index = batch.schema().index_of(name)?;
+    Ok(batch.column(index))
+}
+
+/// Returns the column with the given name if it stores integers. Node ids are
+/// never read from floating point columns, which would truncate them.
+fn id_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef, Error> {
+    let column = column(batch, name)?;
+    match column.data_type() {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => Ok(column),
+        other => Err(invalid_column(
+            name,
+            format!("node ids must be integers, got {:?}", other),
+        )),
+    }
+}
+
+impl<NI, EV> TryFrom<(ArrowIpc<NI, EV>, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from((input, csr_layout): (ArrowIpc<NI, EV>, CsrLayout)) -> Result<Self, Self::Error> {
+        Ok(DirectedCsrGraph::from((input.edge_list()?, csr_layout)))
+    }
+}
+
+impl<NI, EV> TryFrom<(ArrowIpc<NI, EV>, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from((input, csr_layout): (ArrowIpc<NI, EV>, CsrLayout)) -> Result<Self, Self::Error> {
+        Ok(UndirectedCsrGraph::from((input.edge_list()?, csr_layout)))
+    }
+}
+
+/// A node id or value type that can be read from and written to an Arrow
+/// column.
+pub trait ArrowValue: Sized + Send {
+    /// Reads all values of the given column, converting them if necessary.
+    fn read_column(array: &dyn Array, name: &str) -> Result<Vec<Self>, Error>;
+
+    /// Creates a column from the given values. Returns `None` for types that
+    /// are not stored, e.g., `()`.
+    fn write_column(values: Vec<Self>) -> Option<ArrayRef>;
+}
+
+impl ArrowValue for () {
+    fn read_column(array: &dyn Array, _name: &str) -> Result<Vec<Self>, Error> {
+        Ok(vec![(); array.len()])
+    }
+
+    fn write_column(_values: Vec<Self>) -> Option<ArrayRef> {
+        None
+    }
+}
+
+macro_rules! impl_arrow_value {
+    ($($ty:ty => $arrow:ty),+ $(,)?) => {
+        $(
+            impl $crate::input::arrow::ArrowValue for $ty {
+                fn read_column(array: &dyn Array, name: &str) -> Result<Vec<Self>, Error> {
+                    read_primitive(array, name)
+                }
+
+                fn write_column(values: Vec<Self>) -> Option<ArrayRef> {
+                    let values = values
+                        .into_iter()
+                        .map(|v| v as <$arrow as ArrowPrimitiveType>::Native)
+                        .collect::<Vec<_>>();
+                    Some(Arc::new(PrimitiveArray::<$arrow>::from(values)))
+                }
+            }
+        )+
+    };
+}
+
+impl_arrow_value!(
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    isize => Int64Type,
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type,
+    usize => UInt64Type,
+    f32 => Float32Type,
+    f64 => Float64Type,
+);
+
+fn read_primitive<T>(array: &dyn Array, name: &str) -> Result<Vec<T>, Error>
+where
+    T: NumCast + Copy + 'static,
+{
+    if array.null_count() > 0 {
+        return Err(invalid_column(name, "null values are not supported"));
+    }
+
+    macro_rules! read {
+        ($($variant:ident => $arrow:ty),+ $(,)?) => {
+            match array.data_type() {
+                $(
+                    DataType::$variant => {
+                        let values = array
+                            .as_any()
+                            .downcast_ref::<PrimitiveArray<$arrow>>()
+                            .expect("array type matches data type")
+                            .values();
+                        convert(values, name)
+                    }
+                )+
+                other => Err(invalid_column(name, format!("unsupported type {:?}", other))),
+            }
+        };
+    }
+
+    read!(
+        Int8 => Int8Type,
+        Int16 => Int16Type,
+        Int32 => Int32Type,
+        Int64 => Int64Type,
+        UInt8 => UInt8Type,
+        UInt16 => UInt16Type,
+        UInt32 => UInt32Type,
+        UInt64 => UInt64Type,
+        Float32 => Float32Type,
+        Float64 => Float64Type,
+    )
+}
+
+fn convert<N, T>(values: &[N], name: &str) -> Result<Vec<T>, Error>
+where
+    N: NumCast + Copy + 'static,
+    T: NumCast + Copy + 'static,
+{
+    if TypeId::of::<N>() == TypeId::of::<T>() {
+        // SAFETY: N and T are the same type.
+        let values =
+            unsafe { std::slice::from_raw_parts(values.as_ptr() as *const T, values.len()) };
+        return Ok(values.to_vec());
+    }
+
+    values
+        .iter()
+        .map(|v| T::from(*v).ok_or_else(|| invalid_column(name, "value out of range")))
+        .collect()
+}
+
+fn invalid_column(column: &str, reason: impl Into<String>) -> Error {
+    Error::InvalidArrowColumn {
+        column: column.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Exports the outgoing edges of a graph as a record batch.
+///
+/// The record batch contains the columns `source` and `target`, and a `value`
+/// column unless the edge value type is `()`. It can be read again using the
+/// default [`ArrowIpcInput`].
+pub fn edges_to_record_batch<NI, EV, G>(graph: &G) -> Result<RecordBatch, Error>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Copy,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, EV>,
+{
+    let edge_count = graph.edge_count().index();
+
+    let mut sources = Vec::with_capacity(edge_count);
+    let mut targets = Vec::with_capacity(edge_count);
+    let mut values = Vec::with_capacity(edge_count);
+
+    for node in 0..graph.node_count().index() {
+        let node = NI::new(node);
+        for target in graph.out_neighbors_with_values(node) {
+            sources.push(node);
+            targets.push(target.target);
+            values.push(target.value);
+        }
+    }
+
+    let mut columns = Vec::with_capacity(3);
+    push_column(&mut columns, "source", sources);
+    push_column(&mut columns, "target", targets);
+    push_column(&mut columns, "value", values);
+
+    record_batch(columns)
+}
+
+/// Exports the node values of a graph as a record batch with the columns `id`
+/// and `value`.
+pub fn node_values_to_record_batch<NI, NV, G>(graph: &G) -> Result<RecordBatch, Error>
+where
+    NI: Idx + ArrowValue,
+    NV: ArrowValue + Copy,
+    G: Graph<NI> + NodeValues<NI, NV>,
+{
+    let node_count = graph.node_count().index();
+
+    let ids = (0..node_count).map(NI::new).collect::<Vec<_>>();
+    let values = ids.iter().map(|node| *graph.node_value(*node)).collect();
+
+    let mut columns = Vec::with_capacity(2);
+    push_column(&mut columns, "id", ids);
+    push_column(&mut columns, "value", values);
+
+    record_batch(columns)
+}
+
+/// Writes the given record batches into an Arrow IPC file. All batches must
+/// have the same schema.
+pub fn write_record_batches<W: Write>(writer: W, batches: &[RecordBatch]) -> Result<(), Error> {
+    let schema = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => Arc::new(Schema::empty()),
+    };
+
+    let mut writer = FileWriter::try_new(writer, &schema)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+fn push_column<T: ArrowValue>(columns: &mut Vec<(Field, ArrayRef)>, name: &str, values: Vec<T>) {
+    if let Some(column) = T::write_column(values) {
+        columns.push((Field::new(name, column.data_type().clone(), false), column));
+    }
+}
+
+fn record_batch(columns: Vec<(Field, ArrayRef)>) -> Result<RecordBatch, Error> {
+    let (fields, columns): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use arrow::array::{Float32Array, Float64Array, Int32Array};
+
+    use rayon::prelude::*;
+
+    use super::*;
+    use crate::{builder::GraphBuilder, input::Edges};
+
+    fn write_batch(batch: RecordBatch) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write_record_batches(file.as_file_mut(), &[batch]).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn read_selected_columns_test() {
+        let schema = Schema::new(vec![
+            Field::new("src", DataType::Int32, false),
+            Field::new("dst", DataType::Int32, false),
+            Field::new("weight", DataType::Float64, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![0, 0, 1])),
+                Arc::new(Int32Array::from(vec![1, 2, 2])),
+                Arc::new(Float64Array::from(vec![0.5, 1.5, 2.5])),
+            ],
+        )
+        .unwrap();
+
+        let file = write_batch(batch);
+
+        let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .file_format(ArrowIpcInput::new("src", "dst").value_column("weight"))
+            .path(file.path())
+            .build()
+            .unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+
+        let values = graph
+            .out_neighbors_with_values(0)
+            .map(|t| (t.target, t.value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(values, vec![(1, 0.5), (2, 1.5)]);
+    }
+
+    #[test]
+    fn missing_column_test() {
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new().edges(vec![(0, 1), (1, 2)]).build();
+
+        let file = write_batch(edges_to_record_batch(&graph).unwrap());
+
+        let result: Result<DirectedCsrGraph<u32>, _> = GraphBuilder::new()
+            .file_format(ArrowIpcInput::new("src", "dst"))
+            .path(file.path())
+            .build();
+
+        assert!(matches!(result, Err(Error::ArrowError { .. })));
+    }
+
+    #[test]
+    fn float_id_column_test() {
+        let schema = Schema::new(vec![
+            Field::new("source", DataType::Float64, false),
+            Field::new("target", DataType::Int32, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Float64Array::from(vec![0.0, 1.5])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+
+        let file = write_batch(batch);
+
+        let result: Result<DirectedCsrGraph<u32>, _> = GraphBuilder::new()
+            .file_format(ArrowIpcInput::default())
+            .path(file.path())
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArrowColumn { column, .. }) if column == "source"
+        ));
+    }
+
+    #[test]
+    fn repeated_edge_list_test() {
+        let graph: DirectedCsrGraph<u32> = GraphBuilder::new().edges(vec![(0, 1), (1, 2)]).build();
+
+        let file = write_batch(edges_to_record_batch(&graph).unwrap());
+
+        let input = ArrowIpc::<u32, ()>::try_from(InputPath(file.path())).unwrap();
+
+        let first = input.edge_list().unwrap();
+        let second = input.edge_list().unwrap();
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(
+            first.edges().collect::<Vec<_>>(),
+            second.edges().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let graph: DirectedCsrGraph<usize, (), f64> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![(0, 1, 0.1), (0, 2, 0.2), (1, 2, 0.3), (2, 0, 0.4)])
+            .build();
+
+        let batch = edges_to_record_batch(&graph).unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        assert_eq!(batch.schema().field(0).data_type(), &DataType::UInt64);
+
+        let file = write_batch(batch);
+
+        let actual: DirectedCsrGraph<usize, (), f64> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .file_format(ArrowIpcInput::default().value_column("value"))
+            .path(file.path())
+            .build()
+            .unwrap();
+
+        for node in 0..graph.node_count() {
+            assert_eq!(
+                actual.out_neighbors_with_values(node).as_slice(),
+                graph.out_neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn node_values_test() {
+        let graph: DirectedCsrGraph<u32, f32> = GraphBuilder::new()
+            .edges(vec![(0, 1), (1, 2)])
+            .node_values(vec![0.5, 1.5, 2.5])
+            .build();
+
+        let batch = node_values_to_record_batch(&graph).unwrap();
+
+        let values = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap()
+            .values()
+            .to_vec();
+
+        assert_eq!(batch.schema().field(0).name(), "id");
+        assert_eq!(values, vec![0.5, 1.5, 2.5]);
+    }
+}
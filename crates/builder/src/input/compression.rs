@@ -0,0 +1,430 @@
+//! Transparent decompression of graph input files.
+//!
+//! Compressed text files are detected by their magic bytes or, if the magic
+//! bytes are not conclusive, by their file extension. Binary files are only
+//! detected by their file extension, since their content may start with any
+//! bytes. Files that consist of independently compressed blocks, i.e., BGZF
+//! files (`bgzip`) and zstd files with multiple frames (`zstd -T0`, `pzstd`),
+//! are decompressed in parallel.
+//!
+//! Decompression requires the `compression` feature. Without it, compressed
+//! files are still detected, but reading them fails with an error.
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    ops::Deref,
+    path::Path,
+    time::Instant,
+};
+
+#[cfg(feature = "compression")]
+use flate2::read::{GzDecoder, MultiGzDecoder};
+use log::info;
+use memmap2::Mmap;
+#[cfg(feature = "compression")]
+use rayon::prelude::*;
+#[cfg(feature = "compression")]
+use std::io::{BufReader, ErrorKind};
+
+use crate::Error;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: [u8; 3] = *b"BZh";
+
+/// The compression format of an input file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// Detects the compression format from the magic bytes at the start of a
+    /// file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::input::compression::Compression;
+    ///
+    /// assert_eq!(Compression::from_magic(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+    /// assert_eq!(Compression::from_magic(b"0 1\n"), Compression::None);
+    /// ```
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else if bytes.starts_with(&BZIP2_MAGIC) {
+            Compression::Bzip2
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Detects the compression format from the file extension.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::input::compression::Compression;
+    ///
+    /// assert_eq!(Compression::from_extension("graph.txt.zst"), Compression::Zstd);
+    /// assert_eq!(Compression::from_extension("graph.txt"), Compression::None);
+    /// ```
+    pub fn from_extension(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("gz" | "gzip" | "bgz") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            Some("bz2" | "bzip2") => Compression::Bzip2,
+            _ => Compression::None,
+        }
+    }
+
+    /// Detects the compression format from the magic bytes and falls back to
+    /// the file extension if the magic bytes are unknown.
+    pub fn detect(path: impl AsRef<Path>, bytes: &[u8]) -> Self {
+        match Compression::from_magic(bytes) {
+            Compression::None => Compression::from_extension(path),
+            compression => compression,
+        }
+    }
+}
+
+/// The content of an input file, either memory-mapped or decompressed.
+pub(crate) enum InputBytes {
+    Mapped(Mmap),
+    Decompressed(Vec<u8>),
+}
+
+impl Deref for InputBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            InputBytes::Mapped(mmap) => mmap.as_ref(),
+            InputBytes::Decompressed(bytes) => bytes.as_slice(),
+        }
+    }
+}
+
+/// Memory-maps the given text file and decompresses it if necessary.
+pub(crate) fn read_bytes(path: &Path) -> Result<InputBytes, Error> {
+    let mmap = map_file(path)?;
+    let compression = Compression::detect(path, mmap.as_ref());
+    decompress_mapped(compression, mmap)
+}
+
+/// Memory-maps the given binary file and decompresses it if its extension
+/// says so. The magic bytes are not considered, since a binary file may start
+/// with any bytes.
+pub(crate) fn read_binary_bytes(path: &Path) -> Result<InputBytes, Error> {
+    let mmap = map_file(path)?;
+    decompress_mapped(Compression::from_extension(path), mmap)
+}
+
+fn map_file(path: &Path) -> Result<Mmap, Error> {
+    let file = File::open(path)?;
+    Ok(unsafe { memmap2::MmapOptions::new().populate().map(&file)? })
+}
+
+fn decompress_mapped(compression: Compression, mmap: Mmap) -> Result<InputBytes, Error> {
+    if compression == Compression::None {
+        return Ok(InputBytes::Mapped(mmap));
+    }
+
+    let start = Instant::now();
+    let bytes = decompress(compression, mmap.as_ref())?;
+
+    info!(
+        "Decompressed {:?} input from {} to {} bytes in {:?}",
+        compression,
+        mmap.len(),
+        bytes.len(),
+        start.elapsed()
+    );
+
+    Ok(InputBytes::Decompressed(bytes))
+}
+
+/// Opens the given text file as a stream that decompresses on the fly if
+/// necessary.
+pub(crate) fn open_reader(path: &Path) -> Result<Box<dyn Read + Send>, Error> {
+    let mut file = File::open(path)?;
+
+    let mut magic = [0_u8; 4];
+    let len = read_prefix(&mut file, &mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    decoder(Compression::detect(path, &magic[..len]), file)
+}
+
+fn read_prefix(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match file.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(feature = "compression")]
+fn decoder(compression: Compression, file: File) -> Result<Box<dyn Read + Send>, Error> {
+    let reader: Box<dyn Read + Send> = match compression {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+        Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(BufReader::new(file))),
+    };
+
+    Ok(reader)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decoder(compression: Compression, file: File) -> Result<Box<dyn Read + Send>, Error> {
+    match compression {
+        Compression::None => Ok(Box::new(file)),
+        compression => Err(unsupported(compression)),
+    }
+}
+
+#[cfg(feature = "compression")]
+fn decompress(compression: Compression, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    match compression {
+        Compression::None => Ok(bytes.to_vec()),
+        Compression::Gzip => match bgzf_blocks(bytes) {
+            Some(blocks) => decompress_blocks(&blocks, |block| read_all(GzDecoder::new(block))),
+            None => read_all(MultiGzDecoder::new(bytes)),
+        },
+        Compression::Zstd => {
+            let frames = zstd_frames(bytes)?;
+            decompress_blocks(&frames, |frame| Ok(zstd::stream::decode_all(frame)?))
+        }
+        Compression::Bzip2 => read_all(bzip2::read::MultiBzDecoder::new(bytes)),
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(compression: Compression, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    match compression {
+        Compression::None => Ok(bytes.to_vec()),
+        compression => Err(unsupported(compression)),
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+fn unsupported(compression: Compression) -> Error {
+    Error::InvalidInput {
+        format: format!("{:?}", compression),
+        reason: "decompression requires the `compression` feature".to_string(),
+    }
+}
+
+#[cfg(feature = "compression")]
+fn read_all<R: Read>(mut reader: R) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(feature = "compression")]
+fn decompress_blocks<F>(blocks: &[&[u8]], decompress: F) -> Result<Vec<u8>, Error>
+where
+    F: Fn(&[u8]) -> Result<Vec<u8>, Error> + Sync,
+{
+    let blocks = blocks
+        .par_iter()
+        .map(|block| decompress(block))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(blocks.concat())
+}
+
+#[cfg(feature = "compression")]
+/// Splits a BGZF file into its gzip blocks. Returns `None` if the file is a
+/// regular gzip file.
+///
+/// Each BGZF block is a gzip member with an extra field `BC` that stores the
+/// size of the block, see the SAM specification, section 4.1.
+fn bgzf_blocks(bytes: &[u8]) -> Option<Vec<&[u8]>> {
+    const FEXTRA: u8 = 0x04;
+
+    let mut blocks = Vec::new();
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        if rest.len() < 18
+            || !rest.starts_with(&GZIP_MAGIC)
+            || rest[3] & FEXTRA == 0
+            || rest[12..14] != *b"BC"
+        {
+            return None;
+        }
+
+        let block_size = u16::from_le_bytes([rest[16], rest[17]]) as usize + 1;
+        if block_size > rest.len() {
+            return None;
+        }
+
+        let (block, tail) = rest.split_at(block_size);
+        blocks.push(block);
+        rest = tail;
+    }
+
+    Some(blocks)
+}
+
+#[cfg(feature = "compression")]
+/// Splits a zstd file into its frames.
+fn zstd_frames(bytes: &[u8]) -> Result<Vec<&[u8]>, Error> {
+    let mut frames = Vec::new();
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        let frame_size = zstd::zstd_safe::find_frame_compressed_size(rest).map_err(|code| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                zstd::zstd_safe::get_error_name(code),
+            )
+        })?;
+
+        let (frame, tail) = rest.split_at(frame_size);
+        frames.push(frame);
+        rest = tail;
+    }
+
+    Ok(frames)
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, GzBuilder};
+
+    use super::*;
+
+    const CONTENT: &[u8] = b"0 1\n0 2\n1 2\n1 3\n2 3\n";
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn bgzf_block(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzBuilder::new()
+            .extra(vec![b'B', b'C', 2, 0, 0, 0])
+            .write(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        let mut block = encoder.finish().unwrap();
+
+        let block_size = (block.len() - 1) as u16;
+        block[16..18].copy_from_slice(&block_size.to_le_bytes());
+        block
+    }
+
+    fn bzip2(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn detect_test() {
+        assert_eq!(
+            Compression::detect("g.txt", &gzip(CONTENT)),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::detect("g.txt", &bzip2(CONTENT)),
+            Compression::Bzip2
+        );
+        assert_eq!(
+            Compression::detect("g.txt", &zstd::encode_all(CONTENT, 0).unwrap()),
+            Compression::Zstd
+        );
+        assert_eq!(Compression::detect("g.bz2", CONTENT), Compression::Bzip2);
+        assert_eq!(Compression::detect("g.txt", CONTENT), Compression::None);
+    }
+
+    #[test]
+    fn read_binary_bytes_test() {
+        // A binary file that happens to start with the gzip magic bytes.
+        let content = [GZIP_MAGIC[0], GZIP_MAGIC[1], 0, 0, 1, 0, 0, 0, 0, 0, 0, 0];
+
+        let mut file = tempfile::Builder::new()
+            .suffix(".graph500")
+            .tempfile()
+            .unwrap();
+        file.write_all(&content).unwrap();
+        file.flush().unwrap();
+
+        let bytes = read_binary_bytes(file.path()).unwrap();
+
+        assert!(matches!(bytes, InputBytes::Mapped(_)));
+        assert_eq!(&*bytes, &content);
+
+        let mut file = tempfile::Builder::new()
+            .suffix(".graph500.gz")
+            .tempfile()
+            .unwrap();
+        file.write_all(&gzip(&content)).unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(&*read_binary_bytes(file.path()).unwrap(), &content);
+    }
+
+    #[test]
+    fn gzip_test() {
+        let mut bytes = gzip(&CONTENT[..8]);
+        bytes.extend(gzip(&CONTENT[8..]));
+
+        assert!(bgzf_blocks(&bytes).is_none());
+        assert_eq!(decompress(Compression::Gzip, &bytes).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn bgzf_test() {
+        let mut bytes = bgzf_block(&CONTENT[..8]);
+        bytes.extend(bgzf_block(&CONTENT[8..]));
+        bytes.extend(bgzf_block(&[]));
+
+        assert_eq!(bgzf_blocks(&bytes).unwrap().len(), 3);
+        assert_eq!(decompress(Compression::Gzip, &bytes).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn zstd_test() {
+        let mut bytes = zstd::encode_all(&CONTENT[..8], 0).unwrap();
+        bytes.extend(zstd::encode_all(&CONTENT[8..], 0).unwrap());
+
+        assert_eq!(zstd_frames(&bytes).unwrap().len(), 2);
+        assert_eq!(decompress(Compression::Zstd, &bytes).unwrap(), CONTENT);
+    }
+
+    #[test]
+    fn bzip2_test() {
+        assert_eq!(
+            decompress(Compression::Bzip2, &bzip2(CONTENT)).unwrap(),
+            CONTENT
+        );
+    }
+
+    #[test]
+    fn open_reader_test() {
+        let mut file = tempfile::Builder::new().suffix(".zst").tempfile().unwrap();
+        file.write_all(&zstd::encode_all(CONTENT, 0).unwrap())
+            .unwrap();
+        file.flush().unwrap();
+
+        let actual = read_all(open_reader(file.path()).unwrap()).unwrap();
+
+        assert_eq!(actual, CONTENT);
+    }
+}
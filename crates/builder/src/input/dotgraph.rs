@@ -1,6 +1,6 @@
 use std::{
-    convert::TryFrom, fs::File, hash::Hash, io::Read, marker::PhantomData, mem::ManuallyDrop,
-    path::Path, sync::atomic::Ordering::Acquire,
+    convert::TryFrom, hash::Hash, io::Read, marker::PhantomData, mem::ManuallyDrop, path::Path,
+    sync::atomic::Ordering::Acquire,
 };
 
 use atomic::Atomic;
@@ -14,7 +14,7 @@ use crate::{
     Error, SharedMut,
 };
 
-use super::{edgelist::EdgeList, InputCapabilities, InputPath};
+use super::{compression, edgelist::EdgeList, InputCapabilities, InputPath};
 
 /// DotGraph (the name is based on the file ending `.graph`) is a textual
 /// description of a node labeled graph primarily used as input for subgraph
@@ -185,8 +185,7 @@ where
     type Error = Error;
 
     fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
-        let file = File::open(path.0.as_ref())?;
-        let reader = LineReader::new(file);
+        let reader = LineReader::new(compression::open_reader(path.0.as_ref())?);
         let dot_graph = DotGraph::try_from(reader)?;
         Ok(dot_graph)
     }
@@ -1,6 +1,6 @@
 use atomic::Atomic;
 use log::info;
-use std::{convert::TryFrom, fs::File, marker::PhantomData, path::Path, sync::Arc};
+use std::{convert::TryFrom, marker::PhantomData, mem::size_of, path::Path, sync::Arc};
 
 use crate::index::Idx;
 
@@ -8,9 +8,13 @@ use parking_lot::Mutex;
 use rayon::prelude::*;
 use std::sync::atomic::Ordering::AcqRel;
 
-use crate::{input::Direction, Error};
+use crate::{
+    input::Direction,
+    memory::{MemoryFootprint, MemoryUsage},
+    Error,
+};
 
-use super::{InputCapabilities, InputPath, ParseValue};
+use super::{compression, InputCapabilities, InputPath, ParseValue};
 
 /// Reads a graph from a file that contains an edge per line.
 ///
@@ -111,6 +115,18 @@ impl<NI: Idx, EV: Sync> EdgeList<NI, EV> {
     }
 }
 
+impl<NI: Idx, EV> MemoryUsage for EdgeList<NI, EV> {
+    fn memory_usage(&self) -> MemoryFootprint {
+        let len = self.list.len();
+
+        MemoryFootprint {
+            targets: len * 2 * size_of::<NI>(),
+            edge_values: len * (size_of::<(NI, NI, EV)>() - 2 * size_of::<NI>()),
+            ..MemoryFootprint::default()
+        }
+    }
+}
+
 impl<NI: Idx, EV: Copy + Send + Sync> Edges for EdgeList<NI, EV> {
     type NI = NI;
 
@@ -171,9 +187,8 @@ where
     type Error = Error;
 
     fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
-        let file = File::open(path.0.as_ref())?;
-        let mmap = unsafe { memmap2::MmapOptions::new().populate().map(&file)? };
-        EdgeList::try_from(mmap.as_ref())
+        let bytes = compression::read_bytes(path.0.as_ref())?;
+        EdgeList::try_from(bytes.as_ref())
     }
 }
 
@@ -1,62 +1,235 @@
-use crate::graph::csr::{CsrLayout, DirectedCsrGraph, UndirectedCsrGraph};
+use crate::graph::csr::{CsrLayout, DirectedCsrGraph, NodeValues, UndirectedCsrGraph};
 use crate::index::Idx;
 use crate::input::{DotGraph, EdgeList};
+use crate::Error;
 
 use gdl::CypherValue;
 use linereader::LineReader;
 use std::fmt::Write;
 use std::hash::Hash;
 
-/// A wrapper around [`gdl::CypherValue`] to allow custom From implementations.
+/// A wrapper around [`gdl::CypherValue`] to allow custom TryFrom implementations.
 pub struct MyCypherValue<'a>(&'a CypherValue);
 
-impl<'a> From<MyCypherValue<'a>> for () {
-    fn from(_: MyCypherValue) -> Self {}
+impl<'a> TryFrom<MyCypherValue<'a>> for () {
+    type Error = Error;
+
+    fn try_from(_: MyCypherValue) -> Result<Self, Self::Error> {
+        Ok(())
+    }
 }
 
-macro_rules! impl_from_cypher_value {
+macro_rules! impl_try_from_cypher_value {
     ($enum:path, $ty:ty) => {
-        impl<'a> ::std::convert::From<$crate::input::gdl::MyCypherValue<'a>> for $ty {
-            fn from(cv: $crate::input::gdl::MyCypherValue) -> Self {
+        impl<'a> ::std::convert::TryFrom<$crate::input::gdl::MyCypherValue<'a>> for $ty {
+            type Error = $crate::Error;
+
+            fn try_from(cv: $crate::input::gdl::MyCypherValue) -> Result<Self, Self::Error> {
                 if let $enum(f) = cv.0 {
-                    *f as $ty
+                    Ok(*f as $ty)
                 } else {
-                    panic!("expected {} value", stringify!($ty))
+                    Err($crate::Error::InvalidGdlValue {
+                        expected: stringify!($ty).to_string(),
+                        actual: format!("{:?}", cv.0),
+                    })
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_try_from_cypher_integer {
+    ($ty:ty) => {
+        impl<'a> ::std::convert::TryFrom<$crate::input::gdl::MyCypherValue<'a>> for $ty {
+            type Error = $crate::Error;
+
+            fn try_from(cv: $crate::input::gdl::MyCypherValue) -> Result<Self, Self::Error> {
+                match cv.0 {
+                    CypherValue::Integer(i) => <$ty>::try_from(*i).ok(),
+                    _ => None,
                 }
+                .ok_or_else(|| $crate::Error::InvalidGdlValue {
+                    expected: stringify!($ty).to_string(),
+                    actual: format!("{:?}", cv.0),
+                })
             }
         }
     };
 }
 
-impl_from_cypher_value!(CypherValue::Float, f32);
-impl_from_cypher_value!(CypherValue::Float, f64);
-impl_from_cypher_value!(CypherValue::Integer, i32);
-impl_from_cypher_value!(CypherValue::Integer, i64);
+impl_try_from_cypher_value!(CypherValue::Float, f32);
+impl_try_from_cypher_value!(CypherValue::Float, f64);
+impl_try_from_cypher_integer!(i32);
+impl_try_from_cypher_integer!(i64);
+impl_try_from_cypher_integer!(u32);
+impl_try_from_cypher_integer!(u64);
+impl_try_from_cypher_integer!(usize);
+
+/// Selects where node or edge values are read from when converting a
+/// [`gdl::Graph`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GdlValue {
+    /// Reads the value of the property with the given key. The property must
+    /// exist on every node or relationship.
+    Property(String),
+    /// Uses the id of the node label or the relationship type as value. Ids
+    /// are assigned in lexicographic order of the distinct names, see
+    /// [`node_labels`] and [`relationship_types`]. For nodes with multiple
+    /// labels, the first label is used.
+    Label,
+}
+
+impl GdlValue {
+    /// Creates a value selector for the property with the given key.
+    pub fn property(key: impl Into<String>) -> Self {
+        GdlValue::Property(key.into())
+    }
+}
+
+/// Returns the distinct node labels of the given GDL graph in lexicographic
+/// order. The position of a label is the id it is mapped to by
+/// [`GdlValue::Label`].
+pub fn node_labels(gdl_graph: &gdl::Graph) -> Vec<&str> {
+    let mut labels = gdl_graph
+        .nodes()
+        .flat_map(|node| node.labels())
+        .collect::<Vec<_>>();
+    labels.sort_unstable();
+    labels.dedup();
+    labels
+}
+
+/// Returns the distinct relationship types of the given GDL graph in
+/// lexicographic order. The position of a type is the id it is mapped to by
+/// [`GdlValue::Label`].
+pub fn relationship_types(gdl_graph: &gdl::Graph) -> Vec<&str> {
+    let mut types = gdl_graph
+        .relationships()
+        .filter_map(|rel| rel.rel_type())
+        .collect::<Vec<_>>();
+    types.sort_unstable();
+    types.dedup();
+    types
+}
+
+fn label_value<V>(names: &[&str], name: Option<&str>, variable: &str) -> Result<V, Error>
+where
+    V: for<'a> TryFrom<MyCypherValue<'a>, Error = Error>,
+{
+    let name = name.ok_or_else(|| Error::InvalidGdlValue {
+        expected: "label".to_string(),
+        actual: format!("no label on `{}`", variable),
+    })?;
+    let id = names.binary_search(&name).expect("label is collected");
+    V::try_from(MyCypherValue(&CypherValue::Integer(id as i64)))
+}
+
+fn property_value<V>(value: Option<&CypherValue>, key: &str, variable: &str) -> Result<V, Error>
+where
+    V: for<'a> TryFrom<MyCypherValue<'a>, Error = Error>,
+{
+    let value = value.ok_or_else(|| Error::InvalidGdlValue {
+        expected: format!("property `{}`", key),
+        actual: format!("no value on `{}`", variable),
+    })?;
+    V::try_from(MyCypherValue(value))
+}
 
-impl<'gdl, NI, EV> From<&'gdl gdl::Graph> for EdgeList<NI, EV>
+/// Creates an edge list from the given GDL graph.
+///
+/// If `edge_value` is `None`, the value of the single relationship property is
+/// used, or `EV::default()` if a relationship has no properties.
+pub(crate) fn edge_list_from_gdl<NI, EV>(
+    gdl_graph: &gdl::Graph,
+    edge_value: Option<&GdlValue>,
+) -> Result<EdgeList<NI, EV>, Error>
 where
     NI: Idx,
-    EV: From<MyCypherValue<'gdl>> + Default + Send + Sync,
+    EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Send + Sync,
 {
-    fn from(gdl_graph: &'gdl gdl::Graph) -> Self {
-        let edges = gdl_graph
-            .relationships()
-            .into_iter()
-            .map(|r| {
-                let source = gdl_graph.get_node(r.source()).unwrap().id();
-                let target = gdl_graph.get_node(r.target()).unwrap().id();
-
-                let value = if let Some(k) = r.property_keys().next() {
-                    EV::from(MyCypherValue(r.property_value(k).unwrap()))
-                } else {
-                    EV::default()
-                };
+    let types = match edge_value {
+        Some(GdlValue::Label) => relationship_types(gdl_graph),
+        _ => Vec::new(),
+    };
+
+    let edges = gdl_graph
+        .relationships()
+        .map(|r| {
+            let source = gdl_graph.get_node(r.source()).unwrap().id();
+            let target = gdl_graph.get_node(r.target()).unwrap().id();
+
+            let value = match edge_value {
+                Some(GdlValue::Property(key)) => {
+                    property_value(r.property_value(key), key, r.variable())?
+                }
+                Some(GdlValue::Label) => label_value(&types, r.rel_type(), r.variable())?,
+                None => {
+                    let mut keys = r.property_keys();
+                    match (keys.next(), keys.next()) {
+                        (None, _) => EV::default(),
+                        (Some(key), None) => {
+                            property_value(r.property_value(key), key, r.variable())?
+                        }
+                        (Some(_), Some(_)) => {
+                            return Err(Error::InvalidGdlValue {
+                                expected: "single property".to_string(),
+                                actual: format!("multiple properties on `{}`", r.variable()),
+                            })
+                        }
+                    }
+                }
+            };
+
+            Ok((NI::new(source), NI::new(target), value))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
 
-                (NI::new(source), NI::new(target), value)
-            })
-            .collect::<Vec<_>>();
+    let edge_list = match gdl_graph.node_count() {
+        0 => EdgeList::new(edges),
+        n => EdgeList::with_max_node_id(edges, NI::new(n - 1)),
+    };
+
+    Ok(edge_list)
+}
+
+/// Creates node values from the given GDL graph, indexed by node id.
+pub(crate) fn node_values_from_gdl<NV>(
+    gdl_graph: &gdl::Graph,
+    node_value: &GdlValue,
+) -> Result<NodeValues<NV>, Error>
+where
+    NV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error>,
+{
+    let labels = match node_value {
+        GdlValue::Label => node_labels(gdl_graph),
+        GdlValue::Property(_) => Vec::new(),
+    };
 
-        EdgeList::new(edges)
+    let mut nodes = gdl_graph.nodes().collect::<Vec<_>>();
+    nodes.sort_by_key(|node| node.id());
+
+    let values = nodes
+        .into_iter()
+        .map(|node| match node_value {
+            GdlValue::Property(key) => {
+                property_value(node.property_value(key), key, node.variable())
+            }
+            GdlValue::Label => label_value(&labels, node.labels().next(), node.variable()),
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(NodeValues::new(values))
+}
+
+impl<NI, EV> TryFrom<&gdl::Graph> for EdgeList<NI, EV>
+where
+    NI: Idx,
+    EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(gdl_graph: &gdl::Graph) -> Result<Self, Self::Error> {
+        edge_list_from_gdl(gdl_graph, None)
     }
 }
 
@@ -127,42 +300,56 @@ where
     }
 }
 
-impl<'a, NI, EV> From<(&'a gdl::Graph, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
+impl<NI, EV> TryFrom<(&gdl::Graph, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
 where
     NI: Idx,
-    EV: From<MyCypherValue<'a>> + Default + Copy + Send + Sync,
+    EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Copy + Send + Sync,
 {
-    fn from((gdl_graph, csr_layout): (&'a gdl::Graph, CsrLayout)) -> Self {
-        DirectedCsrGraph::from((EdgeList::from(gdl_graph), csr_layout))
+    type Error = Error;
+
+    fn try_from((gdl_graph, csr_layout): (&gdl::Graph, CsrLayout)) -> Result<Self, Self::Error> {
+        Ok(DirectedCsrGraph::from((
+            edge_list_from_gdl(gdl_graph, None)?,
+            csr_layout,
+        )))
     }
 }
 
-impl<NI, EV> From<(gdl::Graph, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
+impl<NI, EV> TryFrom<(gdl::Graph, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
 where
     NI: Idx,
-    for<'a> EV: From<MyCypherValue<'a>> + Default + Copy + Send + Sync,
+    EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Copy + Send + Sync,
 {
-    fn from((gdl_graph, csr_layout): (gdl::Graph, CsrLayout)) -> Self {
-        DirectedCsrGraph::from((EdgeList::from(&gdl_graph), csr_layout))
+    type Error = Error;
+
+    fn try_from((gdl_graph, csr_layout): (gdl::Graph, CsrLayout)) -> Result<Self, Self::Error> {
+        DirectedCsrGraph::try_from((&gdl_graph, csr_layout))
     }
 }
 
-impl<'a, NI, EV> From<(&'a gdl::Graph, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
+impl<NI, EV> TryFrom<(&gdl::Graph, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
 where
     NI: Idx,
-    EV: From<MyCypherValue<'a>> + Default + Copy + Send + Sync,
+    EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Copy + Send + Sync,
 {
-    fn from((gdl_graph, csr_layout): (&'a gdl::Graph, CsrLayout)) -> Self {
-        UndirectedCsrGraph::from((EdgeList::from(gdl_graph), csr_layout))
+    type Error = Error;
+
+    fn try_from((gdl_graph, csr_layout): (&gdl::Graph, CsrLayout)) -> Result<Self, Self::Error> {
+        Ok(UndirectedCsrGraph::from((
+            edge_list_from_gdl(gdl_graph, None)?,
+            csr_layout,
+        )))
     }
 }
 
-impl<NI, EV> From<(gdl::Graph, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
+impl<NI, EV> TryFrom<(gdl::Graph, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
 where
     NI: Idx,
-    for<'a> EV: From<MyCypherValue<'a>> + Default + Copy + Send + Sync,
+    EV: for<'a> TryFrom<MyCypherValue<'a>, Error = Error> + Default + Copy + Send + Sync,
 {
-    fn from((gdl_graph, csr_layout): (gdl::Graph, CsrLayout)) -> Self {
-        UndirectedCsrGraph::from((EdgeList::from(&gdl_graph), csr_layout))
+    type Error = Error;
+
+    fn try_from((gdl_graph, csr_layout): (gdl::Graph, CsrLayout)) -> Result<Self, Self::Error> {
+        UndirectedCsrGraph::try_from((&gdl_graph, csr_layout))
     }
 }
@@ -1,5 +1,5 @@
 use log::info;
-use std::{fs::File, marker::PhantomData, path::Path};
+use std::{borrow::Cow, marker::PhantomData, path::Path};
 
 use crate::prelude::*;
 use rayon::prelude::*;
@@ -30,9 +30,8 @@ where
     type Error = Error;
 
     fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
-        let file = File::open(path.0.as_ref())?;
-        let mmap = unsafe { memmap2::MmapOptions::new().populate().map(&file)? };
-        Graph500::try_from(mmap.as_ref())
+        let bytes = compression::read_binary_bytes(path.0.as_ref())?;
+        Graph500::try_from(bytes.as_ref())
     }
 }
 
@@ -49,10 +48,18 @@ where
         let edge_count = map.len() / std::mem::size_of::<PackedEdge>();
         let node_count = edge_count / 16;
 
-        let map = map.as_ptr();
-        assert_eq!(map as usize % std::mem::align_of::<PackedEdge>(), 0);
-
-        let edges = unsafe { std::slice::from_raw_parts(map as *const PackedEdge, edge_count) };
+        let map = map.as_ptr() as *const PackedEdge;
+
+        // Decompressed inputs are not guaranteed to be aligned.
+        let edges = if map.align_offset(std::mem::align_of::<PackedEdge>()) == 0 {
+            Cow::Borrowed(unsafe { std::slice::from_raw_parts(map, edge_count) })
+        } else {
+            Cow::Owned(
+                (0..edge_count)
+                    .map(|i| unsafe { map.add(i).read_unaligned() })
+                    .collect::<Vec<_>>(),
+            )
+        };
 
         let mut all_edges = Vec::with_capacity(edge_count);
 
@@ -0,0 +1,588 @@
+//! Reads and writes graphs in the [GraphML](http://graphml.graphdrawing.org/)
+//! format.
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use roxmltree::{Document, Node};
+
+use crate::{
+    graph::csr::{CsrLayout, DirectedCsrGraph, NodeValues, UndirectedCsrGraph},
+    index::Idx,
+    input::{
+        compression, directed_edges, undirected_edges, EdgeList, InputCapabilities, InputPath,
+    },
+    DirectedNeighborsWithValues, Error, Graph, NodeValues as NodeValuesTrait,
+    UndirectedNeighborsWithValues,
+};
+
+use super::TextValue;
+
+const NODE_VALUE_KEY: &str = "node_value";
+const EDGE_VALUE_KEY: &str = "edge_value";
+
+/// Reads a graph from a GraphML file.
+///
+/// Nodes are assigned ids in the order in which their `<node>` elements
+/// appear in the document. Node and edge values are read from the `<data>`
+/// elements of the selected keys, where a key can be selected either by its
+/// `id` or its `attr.name`. If an element has no data for the selected key,
+/// the default of the key or `Default::default()` is used.
+///
+/// The direction of an edge is given by its `directed` attribute or else by
+/// the `edgedefault` of its graph, which defaults to `directed`. Reading a
+/// directed edge into an undirected graph, or an undirected edge into a
+/// directed graph, fails with [`Error::InvalidInput`].
+///
+/// # Example
+///
+/// ```ignore
+/// > cat graph.graphml
+/// <graphml>
+///   <key id="d0" for="edge" attr.name="weight" attr.type="double"/>
+///   <graph edgedefault="directed">
+///     <node id="a"/>
+///     <node id="b"/>
+///     <edge source="a" target="b"><data key="d0">0.5</data></edge>
+///   </graph>
+/// </graphml>
+/// ```
+pub struct GraphMlInput<NI: Idx, NV = (), EV = ()> {
+    node_value_key: Option<String>,
+    edge_value_key: Option<String>,
+    _idx: PhantomData<(NI, NV, EV)>,
+}
+
+impl<NI: Idx, NV, EV> Default for GraphMlInput<NI, NV, EV> {
+    fn default() -> Self {
+        Self {
+            node_value_key: None,
+            edge_value_key: None,
+            _idx: PhantomData,
+        }
+    }
+}
+
+impl<NI: Idx, NV, EV> GraphMlInput<NI, NV, EV> {
+    /// Sets the key from which node values are read.
+    pub fn node_value_key(mut self, key: impl Into<String>) -> Self {
+        self.node_value_key = Some(key.into());
+        self
+    }
+
+    /// Sets the key from which edge values are read.
+    pub fn edge_value_key(mut self, key: impl Into<String>) -> Self {
+        self.edge_value_key = Some(key.into());
+        self
+    }
+}
+
+impl<NI: Idx, NV, EV> InputCapabilities<NI> for GraphMlInput<NI, NV, EV> {
+    type GraphInput = GraphMl<NI, NV, EV>;
+
+    fn configure(&self, mut input: Self::GraphInput) -> Self::GraphInput {
+        input.node_value_key = self.node_value_key.clone();
+        input.edge_value_key = self.edge_value_key.clone();
+        input
+    }
+}
+
+/// A GraphML document and the keys to read node and edge values from.
+pub struct GraphMl<NI: Idx, NV, EV> {
+    document: String,
+    node_value_key: Option<String>,
+    edge_value_key: Option<String>,
+    _idx: PhantomData<(NI, NV, EV)>,
+}
+
+impl<NI: Idx, NV, EV> From<String> for GraphMl<NI, NV, EV> {
+    fn from(document: String) -> Self {
+        Self {
+            document,
+            node_value_key: None,
+            edge_value_key: None,
+            _idx: PhantomData,
+        }
+    }
+}
+
+impl<NI, NV, EV, P> TryFrom<InputPath<P>> for GraphMl<NI, NV, EV>
+where
+    P: AsRef<Path>,
+    NI: Idx,
+{
+    type Error = Error;
+
+    fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
+        let bytes = compression::read_bytes(path.0.as_ref())?;
+        let document = std::str::from_utf8(&bytes).map_err(|e| invalid(e.to_string()))?;
+        Ok(GraphMl::from(document.to_string()))
+    }
+}
+
+/// A `<key>` declaration.
+struct Key<'a> {
+    id: &'a str,
+    default: Option<&'a str>,
+}
+
+impl<NI, NV, EV> GraphMl<NI, NV, EV>
+where
+    NI: Idx,
+    NV: TextValue + Default,
+    EV: TextValue + Default + Sync,
+{
+    /// Parses the document into node values and an edge list.
+    ///
+    /// Fails if the direction of any edge does not match `directed`.
+    pub fn parse(&self, directed: bool) -> Result<(NodeValues<NV>, EdgeList<NI, EV>), Error> {
+        let document = Document::parse(&self.document).map_err(|e| invalid(e.to_string()))?;
+
+        let node_key = self
+            .node_value_key
+            .as_deref()
+            .map(|name| find_key(&document, "node", name))
+            .transpose()?;
+        let edge_key = self
+            .edge_value_key
+            .as_deref()
+            .map(|name| find_key(&document, "edge", name))
+            .transpose()?;
+
+        let mut ids = HashMap::new();
+        let mut node_values = Vec::new();
+
+        for node in document.descendants().filter(|n| n.has_tag_name("node")) {
+            let id = attribute(node, "id")?;
+            if ids.insert(id, NI::new(ids.len())).is_some() {
+                return Err(invalid(format!("duplicate node id `{}`", id)));
+            }
+            node_values.push(value(node, node_key.as_ref())?);
+        }
+
+        let node_id = |element: Node, name: &str| {
+            let id = attribute(element, name)?;
+            ids.get(id)
+                .copied()
+                .ok_or_else(|| invalid(format!("unknown node id `{}`", id)))
+        };
+
+        let edges = document
+            .descendants()
+            .filter(|n| n.has_tag_name("edge"))
+            .map(|edge| {
+                if is_directed(edge)? != directed {
+                    return Err(invalid(format!(
+                        "{} edge in {} graph",
+                        direction(!directed),
+                        direction(directed)
+                    )));
+                }
+                let source = node_id(edge, "source")?;
+                let target = node_id(edge, "target")?;
+                Ok((source, target, value(edge, edge_key.as_ref())?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let edge_list = match node_values.len() {
+            0 => EdgeList::new(edges),
+            n => EdgeList::with_max_node_id(edges, NI::new(n - 1)),
+        };
+
+        Ok((NodeValues::new(node_values), edge_list))
+    }
+}
+
+fn find_key<'a>(document: &'a Document, domain: &str, name: &'a str) -> Result<Key<'a>, Error> {
+    document
+        .descendants()
+        .filter(|n| n.has_tag_name("key"))
+        .filter(|key| matches!(key.attribute("for"), Some(d) if d == domain || d == "all"))
+        .find(|key| key.attribute("id") == Some(name) || key.attribute("attr.name") == Some(name))
+        .map(|key| Key {
+            id: key.attribute("id").unwrap_or(name),
+            default: key
+                .children()
+                .find(|n| n.has_tag_name("default"))
+                .and_then(|n| n.text()),
+        })
+        .ok_or_else(|| invalid(format!("no {} key `{}`", domain, name)))
+}
+
+fn attribute<'a>(element: Node<'a, '_>, name: &str) -> Result<&'a str, Error> {
+    element.attribute(name).ok_or_else(|| {
+        invalid(format!(
+            "missing attribute `{}` on <{}>",
+            name,
+            element.tag_name().name()
+        ))
+    })
+}
+
+/// Returns whether an edge is directed, either by its own `directed`
+/// attribute or by the `edgedefault` of the enclosing graph.
+fn is_directed(edge: Node) -> Result<bool, Error> {
+    let edge_default = edge
+        .ancestors()
+        .find(|n| n.has_tag_name("graph"))
+        .and_then(|graph| graph.attribute("edgedefault"));
+
+    match (edge.attribute("directed"), edge_default) {
+        (Some("true"), _) => Ok(true),
+        (Some("false"), _) => Ok(false),
+        (Some(other), _) => Err(invalid(format!(
+            "invalid edge attribute directed=`{}`",
+            other
+        ))),
+        (None, Some("directed") | None) => Ok(true),
+        (None, Some("undirected")) => Ok(false),
+        (None, Some(other)) => Err(invalid(format!("invalid edgedefault `{}`", other))),
+    }
+}
+
+fn direction(directed: bool) -> &'static str {
+    if directed {
+        "directed"
+    } else {
+        "undirected"
+    }
+}
+
+fn value<V: TextValue + Default>(element: Node, key: Option<&Key>) -> Result<V, Error> {
+    let key = match key {
+        Some(key) => key,
+        None => return Ok(V::default()),
+    };
+
+    let text = element
+        .children()
+        .find(|n| n.has_tag_name("data") && n.attribute("key") == Some(key.id))
+        .map(|n| n.text().unwrap_or_default())
+        .or(key.default);
+
+    match text {
+        Some(text) => V::parse_text(text)
+            .ok_or_else(|| invalid(format!("invalid value `{}` for key `{}`", text, key.id))),
+        None => Ok(V::default()),
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidInput {
+        format: String::from("GraphML"),
+        reason: reason.into(),
+    }
+}
+
+impl<NI, NV, EV> TryFrom<(GraphMl<NI, NV, EV>, CsrLayout)> for DirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: TextValue + Default,
+    EV: TextValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(
+        (input, csr_layout): (GraphMl<NI, NV, EV>, CsrLayout),
+    ) -> Result<Self, Self::Error> {
+        let (node_values, edge_list) = input.parse(true)?;
+        Ok(DirectedCsrGraph::from((node_values, edge_list, csr_layout)))
+    }
+}
+
+impl<NI, NV, EV> TryFrom<(GraphMl<NI, NV, EV>, CsrLayout)> for UndirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: TextValue + Default,
+    EV: TextValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(
+        (input, csr_layout): (GraphMl<NI, NV, EV>, CsrLayout),
+    ) -> Result<Self, Self::Error> {
+        let (node_values, edge_list) = input.parse(false)?;
+        Ok(UndirectedCsrGraph::from((
+            node_values,
+            edge_list,
+            csr_layout,
+        )))
+    }
+}
+
+/// Writes a directed graph as GraphML.
+///
+/// Nodes are written with ids `n0`, `n1`, ... Node and edge values are stored
+/// in the keys `node_value` and `edge_value`, unless their type is `()`. The
+/// output can be read again using [`GraphMlInput`].
+///
+/// # Example
+///
+/// ```
+/// use graph_builder::prelude::*;
+/// use graph_builder::input::graphml;
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .edges_with_values(vec![(0, 1, 0.5)])
+///     .build();
+///
+/// let mut output = Vec::new();
+/// graphml::write_directed(&graph, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains(r#"<edge source="n0" target="n1">"#));
+/// ```
+pub fn write_directed<NI, NV, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    NV: TextValue,
+    EV: TextValue + Copy,
+    G: Graph<NI> + NodeValuesTrait<NI, NV> + DirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write(graph, &directed_edges(graph), "directed", output)
+}
+
+/// Writes an undirected graph as GraphML, each edge only once.
+///
+/// See [`write_directed`] for details on the output.
+pub fn write_undirected<NI, NV, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    NV: TextValue,
+    EV: TextValue + Copy,
+    G: Graph<NI> + NodeValuesTrait<NI, NV> + UndirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write(graph, &undirected_edges(graph), "undirected", output)
+}
+
+fn write<NI, NV, EV, G, W>(
+    graph: &G,
+    edges: &[(NI, NI, EV)],
+    edge_default: &str,
+    output: W,
+) -> Result<(), Error>
+where
+    NI: Idx,
+    NV: TextValue,
+    EV: TextValue,
+    G: Graph<NI> + NodeValuesTrait<NI, NV>,
+    W: Write,
+{
+    let mut out = BufWriter::new(output);
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    if let Some(type_name) = NV::TYPE_NAME {
+        writeln!(
+            out,
+            r#"  <key id="{}" for="node" attr.name="value" attr.type="{}"/>"#,
+            NODE_VALUE_KEY, type_name
+        )?;
+    }
+    if let Some(type_name) = EV::TYPE_NAME {
+        writeln!(
+            out,
+            r#"  <key id="{}" for="edge" attr.name="value" attr.type="{}"/>"#,
+            EDGE_VALUE_KEY, type_name
+        )?;
+    }
+    writeln!(out, r#"  <graph id="G" edgedefault="{}">"#, edge_default)?;
+
+    for node in 0..graph.node_count().index() {
+        if NV::TYPE_NAME.is_some() {
+            let value = graph.node_value(NI::new(node)).to_text();
+            writeln!(
+                out,
+                r#"    <node id="n{}"><data key="{}">{}</data></node>"#,
+                node, NODE_VALUE_KEY, value
+            )?;
+        } else {
+            writeln!(out, r#"    <node id="n{}"/>"#, node)?;
+        }
+    }
+
+    for (source, target, value) in edges {
+        let (source, target) = (source.index(), target.index());
+        if EV::TYPE_NAME.is_some() {
+            writeln!(
+                out,
+                r#"    <edge source="n{}" target="n{}"><data key="{}">{}</data></edge>"#,
+                source,
+                target,
+                EDGE_VALUE_KEY,
+                value.to_text()
+            )?;
+        } else {
+            writeln!(
+                out,
+                r#"    <edge source="n{}" target="n{}"/>"#,
+                source, target
+            )?;
+        }
+    }
+
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")?;
+    out.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::GraphBuilder, DirectedDegrees, UndirectedDegrees};
+
+    const GRAPH: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="age" attr.type="int">
+    <default>7</default>
+  </key>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="G" edgedefault="directed">
+    <node id="alice"><data key="d0">42</data></node>
+    <node id="bob"/>
+    <node id="eve"><data key="d0">23</data></node>
+    <edge source="alice" target="bob"><data key="d1">0.5</data></edge>
+    <edge source="bob" target="eve"><data key="d1">1.5</data></edge>
+  </graph>
+</graphml>"#;
+
+    #[test]
+    fn parse_test() {
+        let graph: DirectedCsrGraph<u32, i64, f32> = DirectedCsrGraph::try_from((
+            GraphMlInput::default()
+                .node_value_key("age")
+                .edge_value_key("d1")
+                .configure(GraphMl::from(GRAPH.to_string())),
+            CsrLayout::Sorted,
+        ))
+        .unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(*graph.node_value(0), 42);
+        assert_eq!(*graph.node_value(1), 7);
+        assert_eq!(*graph.node_value(2), 23);
+        assert_eq!(
+            graph.out_neighbors_with_values(1).as_slice(),
+            &[crate::Target::new(2, 1.5)]
+        );
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let result = GraphMl::<u32, (), f32>::from(GRAPH.to_string()).parse(true);
+        assert!(result.is_ok());
+
+        let input = GraphMlInput::<u32, (), f32>::default()
+            .edge_value_key("unknown")
+            .configure(GraphMl::from(GRAPH.to_string()));
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let graph = GRAPH.replace(r#"target="eve""#, r#"target="carl""#);
+        let input = GraphMl::<u32, (), ()>::from(graph);
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let input = GraphMlInput::<u32, u8, ()>::default()
+            .node_value_key("age")
+            .configure(GraphMl::from(GRAPH.replace(">42<", ">-1<")));
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn direction_test() {
+        let input = GraphMl::<u32, (), ()>::from(GRAPH.to_string());
+        assert!(input.parse(true).is_ok());
+        assert!(matches!(
+            input.parse(false),
+            Err(Error::InvalidInput { .. })
+        ));
+
+        let undirected = GRAPH.replace(r#"edgedefault="directed""#, r#"edgedefault="undirected""#);
+        let input = GraphMl::<u32, (), ()>::from(undirected.clone());
+        assert!(input.parse(false).is_ok());
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let mixed = undirected.replace(
+            r#"<edge source="bob""#,
+            r#"<edge directed="true" source="bob""#,
+        );
+        let input = GraphMl::<u32, (), ()>::from(mixed);
+        assert!(matches!(
+            input.parse(false),
+            Err(Error::InvalidInput { .. })
+        ));
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let overridden = undirected.replace("<edge ", r#"<edge directed="true" "#);
+        let input = GraphMl::<u32, (), ()>::from(overridden);
+        assert!(input.parse(true).is_ok());
+    }
+
+    #[test]
+    fn directed_round_trip_test() {
+        let graph: DirectedCsrGraph<u32, f64, u64> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![(0, 1, 4), (0, 2, 5), (2, 2, 6), (3, 0, 7)])
+            .node_values(vec![0.5, 1.5, 2.5, 3.5])
+            .build();
+
+        let mut output = Vec::new();
+        write_directed(&graph, &mut output).unwrap();
+
+        let actual: DirectedCsrGraph<u32, f64, u64> = DirectedCsrGraph::try_from((
+            GraphMlInput::default()
+                .node_value_key("value")
+                .edge_value_key("value")
+                .configure(GraphMl::from(String::from_utf8(output).unwrap())),
+            CsrLayout::Sorted,
+        ))
+        .unwrap();
+
+        assert_eq!(actual.node_count(), graph.node_count());
+        for node in 0..graph.node_count() {
+            assert_eq!(actual.node_value(node), graph.node_value(node));
+            assert_eq!(actual.in_degree(node), graph.in_degree(node));
+            assert_eq!(
+                actual.out_neighbors_with_values(node).as_slice(),
+                graph.out_neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn undirected_round_trip_test() {
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (1, 2), (2, 2), (3, 3)])
+            .build();
+
+        let mut output = Vec::new();
+        write_undirected(&graph, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#"edgedefault="undirected""#));
+        assert_eq!(output.matches("<edge ").count(), 4);
+
+        let actual: UndirectedCsrGraph<u32> =
+            UndirectedCsrGraph::try_from((GraphMl::from(output), CsrLayout::Sorted)).unwrap();
+
+        for node in 0..graph.node_count() {
+            assert_eq!(actual.degree(node), graph.degree(node));
+            assert_eq!(
+                actual.neighbors_with_values(node).as_slice(),
+                graph.neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+}
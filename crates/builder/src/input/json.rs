@@ -0,0 +1,577 @@
+//! Reads and writes graphs in the node-link JSON format, as used by
+//! [NetworkX](https://networkx.org/documentation/stable/reference/readwrite/json_graph.html)
+//! and D3.
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    graph::csr::{CsrLayout, DirectedCsrGraph, NodeValues, UndirectedCsrGraph},
+    index::Idx,
+    input::{
+        compression, directed_edges, undirected_edges, EdgeList, InputCapabilities, InputPath,
+    },
+    DirectedNeighborsWithValues, Error, Graph, NodeValues as NodeValuesTrait,
+    UndirectedNeighborsWithValues,
+};
+
+use super::TextValue;
+
+const VALUE_KEY: &str = "value";
+
+/// Reads a graph from a node-link JSON document.
+///
+/// The document is an object with a `nodes` array and a `links` (or `edges`)
+/// array. Each node is an object with an `id`, each link an object with a
+/// `source` and a `target` that refer to node ids. Ids are compared by their
+/// JSON value, i.e., the number `1` and the string `"1"` are different nodes.
+/// Nodes are assigned ids in the order in which they appear in the `nodes`
+/// array.
+///
+/// Node and edge values are read from the selected object keys. If an object
+/// has no such key, `Default::default()` is used.
+///
+/// If the document has a `directed` attribute, it must match the graph that is
+/// read. Reading a directed document into an undirected graph, or an
+/// undirected document into a directed graph, fails with
+/// [`Error::InvalidInput`].
+///
+/// # Example
+///
+/// ```ignore
+/// > cat graph.json
+/// {
+///   "directed": true,
+///   "nodes": [{ "id": "a" }, { "id": "b" }],
+///   "links": [{ "source": "a", "target": "b", "weight": 0.5 }]
+/// }
+/// ```
+pub struct NodeLinkJsonInput<NI: Idx, NV = (), EV = ()> {
+    node_value_key: Option<String>,
+    edge_value_key: Option<String>,
+    _idx: PhantomData<(NI, NV, EV)>,
+}
+
+impl<NI: Idx, NV, EV> Default for NodeLinkJsonInput<NI, NV, EV> {
+    fn default() -> Self {
+        Self {
+            node_value_key: None,
+            edge_value_key: None,
+            _idx: PhantomData,
+        }
+    }
+}
+
+impl<NI: Idx, NV, EV> NodeLinkJsonInput<NI, NV, EV> {
+    /// Sets the node attribute from which node values are read.
+    pub fn node_value_key(mut self, key: impl Into<String>) -> Self {
+        self.node_value_key = Some(key.into());
+        self
+    }
+
+    /// Sets the link attribute from which edge values are read.
+    pub fn edge_value_key(mut self, key: impl Into<String>) -> Self {
+        self.edge_value_key = Some(key.into());
+        self
+    }
+}
+
+impl<NI: Idx, NV, EV> InputCapabilities<NI> for NodeLinkJsonInput<NI, NV, EV> {
+    type GraphInput = NodeLinkJson<NI, NV, EV>;
+
+    fn configure(&self, mut input: Self::GraphInput) -> Self::GraphInput {
+        input.node_value_key = self.node_value_key.clone();
+        input.edge_value_key = self.edge_value_key.clone();
+        input
+    }
+}
+
+/// A node-link JSON document and the keys to read node and edge values from.
+pub struct NodeLinkJson<NI: Idx, NV, EV> {
+    document: Value,
+    node_value_key: Option<String>,
+    edge_value_key: Option<String>,
+    _idx: PhantomData<(NI, NV, EV)>,
+}
+
+impl<NI: Idx, NV, EV> From<Value> for NodeLinkJson<NI, NV, EV> {
+    fn from(document: Value) -> Self {
+        Self {
+            document,
+            node_value_key: None,
+            edge_value_key: None,
+            _idx: PhantomData,
+        }
+    }
+}
+
+impl<NI, NV, EV, P> TryFrom<InputPath<P>> for NodeLinkJson<NI, NV, EV>
+where
+    P: AsRef<Path>,
+    NI: Idx,
+{
+    type Error = Error;
+
+    fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
+        let bytes = compression::read_bytes(path.0.as_ref())?;
+        let document =
+            serde_json::from_slice::<Value>(&bytes).map_err(|e| invalid(e.to_string()))?;
+        Ok(NodeLinkJson::from(document))
+    }
+}
+
+impl<NI, NV, EV> NodeLinkJson<NI, NV, EV>
+where
+    NI: Idx,
+    NV: TextValue + Default,
+    EV: TextValue + Default + Sync,
+{
+    /// Parses the document into node values and an edge list.
+    ///
+    /// Fails if the `directed` attribute of the document does not match
+    /// `directed`.
+    pub fn parse(&self, directed: bool) -> Result<(NodeValues<NV>, EdgeList<NI, EV>), Error> {
+        if is_directed(&self.document)?.map_or(false, |d| d != directed) {
+            return Err(invalid(format!(
+                "{} document read into {} graph",
+                direction(!directed),
+                direction(directed)
+            )));
+        }
+
+        let nodes = array(&self.document, &["nodes"])?;
+        let links = array(&self.document, &["links", "edges"])?;
+
+        let mut ids = HashMap::with_capacity(nodes.len());
+        let mut node_values = Vec::with_capacity(nodes.len());
+
+        for node in nodes {
+            let id = field(node, "id")?;
+            if ids.insert(id_key(id), NI::new(ids.len())).is_some() {
+                return Err(invalid(format!("duplicate node id `{}`", id)));
+            }
+            node_values.push(value(node, self.node_value_key.as_deref())?);
+        }
+
+        let node_id = |link: &Value, name: &str| {
+            let id = field(link, name)?;
+            ids.get(&id_key(id))
+                .copied()
+                .ok_or_else(|| invalid(format!("unknown node id `{}`", id)))
+        };
+
+        let edges = links
+            .iter()
+            .map(|link| {
+                let source = node_id(link, "source")?;
+                let target = node_id(link, "target")?;
+                Ok((source, target, value(link, self.edge_value_key.as_deref())?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let edge_list = match node_values.len() {
+            0 => EdgeList::new(edges),
+            n => EdgeList::with_max_node_id(edges, NI::new(n - 1)),
+        };
+
+        Ok((NodeValues::new(node_values), edge_list))
+    }
+}
+
+fn array<'a>(document: &'a Value, names: &[&str]) -> Result<&'a Vec<Value>, Error> {
+    names
+        .iter()
+        .find_map(|name| document.get(name))
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid(format!("expected an array `{}`", names[0])))
+}
+
+/// Returns the `directed` attribute of the document, if it has one.
+fn is_directed(document: &Value) -> Result<Option<bool>, Error> {
+    match document.get("directed") {
+        None => Ok(None),
+        Some(Value::Bool(directed)) => Ok(Some(*directed)),
+        Some(other) => Err(invalid(format!("invalid attribute directed=`{}`", other))),
+    }
+}
+
+fn direction(directed: bool) -> &'static str {
+    if directed {
+        "directed"
+    } else {
+        "undirected"
+    }
+}
+
+fn field<'a>(object: &'a Value, name: &str) -> Result<&'a Value, Error> {
+    object
+        .get(name)
+        .ok_or_else(|| invalid(format!("missing attribute `{}` in {}", name, object)))
+}
+
+/// A hashable node id that keeps string ids apart from all other ids.
+#[derive(PartialEq, Eq, Hash)]
+enum IdKey<'a> {
+    String(&'a str),
+    Json(String),
+}
+
+fn id_key(id: &Value) -> IdKey<'_> {
+    match id {
+        Value::String(id) => IdKey::String(id),
+        id => IdKey::Json(id.to_string()),
+    }
+}
+
+fn value<V: TextValue + Default>(object: &Value, key: Option<&str>) -> Result<V, Error> {
+    let value = match key.and_then(|key| object.get(key)) {
+        Some(value) => value,
+        None => return Ok(V::default()),
+    };
+
+    let text = match value {
+        Value::Null => return Ok(V::default()),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    };
+
+    V::parse_text(&text).ok_or_else(|| {
+        invalid(format!(
+            "invalid value `{}` for key `{}`",
+            text,
+            key.unwrap_or_default()
+        ))
+    })
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidInput {
+        format: String::from("node-link JSON"),
+        reason: reason.into(),
+    }
+}
+
+impl<NI, NV, EV> TryFrom<(NodeLinkJson<NI, NV, EV>, CsrLayout)> for DirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: TextValue + Default,
+    EV: TextValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(
+        (input, csr_layout): (NodeLinkJson<NI, NV, EV>, CsrLayout),
+    ) -> Result<Self, Self::Error> {
+        let (node_values, edge_list) = input.parse(true)?;
+        Ok(DirectedCsrGraph::from((node_values, edge_list, csr_layout)))
+    }
+}
+
+impl<NI, NV, EV> TryFrom<(NodeLinkJson<NI, NV, EV>, CsrLayout)> for UndirectedCsrGraph<NI, NV, EV>
+where
+    NI: Idx,
+    NV: TextValue + Default,
+    EV: TextValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from(
+        (input, csr_layout): (NodeLinkJson<NI, NV, EV>, CsrLayout),
+    ) -> Result<Self, Self::Error> {
+        let (node_values, edge_list) = input.parse(false)?;
+        Ok(UndirectedCsrGraph::from((
+            node_values,
+            edge_list,
+            csr_layout,
+        )))
+    }
+}
+
+/// Writes a directed graph as node-link JSON.
+///
+/// Node ids are the indices of the nodes. Node and edge values are stored in
+/// the attribute `value`, unless their type is `()`. The output can be read
+/// again using [`NodeLinkJsonInput`].
+///
+/// # Example
+///
+/// ```
+/// use graph_builder::prelude::*;
+/// use graph_builder::input::json;
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .edges_with_values(vec![(0, 1, 0.5)])
+///     .build();
+///
+/// let mut output = Vec::new();
+/// json::write_directed(&graph, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains(r#"{"source":0,"target":1,"value":0.5}"#));
+/// ```
+pub fn write_directed<NI, NV, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    NV: TextValue,
+    EV: TextValue + Copy,
+    G: Graph<NI> + NodeValuesTrait<NI, NV> + DirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write(graph, &directed_edges(graph), true, output)
+}
+
+/// Writes an undirected graph as node-link JSON, each edge only once.
+///
+/// See [`write_directed`] for details on the output.
+pub fn write_undirected<NI, NV, EV, G, W>(graph: &G, output: W) -> Result<(), Error>
+where
+    NI: Idx,
+    NV: TextValue,
+    EV: TextValue + Copy,
+    G: Graph<NI> + NodeValuesTrait<NI, NV> + UndirectedNeighborsWithValues<NI, EV>,
+    W: Write,
+{
+    write(graph, &undirected_edges(graph), false, output)
+}
+
+fn write<NI, NV, EV, G, W>(
+    graph: &G,
+    edges: &[(NI, NI, EV)],
+    directed: bool,
+    output: W,
+) -> Result<(), Error>
+where
+    NI: Idx,
+    NV: TextValue,
+    EV: TextValue,
+    G: Graph<NI> + NodeValuesTrait<NI, NV>,
+    W: Write,
+{
+    let nodes = (0..graph.node_count().index())
+        .map(|node| {
+            let mut object = Map::new();
+            object.insert(String::from("id"), json!(node));
+            if NV::TYPE_NAME.is_some() {
+                let value = graph.node_value(NI::new(node));
+                object.insert(String::from(VALUE_KEY), to_json(value));
+            }
+            Value::Object(object)
+        })
+        .collect::<Vec<_>>();
+
+    let links = edges
+        .iter()
+        .map(|(source, target, value)| {
+            let mut object = Map::new();
+            object.insert(String::from("source"), json!(source.index()));
+            object.insert(String::from("target"), json!(target.index()));
+            if EV::TYPE_NAME.is_some() {
+                object.insert(String::from(VALUE_KEY), to_json(value));
+            }
+            Value::Object(object)
+        })
+        .collect::<Vec<_>>();
+
+    let document = json!({
+        "directed": directed,
+        "multigraph": true,
+        "graph": {},
+        "nodes": nodes,
+        "links": links,
+    });
+
+    let mut out = BufWriter::new(output);
+    serde_json::to_writer(&mut out, &document).map_err(std::io::Error::from)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Converts a value to a JSON number or boolean, or to a string if its text
+/// is not valid JSON, e.g., for `NaN`.
+fn to_json<V: TextValue>(value: &V) -> Value {
+    let text = value.to_text();
+    serde_json::from_str(&text).unwrap_or(Value::String(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::GraphBuilder, DirectedDegrees, DirectedNeighbors, Target, UndirectedDegrees,
+    };
+
+    fn document() -> Value {
+        json!({
+            "directed": true,
+            "multigraph": false,
+            "graph": {},
+            "nodes": [
+                { "id": "alice", "age": 42 },
+                { "id": "bob" },
+                { "id": 7, "age": "23" }
+            ],
+            "links": [
+                { "source": "alice", "target": "bob", "weight": 0.5 },
+                { "source": "bob", "target": 7, "weight": 1.5 }
+            ]
+        })
+    }
+
+    #[test]
+    fn parse_test() {
+        let graph: DirectedCsrGraph<u32, i64, f32> = DirectedCsrGraph::try_from((
+            NodeLinkJsonInput::default()
+                .node_value_key("age")
+                .edge_value_key("weight")
+                .configure(NodeLinkJson::from(document())),
+            CsrLayout::Sorted,
+        ))
+        .unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(*graph.node_value(0), 42);
+        assert_eq!(*graph.node_value(1), 0);
+        assert_eq!(*graph.node_value(2), 23);
+        assert_eq!(
+            graph.out_neighbors_with_values(1).as_slice(),
+            &[Target::new(2, 1.5)]
+        );
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let mut string_id = document();
+        string_id["links"][1]["target"] = json!("7");
+        let input = NodeLinkJson::<u32, (), ()>::from(string_id);
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let mut document = document();
+        document["links"][0]["target"] = json!("carl");
+        let input = NodeLinkJson::<u32, (), ()>::from(document);
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let input = NodeLinkJson::<u32, (), ()>::from(json!({ "nodes": [] }));
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let input = NodeLinkJsonInput::<u32, u8, ()>::default()
+            .node_value_key("age")
+            .configure(NodeLinkJson::from(document_with_age(-1)));
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn direction_test() {
+        let input = NodeLinkJson::<u32, (), ()>::from(document());
+        assert!(input.parse(true).is_ok());
+        assert!(matches!(
+            input.parse(false),
+            Err(Error::InvalidInput { .. })
+        ));
+
+        let mut undirected = document();
+        undirected["directed"] = json!(false);
+        let input = NodeLinkJson::<u32, (), ()>::from(undirected);
+        assert!(input.parse(false).is_ok());
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+
+        let mut unspecified = document();
+        unspecified.as_object_mut().unwrap().remove("directed");
+        let input = NodeLinkJson::<u32, (), ()>::from(unspecified);
+        assert!(input.parse(true).is_ok());
+        assert!(input.parse(false).is_ok());
+
+        let mut invalid = document();
+        invalid["directed"] = json!("yes");
+        let input = NodeLinkJson::<u32, (), ()>::from(invalid);
+        assert!(matches!(input.parse(true), Err(Error::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn string_and_number_ids_test() {
+        let document = json!({
+            "nodes": [{ "id": 1 }, { "id": "1" }],
+            "links": [{ "source": 1, "target": "1" }, { "source": "1", "target": "1" }]
+        });
+
+        let graph: DirectedCsrGraph<u32> =
+            DirectedCsrGraph::try_from((NodeLinkJson::from(document), CsrLayout::Sorted)).unwrap();
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.out_neighbors(0).as_slice(), &[1]);
+        assert_eq!(graph.out_neighbors(1).as_slice(), &[1]);
+    }
+
+    fn document_with_age(age: i64) -> Value {
+        let mut document = document();
+        document["nodes"][0]["age"] = json!(age);
+        document
+    }
+
+    #[test]
+    fn directed_round_trip_test() {
+        let graph: DirectedCsrGraph<u32, f64, u64> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges_with_values(vec![(0, 1, 4), (0, 2, 5), (2, 2, 6), (3, 0, 7)])
+            .node_values(vec![0.5, 1.5, 2.5, 3.5])
+            .build();
+
+        let mut output = Vec::new();
+        write_directed(&graph, &mut output).unwrap();
+
+        let actual: DirectedCsrGraph<u32, f64, u64> = DirectedCsrGraph::try_from((
+            NodeLinkJsonInput::default()
+                .node_value_key("value")
+                .edge_value_key("value")
+                .configure(NodeLinkJson::from(
+                    serde_json::from_slice::<Value>(&output).unwrap(),
+                )),
+            CsrLayout::Sorted,
+        ))
+        .unwrap();
+
+        assert_eq!(actual.node_count(), graph.node_count());
+        for node in 0..graph.node_count() {
+            assert_eq!(actual.node_value(node), graph.node_value(node));
+            assert_eq!(actual.in_degree(node), graph.in_degree(node));
+            assert_eq!(
+                actual.out_neighbors_with_values(node).as_slice(),
+                graph.out_neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn undirected_round_trip_test() {
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .edges(vec![(0, 1), (1, 2), (2, 2), (3, 3)])
+            .build();
+
+        let mut output = Vec::new();
+        write_undirected(&graph, &mut output).unwrap();
+
+        let document = serde_json::from_slice::<Value>(&output).unwrap();
+        assert_eq!(document["directed"], json!(false));
+        assert_eq!(document["links"].as_array().unwrap().len(), 4);
+
+        let actual: UndirectedCsrGraph<u32> =
+            UndirectedCsrGraph::try_from((NodeLinkJson::from(document), CsrLayout::Sorted))
+                .unwrap();
+
+        for node in 0..graph.node_count() {
+            assert_eq!(actual.degree(node), graph.degree(node));
+            assert_eq!(
+                actual.neighbors_with_values(node).as_slice(),
+                graph.neighbors_with_values(node).as_slice()
+            );
+        }
+    }
+}
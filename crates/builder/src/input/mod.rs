@@ -1,26 +1,63 @@
+pub mod adjacency_graph;
+#[cfg(feature = "arrow")]
+#[doc(cfg(feature = "arrow"))]
+pub mod arrow;
 pub mod binary;
+pub mod compression;
 pub mod dotgraph;
 pub mod edgelist;
 #[cfg(feature = "gdl")]
 #[doc(cfg(feature = "gdl"))]
 pub mod gdl;
 pub mod graph500;
+#[cfg(feature = "graphml")]
+#[doc(cfg(feature = "graphml"))]
+pub mod graphml;
+#[cfg(feature = "json")]
+#[doc(cfg(feature = "json"))]
+pub mod json;
+#[cfg(feature = "parquet")]
+#[doc(cfg(feature = "parquet"))]
+pub mod parquet;
 
+#[cfg(feature = "arrow")]
+#[doc(cfg(feature = "arrow"))]
+pub use self::arrow::ArrowIpcInput;
+#[cfg(feature = "parquet")]
+#[doc(cfg(feature = "parquet"))]
+pub use self::parquet::ParquetInput;
+pub use adjacency_graph::AdjacencyGraph;
+pub use adjacency_graph::AdjacencyGraphInput;
 pub use binary::BinaryInput;
 pub use dotgraph::DotGraph;
 pub use dotgraph::DotGraphInput;
 pub use edgelist::EdgeList;
 pub use edgelist::EdgeListInput;
 pub use edgelist::Edges;
+#[cfg(feature = "gdl")]
+#[doc(cfg(feature = "gdl"))]
+pub use gdl::GdlValue;
 pub use graph500::Graph500;
 pub use graph500::Graph500Input;
+#[cfg(feature = "graphml")]
+#[doc(cfg(feature = "graphml"))]
+pub use graphml::GraphMlInput;
+#[cfg(feature = "json")]
+#[doc(cfg(feature = "json"))]
+pub use json::NodeLinkJsonInput;
 
-use crate::index::Idx;
+use crate::{index::Idx, DirectedNeighborsWithValues, Graph, UndirectedNeighborsWithValues};
 
 pub struct InputPath<P>(pub(crate) P);
 
 pub trait InputCapabilities<NI: Idx> {
     type GraphInput;
+
+    /// Applies the options of the input format to the graph input after it
+    /// has been loaded from a path.
+    fn configure(&self, input: Self::GraphInput) -> Self::GraphInput {
+        input
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -101,3 +138,117 @@ impl_parse_value!(parse_float, f32, f64);
 fn parse_float<T: fast_float::FastFloat>(bytes: &[u8]) -> (T, usize) {
     fast_float::parse_partial(bytes).unwrap()
 }
+
+/// Used by text-based exchange formats, e.g., GraphML, to read and write node
+/// or edge values.
+pub trait TextValue: Sized {
+    /// The GraphML attribute type of the value, or `None` if values of this
+    /// type are not written.
+    const TYPE_NAME: Option<&'static str>;
+
+    /// Parses a value from its textual representation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use graph_builder::input::TextValue;
+    ///
+    /// assert_eq!(f32::parse_text(" 13.37 "), Some(13.37));
+    /// assert_eq!(u32::parse_text("-1"), None);
+    /// ```
+    fn parse_text(text: &str) -> Option<Self>;
+
+    /// Returns the textual representation of the value.
+    fn to_text(&self) -> String;
+}
+
+impl TextValue for () {
+    const TYPE_NAME: Option<&'static str> = None;
+
+    fn parse_text(_text: &str) -> Option<Self> {
+        Some(())
+    }
+
+    fn to_text(&self) -> String {
+        String::new()
+    }
+}
+
+macro_rules! impl_text_value {
+    ($($ty:ty => $type_name:literal),+ $(,)?) => {
+        $(
+            impl $crate::input::TextValue for $ty {
+                const TYPE_NAME: Option<&'static str> = Some($type_name);
+
+                fn parse_text(text: &str) -> Option<Self> {
+                    text.trim().parse().ok()
+                }
+
+                fn to_text(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )+
+    };
+}
+
+impl_text_value!(
+    u8 => "int",
+    u16 => "int",
+    u32 => "long",
+    u64 => "long",
+    usize => "long",
+    i8 => "int",
+    i16 => "int",
+    i32 => "int",
+    i64 => "long",
+    isize => "long",
+    f32 => "float",
+    f64 => "double",
+    bool => "boolean",
+);
+
+/// Collects the outgoing edges of all nodes of a directed graph.
+pub(crate) fn directed_edges<NI, EV, G>(graph: &G) -> Vec<(NI, NI, EV)>
+where
+    NI: Idx,
+    EV: Copy,
+    G: Graph<NI> + DirectedNeighborsWithValues<NI, EV>,
+{
+    let mut edges = Vec::with_capacity(graph.edge_count().index());
+
+    for node in 0..graph.node_count().index() {
+        let node = NI::new(node);
+        for target in graph.out_neighbors_with_values(node) {
+            edges.push((node, target.target, target.value));
+        }
+    }
+
+    edges
+}
+
+/// Collects the edges of an undirected graph, each edge only once.
+pub(crate) fn undirected_edges<NI, EV, G>(graph: &G) -> Vec<(NI, NI, EV)>
+where
+    NI: Idx,
+    EV: Copy,
+    G: Graph<NI> + UndirectedNeighborsWithValues<NI, EV>,
+{
+    let mut edges = Vec::with_capacity(graph.edge_count().index());
+
+    for node in 0..graph.node_count().index() {
+        let node = NI::new(node);
+        // self loops are stored twice in the neighbor list of a node
+        let mut loops = 0;
+        for target in graph.neighbors_with_values(node) {
+            if node < target.target || (node == target.target && loops % 2 == 0) {
+                edges.push((node, target.target, target.value));
+            }
+            if node == target.target {
+                loops += 1;
+            }
+        }
+    }
+
+    edges
+}
@@ -0,0 +1,471 @@
+//! Reads graphs from edge tables stored in one or more Parquet files.
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::ErrorKind,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use log::info;
+use parquet::{
+    arrow::{ArrowReader, ParquetFileArrowReader},
+    file::reader::SerializedFileReader,
+};
+use rayon::prelude::*;
+
+use crate::{
+    graph::csr::{CsrLayout, DirectedCsrGraph, UndirectedCsrGraph},
+    index::Idx,
+    input::{
+        arrow::{ArrowValue, EdgeColumns},
+        edgelist::EdgeList,
+        InputCapabilities, InputPath,
+    },
+    Error,
+};
+
+const BATCH_SIZE: usize = 64 * 1024;
+
+/// Reads a graph from an edge table that is stored in Parquet files.
+///
+/// The path can either point to a single file, to a directory, in which case
+/// all files with the `.parquet` extension are read, or to a file name pattern
+/// within a directory, e.g., `edges/part-*.parquet`. Patterns support `*` and
+/// `?` in the file name. Directories are searched recursively, so partitioned
+/// tables with `key=value` subdirectories, as written by Hive or Spark, can be
+/// read. Files and directories starting with `_` or `.` are ignored. Files are
+/// read in parallel.
+///
+/// Source and target node ids are read from the columns `source` and
+/// `target`, edge values are only read if a value column is set. The id columns
+/// must have an integer type, the value column can have any integer or
+/// floating point type. Values are converted to the node id and edge value
+/// types of the graph. Columns must not contain nulls.
+///
+/// # Example
+///
+/// ```ignore
+/// use graph_builder::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+///     .file_format(ParquetInput::new("src", "dst").value_column("weight"))
+///     .path("edges/part-*.parquet")
+///     .build()
+///     .expect("loading failed");
+/// ```
+pub struct ParquetInput<NI: Idx, EV = ()> {
+    columns: EdgeColumns,
+    _idx: PhantomData<(NI, EV)>,
+}
+
+impl<NI: Idx, EV> Default for ParquetInput<NI, EV> {
+    fn default() -> Self {
+        Self {
+            columns: EdgeColumns::default(),
+            _idx: PhantomData,
+        }
+    }
+}
+
+impl<NI: Idx, EV> ParquetInput<NI, EV> {
+    /// Creates an input that reads node ids from the given columns.
+    pub fn new(source_column: impl Into<String>, target_column: impl Into<String>) -> Self {
+        Self {
+            columns: EdgeColumns::new(source_column.into(), target_column.into()),
+            _idx: PhantomData,
+        }
+    }
+
+    /// Sets the column from which edge values are read.
+    pub fn value_column(mut self, value_column: impl Into<String>) -> Self {
+        self.columns.value = Some(value_column.into());
+        self
+    }
+}
+
+impl<NI: Idx, EV> InputCapabilities<NI> for ParquetInput<NI, EV> {
+    type GraphInput = Parquet<NI, EV>;
+
+    fn configure(&self, mut input: Self::GraphInput) -> Self::GraphInput {
+        input.columns = self.columns.clone();
+        input
+    }
+}
+
+/// A set of Parquet files and the columns to read edges from.
+pub struct Parquet<NI: Idx, EV> {
+    files: Vec<PathBuf>,
+    columns: EdgeColumns,
+    _idx: PhantomData<(NI, EV)>,
+}
+
+impl<NI, EV, P> TryFrom<InputPath<P>> for Parquet<NI, EV>
+where
+    P: AsRef<Path>,
+    NI: Idx,
+{
+    type Error = Error;
+
+    fn try_from(path: InputPath<P>) -> Result<Self, Self::Error> {
+        let path = path.0.as_ref();
+        let files = parquet_files(path)?;
+
+        if files.is_empty() {
+            return Err(Error::IoError {
+                source: std::io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("no parquet files found at {}", path.display()),
+                ),
+            });
+        }
+
+        Ok(Self {
+            files,
+            columns: EdgeColumns::default(),
+            _idx: PhantomData,
+        })
+    }
+}
+
+impl<NI, EV> Parquet<NI, EV>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Default + Sync,
+{
+    /// Returns the files that are read, in lexicographic order.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Reads the selected columns of all files into an edge list.
+    pub fn edge_list(&self) -> Result<EdgeList<NI, EV>, Error> {
+        let start = Instant::now();
+
+        let parts = self
+            .files
+            .par_iter()
+            .map(|file| self.read_file(file))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut edges = Vec::with_capacity(parts.iter().map(Vec::len).sum());
+        parts.into_iter().for_each(|part| edges.extend(part));
+
+        info!(
+            "Read {} edges from {} files in {:?}",
+            edges.len(),
+            self.files.len(),
+            start.elapsed()
+        );
+
+        Ok(EdgeList::new(edges))
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<(NI, NI, EV)>, Error> {
+        let file_reader = SerializedFileReader::new(File::open(path)?)?;
+        let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
+
+        let mut edges = Vec::new();
+
+        for batch in arrow_reader.get_record_reader(BATCH_SIZE)? {
+            self.columns.read_edges(&batch?, &mut edges)?;
+        }
+
+        Ok(edges)
+    }
+}
+
+impl<NI, EV> TryFrom<(Parquet<NI, EV>, CsrLayout)> for DirectedCsrGraph<NI, (), EV>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from((input, csr_layout): (Parquet<NI, EV>, CsrLayout)) -> Result<Self, Self::Error> {
+        Ok(DirectedCsrGraph::from((input.edge_list()?, csr_layout)))
+    }
+}
+
+impl<NI, EV> TryFrom<(Parquet<NI, EV>, CsrLayout)> for UndirectedCsrGraph<NI, (), EV>
+where
+    NI: Idx + ArrowValue,
+    EV: ArrowValue + Default + Copy + Send + Sync,
+{
+    type Error = Error;
+
+    fn try_from((input, csr_layout): (Parquet<NI, EV>, CsrLayout)) -> Result<Self, Self::Error> {
+        Ok(UndirectedCsrGraph::from((input.edge_list()?, csr_layout)))
+    }
+}
+
+/// Resolves the given path to a sorted list of files.
+fn parquet_files(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if path.is_dir() {
+        return list_files(path, |name| name.ends_with(".parquet"));
+    }
+
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(pattern) if pattern.contains(['*', '?']) => {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            list_files(dir, |name| {
+                matches_pattern(pattern.as_bytes(), name.as_bytes())
+            })
+        }
+        _ => Ok(vec![path.to_path_buf()]),
+    }
+}
+
+fn list_files(dir: &Path, filter: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    collect_files(dir, &filter, &mut files)?;
+    files.sort_unstable();
+    Ok(files)
+}
+
+/// Collects matching files from the directory and all of its subdirectories,
+/// e.g., the `key=value` directories of a partitioned table. Entries starting
+/// with `_` or `.`, such as `_SUCCESS` or `_temporary`, are skipped.
+fn collect_files(
+    dir: &Path,
+    filter: &impl Fn(&str) -> bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) if !name.starts_with(['_', '.']) => name,
+            _ => continue,
+        };
+        if path.is_dir() {
+            collect_files(&path, filter, files)?;
+        } else if path.is_file() && filter(name) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Matches a file name against a pattern, where `*` matches any sequence of
+/// characters and `?` matches a single character.
+fn matches_pattern(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` in the pattern and the name position it was
+    // tried at, to resume from when the rest of the pattern does not match.
+    let mut star = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Float64Array, Int64Array},
+        datatypes::{DataType, Field, Schema},
+        record_batch::RecordBatch,
+    };
+    use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+
+    use super::*;
+    use crate::{builder::GraphBuilder, DirectedNeighborsWithValues, Graph};
+
+    fn write_part(path: &Path, edges: &[(i64, i64, f64)]) {
+        write_compressed_part(path, edges, Compression::UNCOMPRESSED);
+    }
+
+    fn write_compressed_part(path: &Path, edges: &[(i64, i64, f64)], compression: Compression) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("src", DataType::Int64, false),
+            Field::new("dst", DataType::Int64, false),
+            Field::new("weight", DataType::Float64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(
+                    edges.iter().map(|e| e.0).collect::<Vec<_>>(),
+                )),
+                Arc::new(Int64Array::from(
+                    edges.iter().map(|e| e.1).collect::<Vec<_>>(),
+                )),
+                Arc::new(Float64Array::from(
+                    edges.iter().map(|e| e.2).collect::<Vec<_>>(),
+                )),
+            ],
+        )
+        .unwrap();
+
+        let props = WriterProperties::builder()
+            .set_compression(compression)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(path).unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    fn write_parts() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write_part(
+            &dir.path().join("part-0.parquet"),
+            &[(0, 1, 0.5), (0, 2, 1.5)],
+        );
+        write_part(
+            &dir.path().join("part-1.parquet"),
+            &[(1, 2, 2.5), (2, 3, 3.5)],
+        );
+        write_part(&dir.path().join("other.parquet"), &[(3, 4, 4.5)]);
+        dir
+    }
+
+    #[test]
+    fn read_directory_test() {
+        let dir = write_parts();
+
+        let graph: DirectedCsrGraph<u32, (), f32> = GraphBuilder::new()
+            .csr_layout(CsrLayout::Sorted)
+            .file_format(ParquetInput::new("src", "dst").value_column("weight"))
+            .path(dir.path())
+            .build()
+            .unwrap();
+
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 5);
+
+        let values = graph
+            .out_neighbors_with_values(0)
+            .map(|t| (t.target, t.value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(values, vec![(1, 0.5), (2, 1.5)]);
+    }
+
+    #[test]
+    fn read_pattern_test() {
+        let dir = write_parts();
+
+        let input =
+            Parquet::<usize, ()>::try_from(InputPath(dir.path().join("part-*.parquet"))).unwrap();
+
+        assert_eq!(
+            input.files(),
+            &[
+                dir.path().join("part-0.parquet"),
+                dir.path().join("part-1.parquet")
+            ]
+        );
+
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .file_format(ParquetInput::new("src", "dst"))
+            .path(dir.path().join("part-*.parquet"))
+            .build()
+            .unwrap();
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+    }
+
+    #[test]
+    fn read_partitioned_directory_test() {
+        let dir = tempfile::tempdir().unwrap();
+        for (year, edges) in [(2021, [(0, 1, 0.5)]), (2022, [(1, 2, 1.5)])] {
+            let partition = dir.path().join(format!("year={}", year));
+            std::fs::create_dir(&partition).unwrap();
+            write_part(&partition.join("part-0.parquet"), &edges);
+        }
+        std::fs::File::create(dir.path().join("_SUCCESS")).unwrap();
+
+        let input = Parquet::<usize, ()>::try_from(InputPath(dir.path())).unwrap();
+
+        assert_eq!(
+            input.files(),
+            &[
+                dir.path().join("year=2021").join("part-0.parquet"),
+                dir.path().join("year=2022").join("part-0.parquet")
+            ]
+        );
+
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .file_format(ParquetInput::new("src", "dst"))
+            .path(dir.path())
+            .build()
+            .unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn read_compressed_test() {
+        let dir = tempfile::tempdir().unwrap();
+        for (i, compression) in [Compression::SNAPPY, Compression::ZSTD, Compression::GZIP]
+            .into_iter()
+            .enumerate()
+        {
+            write_compressed_part(
+                &dir.path().join(format!("part-{}.parquet", i)),
+                &[(i as i64, i as i64 + 1, 1.0)],
+                compression,
+            );
+        }
+
+        let graph: DirectedCsrGraph<usize> = GraphBuilder::new()
+            .file_format(ParquetInput::new("src", "dst"))
+            .path(dir.path())
+            .build()
+            .unwrap();
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn no_files_test() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = Parquet::<usize, ()>::try_from(InputPath(dir.path()));
+
+        assert!(matches!(result, Err(Error::IoError { .. })));
+    }
+
+    #[test]
+    fn matches_pattern_test() {
+        assert!(matches_pattern(b"part-*.parquet", b"part-0.parquet"));
+        assert!(matches_pattern(b"part-*.parquet", b"part-.parquet"));
+        assert!(matches_pattern(b"part-?.parquet", b"part-1.parquet"));
+        assert!(matches_pattern(b"*", b"anything"));
+        assert!(!matches_pattern(b"part-?.parquet", b"part-10.parquet"));
+        assert!(!matches_pattern(b"part-*.parquet", b"other.parquet"));
+        assert!(matches_pattern(b"*a*b*c", b"xxaxxbxxbxxc"));
+        assert!(matches_pattern(b"**", b""));
+        assert!(!matches_pattern(b"?", b""));
+        assert!(!matches_pattern(b"*a*a*a*a*a*a*b", &[b'a'; 64]));
+    }
+}
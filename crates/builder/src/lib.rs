@@ -174,12 +174,17 @@ pub mod graph;
 pub mod graph_ops;
 pub mod index;
 pub mod input;
+pub mod memory;
 pub mod prelude;
 
 pub use crate::builder::GraphBuilder;
+pub use crate::graph::bipartite::BipartiteCsrGraph;
+pub use crate::graph::bipartite::Partition;
 pub use crate::graph::csr::CsrLayout;
 pub use crate::graph::csr::DirectedCsrGraph;
 pub use crate::graph::csr::UndirectedCsrGraph;
+pub use crate::graph::sharded::ShardedCsrGraph;
+pub use crate::graph::temporal::TemporalCsrGraph;
 
 use std::convert::Infallible;
 
@@ -206,6 +211,30 @@ pub enum Error {
         #[from]
         source: gdl::graph::GraphHandlerError,
     },
+    #[cfg(feature = "gdl")]
+    #[doc(cfg(feature = "gdl"))]
+    #[error("invalid GDL value, expected {expected}, got {actual}")]
+    InvalidGdlValue { expected: String, actual: String },
+    #[cfg(feature = "arrow")]
+    #[doc(cfg(feature = "arrow"))]
+    #[error("error while reading Arrow input")]
+    ArrowError {
+        #[from]
+        source: arrow::error::ArrowError,
+    },
+    #[cfg(feature = "arrow")]
+    #[doc(cfg(feature = "arrow"))]
+    #[error("invalid Arrow column {column}, {reason}")]
+    InvalidArrowColumn { column: String, reason: String },
+    #[cfg(feature = "parquet")]
+    #[doc(cfg(feature = "parquet"))]
+    #[error("error while reading Parquet input")]
+    ParquetError {
+        #[from]
+        source: parquet::errors::ParquetError,
+    },
+    #[error("invalid {format} input, {reason}")]
+    InvalidInput { format: String, reason: String },
     #[error("invalid partitioning")]
     InvalidPartitioning,
     #[error("number of node values must be the same as node count")]
@@ -324,6 +353,129 @@ pub trait DirectedNeighborsWithValues<NI: Idx, EV> {
     fn in_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_>;
 }
 
+/// Returns the neighbors of a given node including the id of the connecting edge.
+///
+/// The edge id is the position of the edge within the target array of the
+/// underlying CSR. Since each undirected edge is stored once for each of its
+/// end nodes, edge ids are in the range `0..2 * edge_count`. Use
+/// [`crate::graph_ops::UndirectedEdgeIdMappingOp`] to find the id of the
+/// opposite edge.
+pub trait UndirectedNeighborsWithEdgeIds<NI: Idx, EV> {
+    type NeighborsIterator<'a>: Iterator<Item = (NI, &'a Target<NI, EV>)>
+    where
+        Self: 'a,
+        EV: 'a;
+
+    /// Returns an iterator of all nodes connected to the given node
+    /// including the id and the value of the connecting edge.
+    fn neighbors_with_edge_ids(&self, node: NI) -> Self::NeighborsIterator<'_>;
+}
+
+/// Returns the neighbors of a given node either in outgoing or incoming
+/// direction including the id of the connecting edge.
+///
+/// The edge id is the position of the edge within the target array of the
+/// outgoing or incoming CSR respectively. Both are in the range
+/// `0..edge_count`, but the same edge usually has a different id in each
+/// direction. Use [`crate::graph_ops::DirectedEdgeIdMappingOp`] to translate
+/// between them.
+pub trait DirectedNeighborsWithEdgeIds<NI: Idx, EV> {
+    type NeighborsIterator<'a>: Iterator<Item = (NI, &'a Target<NI, EV>)>
+    where
+        Self: 'a,
+        EV: 'a;
+
+    /// Returns an iterator of all nodes which are connected in outgoing direction
+    /// to the given node. For each connected node, the id of the connecting
+    /// edge within the outgoing CSR and its value are also returned.
+    fn out_neighbors_with_edge_ids(&self, node: NI) -> Self::NeighborsIterator<'_>;
+
+    /// Returns an iterator of all nodes which are connected in incoming direction
+    /// to the given node. For each connected node, the id of the connecting
+    /// edge within the incoming CSR and its value are also returned.
+    fn in_neighbors_with_edge_ids(&self, node: NI) -> Self::NeighborsIterator<'_>;
+}
+
+/// A bipartite graph is a tuple `(L, R, E)`, where `L` and `R` are disjoint
+/// sets of nodes and `E` a set of edges. Each edge connects exactly one node
+/// in `L` with exactly one node in `R`.
+///
+/// Nodes in `L` are called *left* nodes, nodes in `R` are called *right*
+/// nodes. Both partitions use their own id space, i.e., left nodes are
+/// identified by `0..left_node_count` and right nodes by `0..right_node_count`.
+pub trait BipartiteGraph<NI: Idx> {
+    /// Returns the number of nodes in the left partition.
+    fn left_node_count(&self) -> NI;
+
+    /// Returns the number of nodes in the right partition.
+    fn right_node_count(&self) -> NI;
+
+    /// Returns the number of edges in the graph.
+    fn edge_count(&self) -> NI;
+}
+
+pub trait BipartiteDegrees<NI: Idx> {
+    /// Returns the number of edges connected to the given left node.
+    fn left_degree(&self, node: NI) -> NI;
+
+    /// Returns the number of edges connected to the given right node.
+    fn right_degree(&self, node: NI) -> NI;
+}
+
+/// Returns the neighbors of a given node in either partition.
+///
+/// The neighbors of a left node are right nodes and vice versa.
+pub trait BipartiteNeighbors<NI: Idx> {
+    type NeighborsIterator<'a>: Iterator<Item = &'a NI>
+    where
+        Self: 'a;
+
+    /// Returns an iterator of all right nodes connected to the given left node.
+    fn left_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_>;
+
+    /// Returns an iterator of all left nodes connected to the given right node.
+    fn right_neighbors(&self, node: NI) -> Self::NeighborsIterator<'_>;
+}
+
+/// Returns the neighbors of a given node in either partition.
+///
+/// The neighbors of a left node are right nodes and vice versa.
+pub trait BipartiteNeighborsWithValues<NI: Idx, EV> {
+    type NeighborsIterator<'a>: Iterator<Item = &'a Target<NI, EV>>
+    where
+        Self: 'a,
+        EV: 'a;
+
+    /// Returns an iterator of all right nodes connected to the given left node
+    /// including the value of the connecting edge.
+    fn left_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_>;
+
+    /// Returns an iterator of all left nodes connected to the given right node
+    /// including the value of the connecting edge.
+    fn right_neighbors_with_values(&self, node: NI) -> Self::NeighborsIterator<'_>;
+}
+
+/// Returns the neighbors of a given node that are connected via an edge with
+/// a timestamp in the given time window.
+///
+/// Time windows are half-open, i.e., an edge with timestamp `t` is part of the
+/// window `[start, end)` if `start <= t < end`.
+pub trait TemporalNeighbors<NI: Idx, T> {
+    type NeighborsIterator<'a>: Iterator<Item = &'a NI>
+    where
+        Self: 'a;
+
+    /// Returns an iterator of all nodes which are connected in outgoing
+    /// direction to the given node via an edge with a timestamp in
+    /// `[start, end)`. Neighbors are returned in timestamp order.
+    fn out_neighbors_between(&self, node: NI, start: T, end: T) -> Self::NeighborsIterator<'_>;
+
+    /// Returns an iterator of all nodes which are connected in incoming
+    /// direction to the given node via an edge with a timestamp in
+    /// `[start, end)`. Neighbors are returned in timestamp order.
+    fn in_neighbors_between(&self, node: NI, start: T, end: T) -> Self::NeighborsIterator<'_>;
+}
+
 #[repr(transparent)]
 pub struct SharedMut<T>(*mut T);
 unsafe impl<T: Send> Send for SharedMut<T> {}
@@ -0,0 +1,243 @@
+//! Heap memory accounting for graphs and their building blocks.
+//!
+//! All types that own large allocations implement [`MemoryUsage`] which
+//! reports the number of heap bytes they occupy, split by the purpose of the
+//! allocation. [`estimate_memory_usage`] predicts the memory of a graph and
+//! the edge list it is built from before any data is loaded.
+
+use std::{
+    iter::Sum,
+    mem::size_of,
+    ops::{Add, AddAssign},
+};
+
+use crate::{graph::csr::Target, index::Idx, input::Direction};
+
+/// The number of heap bytes used by a data structure.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryFootprint {
+    /// Bytes used by CSR offset arrays.
+    pub offsets: usize,
+    /// Bytes used by node ids stored as edge targets, or edge sources and
+    /// targets in case of edge lists.
+    pub targets: usize,
+    /// Bytes used by edge values, including padding within the target arrays.
+    pub edge_values: usize,
+    /// Bytes used by node values.
+    pub node_values: usize,
+    /// Bytes used by any other structure, e.g., timestamps or ghost tables.
+    pub auxiliary: usize,
+}
+
+impl MemoryFootprint {
+    /// Returns the total number of bytes.
+    pub fn total(&self) -> usize {
+        self.offsets + self.targets + self.edge_values + self.node_values + self.auxiliary
+    }
+
+    pub(crate) fn of_offsets<NI>(len: usize) -> Self {
+        Self {
+            offsets: len * size_of::<NI>(),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn of_targets<NI, EV>(len: usize) -> Self {
+        Self {
+            targets: len * size_of::<NI>(),
+            edge_values: len * (size_of::<Target<NI, EV>>() - size_of::<NI>()),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn of_node_values<NV>(len: usize) -> Self {
+        Self {
+            node_values: len * size_of::<NV>(),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn of_auxiliary<T>(len: usize) -> Self {
+        Self {
+            auxiliary: len * size_of::<T>(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Add for MemoryFootprint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            offsets: self.offsets + rhs.offsets,
+            targets: self.targets + rhs.targets,
+            edge_values: self.edge_values + rhs.edge_values,
+            node_values: self.node_values + rhs.node_values,
+            auxiliary: self.auxiliary + rhs.auxiliary,
+        }
+    }
+}
+
+impl AddAssign for MemoryFootprint {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for MemoryFootprint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Reports the heap memory used by a data structure.
+///
+/// # Example
+///
+/// ```
+/// use graph_builder::prelude::*;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2), (1, 2)])
+///     .build();
+///
+/// let usage = graph.memory_usage();
+///
+/// // two offset arrays with node_count + 1 entries each
+/// assert_eq!(usage.offsets, 2 * 4 * 4);
+/// // two target arrays with edge_count entries each
+/// assert_eq!(usage.targets, 2 * 3 * 4);
+/// assert_eq!(usage.edge_values, 0);
+/// assert_eq!(usage.total(), 56);
+/// ```
+pub trait MemoryUsage {
+    /// Returns the number of heap bytes used by `self`.
+    fn memory_usage(&self) -> MemoryFootprint;
+}
+
+/// Estimates the heap memory of a graph with the given number of nodes and
+/// edges together with the edge list it is built from.
+///
+/// If `direction` is [`Direction::Undirected`], the estimate is computed for
+/// an [`crate::UndirectedCsrGraph`], which stores each edge twice in a single
+/// CSR. Otherwise, the estimate is computed for a
+/// [`crate::DirectedCsrGraph`], which stores each edge once in an outgoing and
+/// once in an incoming CSR. Node values are assumed to be `()`.
+///
+/// The edge list that is held in memory during construction is reported as
+/// auxiliary memory. Subtract it from the total to get the memory required by
+/// the resulting graph.
+///
+/// This is not the peak memory of the construction. Temporary buffers that
+/// exist at the same time, e.g., the degree and offset arrays used to compute
+/// the prefix sum and the unsorted target arrays before sorting and
+/// deduplication, are not included.
+///
+/// # Example
+///
+/// ```
+/// use graph_builder::prelude::*;
+/// use graph_builder::memory::estimate_memory_usage;
+///
+/// let graph: DirectedCsrGraph<u32> = GraphBuilder::new()
+///     .edges(vec![(0, 1), (0, 2), (1, 2)])
+///     .build();
+///
+/// let estimate = estimate_memory_usage::<u32, ()>(3, 3, Direction::Outgoing);
+///
+/// assert_eq!(estimate.auxiliary, 3 * 2 * 4);
+/// assert_eq!(estimate.total() - estimate.auxiliary, graph.memory_usage().total());
+/// ```
+pub fn estimate_memory_usage<NI: Idx, EV>(
+    node_count: usize,
+    edge_count: usize,
+    direction: Direction,
+) -> MemoryFootprint {
+    let csr = |edge_count: usize| {
+        MemoryFootprint::of_offsets::<NI>(node_count + 1)
+            + MemoryFootprint::of_targets::<NI, EV>(edge_count)
+    };
+
+    let graph = match direction {
+        Direction::Undirected => csr(2 * edge_count),
+        Direction::Outgoing | Direction::Incoming => csr(edge_count) + csr(edge_count),
+    };
+
+    let edge_list = MemoryFootprint::of_auxiliary::<(NI, NI, EV)>(edge_count);
+
+    graph + MemoryFootprint::of_node_values::<()>(node_count) + edge_list
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        builder::GraphBuilder, graph::csr::UndirectedCsrGraph, input::edgelist::EdgeList,
+        DirectedCsrGraph,
+    };
+
+    use super::*;
+
+    #[test]
+    fn footprint_add_test() {
+        let a = MemoryFootprint {
+            offsets: 1,
+            targets: 2,
+            edge_values: 3,
+            node_values: 4,
+            auxiliary: 5,
+        };
+
+        let sum = vec![a, a, a].into_iter().sum::<MemoryFootprint>();
+
+        assert_eq!(sum.total(), 45);
+        assert_eq!(sum.edge_values, 9);
+    }
+
+    #[test]
+    fn target_padding_test() {
+        let usage = MemoryFootprint::of_targets::<u32, u64>(10);
+
+        assert_eq!(usage.targets, 40);
+        assert_eq!(usage.edge_values, 120);
+    }
+
+    #[test]
+    fn estimate_directed_test() {
+        let edges = vec![(0, 1, 0.5), (0, 2, 0.5), (1, 2, 0.5), (2, 3, 0.5)];
+        let edge_list = EdgeList::new(edges.clone());
+
+        let graph: DirectedCsrGraph<usize, (), f32> =
+            GraphBuilder::new().edges_with_values(edges).build();
+
+        let estimate = estimate_memory_usage::<usize, f32>(4, 4, Direction::Outgoing);
+
+        assert_eq!(estimate.auxiliary, edge_list.memory_usage().total());
+        assert_eq!(
+            MemoryFootprint {
+                auxiliary: 0,
+                ..estimate
+            },
+            graph.memory_usage()
+        );
+    }
+
+    #[test]
+    fn estimate_undirected_test() {
+        let edges = vec![(0, 1), (0, 2), (1, 2), (2, 3)];
+
+        let graph: UndirectedCsrGraph<u32> = GraphBuilder::new().edges(edges).build();
+
+        let estimate = estimate_memory_usage::<u32, ()>(4, 4, Direction::Undirected);
+
+        assert_eq!(estimate.offsets, 5 * 4);
+        assert_eq!(estimate.targets, 8 * 4);
+        assert_eq!(
+            MemoryFootprint {
+                auxiliary: 0,
+                ..estimate
+            },
+            graph.memory_usage()
+        );
+    }
+}
@@ -1,32 +1,55 @@
 pub use crate::builder::GraphBuilder;
 
+pub use crate::graph::bipartite::BipartiteCsrGraph;
+pub use crate::graph::bipartite::Partition;
+
 pub use crate::graph::csr::CsrLayout;
 pub use crate::graph::csr::DirectedCsrGraph;
 pub use crate::graph::csr::Target;
 pub use crate::graph::csr::UndirectedCsrGraph;
 
+pub use crate::graph::sharded::GraphShard;
+pub use crate::graph::sharded::ShardedCsrGraph;
+
+pub use crate::graph::temporal::TemporalCsrGraph;
+pub use crate::graph::temporal::TemporalSnapshot;
+
 pub use crate::graph_ops::DegreePartitionOp;
 pub use crate::graph_ops::DeserializeGraphOp;
+pub use crate::graph_ops::DirectedEdgeIdMappingOp;
 pub use crate::graph_ops::ForEachNodeParallelByPartitionOp;
 pub use crate::graph_ops::ForEachNodeParallelOp;
 pub use crate::graph_ops::InDegreePartitionOp;
 pub use crate::graph_ops::OutDegreePartitionOp;
 pub use crate::graph_ops::RelabelByDegreeOp;
 pub use crate::graph_ops::SerializeGraphOp;
+pub use crate::graph_ops::ToDirectedOp;
+pub use crate::graph_ops::ToShardedOp;
 pub use crate::graph_ops::ToUndirectedOp;
+pub use crate::graph_ops::UndirectedEdgeIdMappingOp;
+
+pub use crate::memory::MemoryFootprint;
+pub use crate::memory::MemoryUsage;
 
 pub use crate::index::Idx;
 pub use atomic::Atomic;
 
 pub use crate::input::*;
 
+pub use crate::BipartiteDegrees;
+pub use crate::BipartiteGraph;
+pub use crate::BipartiteNeighbors;
+pub use crate::BipartiteNeighborsWithValues;
 pub use crate::DirectedDegrees;
 pub use crate::DirectedNeighbors;
+pub use crate::DirectedNeighborsWithEdgeIds;
 pub use crate::DirectedNeighborsWithValues;
 pub use crate::Graph;
 pub use crate::NodeValues;
+pub use crate::TemporalNeighbors;
 pub use crate::UndirectedDegrees;
 pub use crate::UndirectedNeighbors;
+pub use crate::UndirectedNeighborsWithEdgeIds;
 pub use crate::UndirectedNeighborsWithValues;
 
 pub use crate::Error;
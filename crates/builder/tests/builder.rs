@@ -196,6 +196,120 @@ fn directed_usize_graph_from_gdl_with_i64_edge_values() {
     assert_eq!(actual, &[Target::new(1, 42), Target::new(2, 43)]);
 }
 
+#[cfg(feature = "gdl")]
+#[test]
+fn directed_usize_graph_from_gdl_with_selected_edge_property() {
+    let g: DirectedCsrGraph<usize, (), f64> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .gdl_str::<usize, _>(
+            "(n0)-[{ cost: 0.1, id: 1 }]->(n1),
+                 (n0)-[{ cost: 0.2, id: 2 }]->(n2)",
+        )
+        .edge_values(GdlValue::property("cost"))
+        .build()
+        .unwrap();
+
+    let actual = g.out_neighbors_with_values(0).as_slice();
+
+    assert_eq!(actual, &[Target::new(1, 0.1), Target::new(2, 0.2)]);
+}
+
+#[cfg(feature = "gdl")]
+#[test]
+fn directed_usize_graph_from_gdl_with_node_values() {
+    let g: DirectedCsrGraph<usize, f32, i64> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .gdl_str::<usize, _>(
+            "(n0 { score: 0.5 }),(n1 { score: 1.5 }),(n2 { score: 2.5 }),
+                 (n0)-[{ w: 1 }]->(n1),
+                 (n0)-[{ w: 2 }]->(n2)",
+        )
+        .node_values(GdlValue::property("score"))
+        .edge_values(GdlValue::property("w"))
+        .build()
+        .unwrap();
+
+    assert_eq!(*g.node_value(0), 0.5);
+    assert_eq!(*g.node_value(1), 1.5);
+    assert_eq!(*g.node_value(2), 2.5);
+
+    let actual = g.out_neighbors_with_values(0).as_slice();
+
+    assert_eq!(actual, &[Target::new(1, 1), Target::new(2, 2)]);
+}
+
+#[cfg(feature = "gdl")]
+#[test]
+fn directed_usize_graph_from_gdl_with_labels_and_types() {
+    let gdl = "(n0:Person),(n1:City),(n2:Person),(n3:Country),
+                 (n0)-[:LIVES_IN]->(n1),
+                 (n0)-[:KNOWS]->(n2),
+                 (n1)-[:LOCATED_IN]->(n3)";
+
+    let g: DirectedCsrGraph<usize, usize, u32> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .gdl_str::<usize, _>(gdl)
+        .node_values(GdlValue::Label)
+        .edge_values(GdlValue::Label)
+        .build()
+        .unwrap();
+
+    let gdl_graph = gdl.parse::<::gdl::Graph>().unwrap();
+    let labels = gdl::node_labels(&gdl_graph);
+    let types = gdl::relationship_types(&gdl_graph);
+
+    assert_eq!(labels, vec!["City", "Country", "Person"]);
+    assert_eq!(types, vec!["KNOWS", "LIVES_IN", "LOCATED_IN"]);
+
+    for var in ["n0", "n1", "n2", "n3"] {
+        let node = gdl_graph.get_node(var).unwrap();
+        let label = node.labels().next().unwrap();
+        assert_eq!(labels[*g.node_value(node.id())], label);
+    }
+
+    let n0 = gdl_graph.get_node("n0").unwrap().id();
+    let n1 = gdl_graph.get_node("n1").unwrap().id();
+    let n2 = gdl_graph.get_node("n2").unwrap().id();
+
+    let mut actual = g.out_neighbors_with_values(n0).as_slice().to_vec();
+    actual.sort_by_key(|t| t.target);
+
+    let mut expected = vec![Target::new(n1, 1), Target::new(n2, 0)];
+    expected.sort_by_key(|t| t.target);
+
+    assert_eq!(actual, expected);
+}
+
+#[cfg(feature = "gdl")]
+#[test]
+fn directed_usize_graph_from_gdl_with_invalid_values() {
+    let result: Result<DirectedCsrGraph<usize, (), f32>, _> = GraphBuilder::new()
+        .gdl_str::<usize, _>("(n0)-[{ f: 42 }]->(n1)")
+        .build();
+
+    assert!(matches!(result, Err(Error::InvalidGdlValue { .. })));
+
+    let result: Result<DirectedCsrGraph<usize, (), f32>, _> = GraphBuilder::new()
+        .gdl_str::<usize, _>("(n0)-[{ f: 0.5 }]->(n1)")
+        .edge_values(GdlValue::property("g"))
+        .build();
+
+    assert!(matches!(result, Err(Error::InvalidGdlValue { .. })));
+
+    let result: Result<DirectedCsrGraph<usize, i64>, _> = GraphBuilder::new()
+        .gdl_str::<usize, _>("(n0 { age: 42 })-->(n1)")
+        .node_values(GdlValue::property("age"))
+        .build();
+
+    assert!(matches!(result, Err(Error::InvalidGdlValue { .. })));
+
+    let result: Result<DirectedCsrGraph<usize, (), u32>, _> = GraphBuilder::new()
+        .gdl_str::<usize, _>("(n0)-[{ f: -1 }]->(n1)")
+        .build();
+
+    assert!(matches!(result, Err(Error::InvalidGdlValue { .. })));
+}
+
 #[cfg(feature = "gdl")]
 #[test]
 fn undirected_usize_graph_from_gdl() {
@@ -343,6 +457,98 @@ fn directed_u32_graph_from_dot_graph_file() {
     assert_directed_graph::<u32, u32>(graph);
 }
 
+#[cfg(feature = "compression")]
+#[test]
+fn directed_usize_graph_from_gzip_edge_list_file() {
+    let path = [env!("CARGO_MANIFEST_DIR"), "resources", "test.el.gz"]
+        .iter()
+        .collect::<PathBuf>();
+
+    let graph = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .file_format(EdgeListInput::default())
+        .path(path)
+        .build()
+        .expect("loading failed");
+
+    assert_directed_graph::<usize, ()>(graph);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn directed_u32_graph_from_zstd_dot_graph_file() {
+    let path = [env!("CARGO_MANIFEST_DIR"), "resources", "test.graph.zst"]
+        .iter()
+        .collect::<PathBuf>();
+
+    let graph: DirectedCsrGraph<u32, u32, ()> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .file_format(DotGraphInput::<u32, u32>::default())
+        .path(path)
+        .build()
+        .expect("loading failed");
+
+    assert_eq!(*graph.node_value(3), 1);
+
+    assert_directed_graph::<u32, u32>(graph);
+}
+
+#[test]
+fn directed_usize_graph_from_adjacency_graph_file() {
+    let path = [env!("CARGO_MANIFEST_DIR"), "resources", "test.adj"]
+        .iter()
+        .collect::<PathBuf>();
+
+    let graph = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .file_format(AdjacencyGraphInput::default())
+        .path(path)
+        .build()
+        .expect("loading failed");
+
+    assert_directed_graph::<usize, ()>(graph);
+}
+
+#[cfg(feature = "graphml")]
+#[test]
+fn directed_u32_graph_from_graphml_file() {
+    let path = [env!("CARGO_MANIFEST_DIR"), "resources", "test.graphml"]
+        .iter()
+        .collect::<PathBuf>();
+
+    let graph: DirectedCsrGraph<u32, u64, ()> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .file_format(GraphMlInput::<u32, u64>::default().node_value_key("value"))
+        .path(path)
+        .build()
+        .expect("loading failed");
+
+    assert_eq!(*graph.node_value(0), 0);
+    assert_eq!(*graph.node_value(3), 3);
+
+    assert_directed_graph::<u32, u64>(graph);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn directed_u32_graph_from_node_link_json_file() {
+    let path = [env!("CARGO_MANIFEST_DIR"), "resources", "test.json"]
+        .iter()
+        .collect::<PathBuf>();
+
+    let graph: DirectedCsrGraph<u32, u64, ()> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .file_format(NodeLinkJsonInput::<u32, u64>::default().node_value_key("value"))
+        .path(path)
+        .build()
+        .expect("loading failed");
+
+    assert_eq!(*graph.node_value(0), 0);
+    assert_eq!(*graph.node_value(3), 3);
+
+    assert_directed_graph::<u32, u64>(graph);
+}
+
 #[test]
 fn undirected_usize_graph_from_edge_list_file() {
     let path = [env!("CARGO_MANIFEST_DIR"), "resources", "test.el"]
@@ -420,6 +626,30 @@ fn directed_u64_graph_from_graph_500_file() {
     );
 }
 
+#[cfg(feature = "compression")]
+#[test]
+fn directed_u64_graph_from_bzip2_graph_500_file() {
+    let path = [
+        env!("CARGO_MANIFEST_DIR"),
+        "resources",
+        "scale_8.graph500.bz2",
+    ]
+    .iter()
+    .collect::<PathBuf>();
+
+    let graph: DirectedCsrGraph<u64> = GraphBuilder::new()
+        .csr_layout(CsrLayout::Sorted)
+        .file_format(Graph500Input::default())
+        .path(path)
+        .build()
+        .expect("loading failed");
+
+    assert_eq!(graph.node_count(), 256);
+    assert_eq!(graph.edge_count(), 4096);
+
+    assert_eq!(graph.out_neighbors(0).as_slice(), &[37, 157]);
+}
+
 #[test]
 fn undirected_u64_graph_from_graph_500_file() {
     let path = [env!("CARGO_MANIFEST_DIR"), "resources", "scale_8.graph500"]